@@ -709,6 +709,72 @@ namespace wrap {
     throw std::invalid_argument("unknown plugin type");
   }
 
+  /**
+   * Enumeration of the optional protocol features that a plugin can declare
+   * support for.
+   *
+   * This wraps `raw::dqcs_plugin_capability_t`, not including the `invalid`
+   * option (since we use exceptions to communicate failure).
+   */
+  enum class PluginCapability {
+
+    /**
+     * Unitary gates.
+     */
+    Unitary = 0,
+
+    /**
+     * Measurement gates.
+     */
+    Measurement = 1,
+
+    /**
+     * Prep gates.
+     */
+    Prep = 2,
+
+    /**
+     * Pauli-product measurement gates.
+     */
+    PauliMeasurement = 3,
+
+    /**
+     * Barriers.
+     */
+    Barrier = 4,
+
+    /**
+     * Custom gates.
+     */
+    Custom = 5,
+
+    /**
+     * Conditional gates.
+     */
+    Conditional = 6
+
+  };
+
+  /**
+   * Converts a `PluginCapability` to its raw C enum.
+   *
+   * \param capability The C++ plugin capability to convert.
+   * \returns The raw plugin capability.
+   */
+  inline raw::dqcs_plugin_capability_t to_raw(PluginCapability capability) noexcept {
+    switch (capability) {
+      case PluginCapability::Unitary:           return raw::dqcs_plugin_capability_t::DQCS_PCAP_UNITARY;
+      case PluginCapability::Measurement:       return raw::dqcs_plugin_capability_t::DQCS_PCAP_MEASUREMENT;
+      case PluginCapability::Prep:              return raw::dqcs_plugin_capability_t::DQCS_PCAP_PREP;
+      case PluginCapability::PauliMeasurement:  return raw::dqcs_plugin_capability_t::DQCS_PCAP_PAULI_MEASUREMENT;
+      case PluginCapability::Barrier:           return raw::dqcs_plugin_capability_t::DQCS_PCAP_BARRIER;
+      case PluginCapability::Custom:            return raw::dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM;
+      case PluginCapability::Conditional:       return raw::dqcs_plugin_capability_t::DQCS_PCAP_CONDITIONAL;
+    }
+    std::cerr << "unknown plugin capability" << std::endl;
+    std::terminate();
+  }
+
   /**
    * Enumeration of gate types supported by DQCsim.
    *
@@ -784,7 +850,37 @@ namespace wrap {
      *  - exactly one measurement result is reported upstream for exactly the
      *    qubits in the measures set.
      */
-    Custom = 4
+    Custom = 4,
+
+    /**
+     * Pauli-product measurement gates have one or more target qubits, each
+     * associated with a Pauli basis (X, Y, or Z). The first target is also
+     * the measured qubit.
+     *
+     * The semantics are:
+     *
+     *  - the joint observable formed by the tensor product of the Paulis is
+     *    measured;
+     *  - a single measurement result is propagated upstream for the first
+     *    target qubit, where zero represents the +1 (even parity) eigenvalue
+     *    and one represents the -1 (odd parity) eigenvalue.
+     */
+    PauliMeasurement = 5,
+
+    /**
+     * Barrier gates have zero or more target qubits and no matrix. They do
+     * not operate on the quantum state, but forbid gates from being moved
+     * across them by operators that reorder or schedule gates.
+     *
+     * The semantics are:
+     *
+     *  - no gate operating on any of the target qubits may be reordered
+     *    across the barrier;
+     *  - if there are no target qubits, the barrier applies to all qubits.
+     *
+     * Backends treat barriers as no-ops.
+     */
+    Barrier = 6
 
   };
 
@@ -796,10 +892,12 @@ namespace wrap {
    */
   inline raw::dqcs_gate_type_t to_raw(GateType type) noexcept {
     switch (type) {
-      case GateType::Unitary:     return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_UNITARY;
-      case GateType::Measurement: return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT;
-      case GateType::Prep:        return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP;
-      case GateType::Custom:      return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM;
+      case GateType::Unitary:          return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_UNITARY;
+      case GateType::Measurement:      return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT;
+      case GateType::Prep:             return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP;
+      case GateType::Custom:           return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM;
+      case GateType::PauliMeasurement: return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT;
+      case GateType::Barrier:          return raw::dqcs_gate_type_t::DQCS_GATE_TYPE_BARRIER;
     }
     std::cerr << "unknown gate type" << std::endl;
     std::terminate();
@@ -816,11 +914,13 @@ namespace wrap {
    */
   inline GateType check(raw::dqcs_gate_type_t type) {
     switch (type) {
-      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_UNITARY:     return GateType::Unitary;
-      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT: return GateType::Measurement;
-      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP:        return GateType::Prep;
-      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM:      return GateType::Custom;
-      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_INVALID:     throw std::runtime_error(raw::dqcs_error_get());
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_UNITARY:           return GateType::Unitary;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT:       return GateType::Measurement;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PREP:              return GateType::Prep;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM:            return GateType::Custom;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT: return GateType::PauliMeasurement;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_BARRIER:           return GateType::Barrier;
+      case raw::dqcs_gate_type_t::DQCS_GATE_TYPE_INVALID:           throw std::runtime_error(raw::dqcs_error_get());
     }
     throw std::invalid_argument("unknown gate type");
   }
@@ -1302,6 +1402,106 @@ namespace wrap {
      */
     SqSwap = 201,
 
+    /**
+     * The iSWAP gate matrix.
+     *
+     * \f[
+     * \textit{iSWAP} = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & 0 & i & 0 \\
+     * 0 & i & 0 & 0 \\
+     * 0 & 0 & 0 & 1
+     * \end{bmatrix}
+     * \f]
+     */
+    ISwap = 202,
+
+    /**
+     * The square-root of an iSWAP gate matrix.
+     *
+     * \f[
+     * \sqrt{\textit{iSWAP}} = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & \frac{1}{\sqrt{2}} & \frac{i}{\sqrt{2}} & 0 \\
+     * 0 & \frac{i}{\sqrt{2}} & \frac{1}{\sqrt{2}} & 0 \\
+     * 0 & 0 & 0 & 1
+     * \end{bmatrix}
+     * \f]
+     */
+    SqISwap = 203,
+
+    /**
+     * The controlled X (CNOT) gate matrix.
+     *
+     * \f[
+     * \textit{CNOT} = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & 1 & 0 & 0 \\
+     * 0 & 0 & 0 & 1 \\
+     * 0 & 0 & 1 & 0
+     * \end{bmatrix}
+     * \f]
+     *
+     * The first qubit is the control qubit. When used to construct a gate,
+     * the control qubit is placed in the control qubit list of the gate, and
+     * the matrix is reduced to the X matrix. When used to detect gates, the
+     * control qubit may be specified either way.
+     */
+    CNOT = 210,
+
+    /**
+     * The controlled Z gate matrix.
+     *
+     * \f[
+     * \textit{CZ} = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & 1 & 0 & 0 \\
+     * 0 & 0 & 1 & 0 \\
+     * 0 & 0 & 0 & -1
+     * \end{bmatrix}
+     * \f]
+     *
+     * The control qubit is handled the same way as for `CNOT`.
+     */
+    CZ = 211,
+
+    /**
+     * The matrix for a fermionic simulation (fSim) gate.
+     *
+     * \f[
+     * \textit{fSim}(\theta, \phi) = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & \cos{\theta} & -i\sin{\theta} & 0 \\
+     * 0 & -i\sin{\theta} & \cos{\theta} & 0 \\
+     * 0 & 0 & 0 & e^{-i\phi}
+     * \end{bmatrix}
+     * \f]
+     *
+     * θ and φ are specified or returned through the first two binary string
+     * arguments of the parameterization ArbData object. They are represented
+     * as little-endian double floating point values, specified in radians.
+     */
+    FSim = 250,
+
+    /**
+     * The matrix for a controlled phase gate with angle π/2^k.
+     *
+     * \f[
+     * \textit{CR}(k) = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 \\
+     * 0 & 1 & 0 & 0 \\
+     * 0 & 0 & 1 & 0 \\
+     * 0 & 0 & 0 & e^{i\pi / 2^k}
+     * \end{bmatrix}
+     * \f]
+     *
+     * k is specified or returned through the first binary string argument
+     * of the parameterization ArbData object. It is represented as a
+     * little-endian unsigned 64-bit integer. The control qubit is handled the
+     * same way as for `CNOT`.
+     */
+    CRk = 251,
+
     /**
      * Any two-qubit unitary gate, parameterized as a full unitary matrix.
      *
@@ -1312,6 +1512,27 @@ namespace wrap {
      */
     U2 = 290,
 
+    /**
+     * The Toffoli (doubly-controlled X) gate matrix.
+     *
+     * \f[
+     * \textit{Toffoli} = \begin{bmatrix}
+     * 1 & 0 & 0 & 0 & 0 & 0 & 0 & 0 \\
+     * 0 & 1 & 0 & 0 & 0 & 0 & 0 & 0 \\
+     * 0 & 0 & 1 & 0 & 0 & 0 & 0 & 0 \\
+     * 0 & 0 & 0 & 1 & 0 & 0 & 0 & 0 \\
+     * 0 & 0 & 0 & 0 & 1 & 0 & 0 & 0 \\
+     * 0 & 0 & 0 & 0 & 0 & 1 & 0 & 0 \\
+     * 0 & 0 & 0 & 0 & 0 & 0 & 0 & 1 \\
+     * 0 & 0 & 0 & 0 & 0 & 0 & 1 & 0
+     * \end{bmatrix}
+     * \f]
+     *
+     * The first two qubits are the control qubits. They are handled the same
+     * way as for `CNOT`.
+     */
+    Toffoli = 300,
+
     /**
      * Any three-qubit unitary gate, parameterized as a full unitary matrix.
      *
@@ -1359,7 +1580,14 @@ namespace wrap {
       case PredefinedGate::R:       return raw::dqcs_predefined_gate_t::DQCS_GATE_R;
       case PredefinedGate::Swap:    return raw::dqcs_predefined_gate_t::DQCS_GATE_SWAP;
       case PredefinedGate::SqSwap:  return raw::dqcs_predefined_gate_t::DQCS_GATE_SQRT_SWAP;
+      case PredefinedGate::ISwap:   return raw::dqcs_predefined_gate_t::DQCS_GATE_ISWAP;
+      case PredefinedGate::SqISwap: return raw::dqcs_predefined_gate_t::DQCS_GATE_SQRT_ISWAP;
+      case PredefinedGate::CNOT:    return raw::dqcs_predefined_gate_t::DQCS_GATE_CNOT;
+      case PredefinedGate::CZ:      return raw::dqcs_predefined_gate_t::DQCS_GATE_CZ;
+      case PredefinedGate::FSim:    return raw::dqcs_predefined_gate_t::DQCS_GATE_FSIM;
+      case PredefinedGate::CRk:     return raw::dqcs_predefined_gate_t::DQCS_GATE_CR_K;
       case PredefinedGate::U2:      return raw::dqcs_predefined_gate_t::DQCS_GATE_U2;
+      case PredefinedGate::Toffoli: return raw::dqcs_predefined_gate_t::DQCS_GATE_TOFFOLI;
       case PredefinedGate::U3:      return raw::dqcs_predefined_gate_t::DQCS_GATE_U3;
     }
     std::cerr << "unknown plugin type" << std::endl;
@@ -1404,7 +1632,14 @@ namespace wrap {
       case raw::dqcs_predefined_gate_t::DQCS_GATE_R:          return PredefinedGate::R;
       case raw::dqcs_predefined_gate_t::DQCS_GATE_SWAP:       return PredefinedGate::Swap;
       case raw::dqcs_predefined_gate_t::DQCS_GATE_SQRT_SWAP:  return PredefinedGate::SqSwap;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_ISWAP:      return PredefinedGate::ISwap;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_SQRT_ISWAP: return PredefinedGate::SqISwap;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_CNOT:       return PredefinedGate::CNOT;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_CZ:         return PredefinedGate::CZ;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_FSIM:       return PredefinedGate::FSim;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_CR_K:       return PredefinedGate::CRk;
       case raw::dqcs_predefined_gate_t::DQCS_GATE_U2:         return PredefinedGate::U2;
+      case raw::dqcs_predefined_gate_t::DQCS_GATE_TOFFOLI:    return PredefinedGate::Toffoli;
       case raw::dqcs_predefined_gate_t::DQCS_GATE_U3:         return PredefinedGate::U3;
       case raw::dqcs_predefined_gate_t::DQCS_GATE_INVALID:    throw std::runtime_error(raw::dqcs_error_get());
     }
@@ -2988,6 +3223,67 @@ namespace wrap {
    */
   using complex = std::complex<double>;
 
+  /**
+   * ZYZ Euler angles of a single-qubit unitary, as returned by
+   * `Matrix::decompose_zyz()`. The unitary equals
+   * `e^(i*phase) RZ(beta) RY(gamma) RZ(delta)`.
+   */
+  struct EulerAngles {
+
+    /**
+     * The global phase of the unitary.
+     */
+    double phase;
+
+    /**
+     * The angle of the last Z rotation.
+     */
+    double beta;
+
+    /**
+     * The angle of the Y rotation.
+     */
+    double gamma;
+
+    /**
+     * The angle of the first Z rotation.
+     */
+    double delta;
+
+  };
+
+  /**
+   * Parameters of the `PredefinedGate::R` gate equivalent to a single-qubit
+   * unitary, as returned by `Matrix::decompose_r()`. The unitary equals
+   * `e^(i*phase)` times the matrix of the R gate.
+   */
+  struct RGateParameters {
+
+    /**
+     * The global phase of the unitary.
+     */
+    double phase;
+
+    /**
+     * The theta parameter of the R gate.
+     */
+    double theta;
+
+    /**
+     * The phi parameter of the R gate.
+     */
+    double phi;
+
+    /**
+     * The lambda parameter of the R gate.
+     */
+    double lambda;
+
+  };
+
+  // Forward declaration for `Matrix::decompose_kak()`.
+  class Gate;
+
   /**
    * Represents a square matrix used for describing N-qubit gates.
    *
@@ -3069,6 +3365,8 @@ namespace wrap {
      * Matrix(PredefinedGate::Phase, ArbData.with_arg(theta));
      * Matrix(PredefinedGate::PhaseK, ArbData.with_arg(k));
      * Matrix(PredefinedGate::R, ArbData.with_arg(theta).with_arg(phi).with_arg(lambda));
+     * Matrix(PredefinedGate::FSim, ArbData.with_arg(theta).with_arg(phi));
+     * Matrix(PredefinedGate::CRk, ArbData.with_arg(k));
      * ```
      *
      * \param gate The gate to construct the matrix for.
@@ -3342,6 +3640,131 @@ namespace wrap {
       return result;
     }
 
+    /**
+     * Multiplies this matrix with another matrix.
+     *
+     * When both matrices represent gates, the product represents applying
+     * `other` first and then this matrix.
+     *
+     * \param other The right-hand side of the product. Its dimension must
+     * match the dimension of this matrix.
+     * \returns The product of this matrix and `other`.
+     * \throws std::runtime_error When either handle is invalid or the
+     * dimensions of the matrices differ.
+     */
+    Matrix multiply(const Matrix &other) const {
+      return Matrix(check(raw::dqcs_mat_multiply(handle, other.get_handle())));
+    }
+
+    /**
+     * Computes the tensor (Kronecker) product of this matrix and another
+     * matrix.
+     *
+     * The qubits of this matrix come before the qubits of `other` in the
+     * resulting matrix.
+     *
+     * \param other The right-hand side of the product.
+     * \returns The tensor product of this matrix and `other`.
+     * \throws std::runtime_error When either handle is invalid.
+     */
+    Matrix kron(const Matrix &other) const {
+      return Matrix(check(raw::dqcs_mat_kron(handle, other.get_handle())));
+    }
+
+    /**
+     * Computes the adjoint (conjugate transpose) of this matrix. For unitary
+     * matrices, this is the inverse.
+     *
+     * \returns The adjoint of this matrix.
+     * \throws std::runtime_error When the handle is invalid.
+     */
+    Matrix adjoint() const {
+      return Matrix(check(raw::dqcs_mat_adjoint(handle)));
+    }
+
+    /**
+     * Computes the trace of this matrix.
+     *
+     * \returns The sum of the diagonal elements of this matrix.
+     * \throws std::runtime_error When the handle is invalid.
+     */
+    complex trace() const {
+      double real, imag;
+      check(raw::dqcs_mat_trace(handle, &real, &imag));
+      return complex(real, imag);
+    }
+
+    /**
+     * Raises this matrix to a non-negative integer power.
+     *
+     * \param exponent The number of times the matrix is multiplied with
+     * itself. The zeroth power is the identity matrix.
+     * \returns The new matrix.
+     * \throws std::runtime_error When the handle is invalid.
+     */
+    Matrix pow(size_t exponent) const {
+      return Matrix(check(raw::dqcs_mat_pow(handle, exponent)));
+    }
+
+    /**
+     * Decomposes a single-qubit unitary into ZYZ Euler angles.
+     *
+     * \param epsilon The maximum difference between this matrix and a
+     * unitary matrix, and between this matrix and the decomposition, using
+     * the same metric as `approx_eq()`.
+     * \returns The Euler angles of the unitary.
+     * \throws std::runtime_error When the handle is invalid, the matrix is not
+     * a 2x2 unitary, or the decomposition fails.
+     */
+    EulerAngles decompose_zyz(double epsilon = 0.000001) const {
+      EulerAngles angles;
+      check(raw::dqcs_mat_decompose_zyz(
+        handle, epsilon, &angles.phase, &angles.beta, &angles.gamma, &angles.delta));
+      return angles;
+    }
+
+    /**
+     * Decomposes a single-qubit unitary into the parameters of an R gate.
+     *
+     * \param epsilon The maximum difference between this matrix and a
+     * unitary matrix, and between this matrix and the decomposition, using
+     * the same metric as `approx_eq()`.
+     * \returns The parameters of the R gate and the global phase.
+     * \throws std::runtime_error When the handle is invalid, the matrix is not
+     * a 2x2 unitary, or the decomposition fails.
+     */
+    RGateParameters decompose_r(double epsilon = 0.000001) const {
+      RGateParameters parameters;
+      check(raw::dqcs_mat_decompose_r(
+        handle, epsilon, &parameters.phase, &parameters.theta, &parameters.phi, &parameters.lambda));
+      return parameters;
+    }
+
+    /**
+     * Decomposes a two-qubit unitary into single-qubit R gates and at most
+     * three CNOTs, using the KAK decomposition.
+     *
+     * \param qa The qubit corresponding to the most significant bit of the
+     * matrix indices.
+     * \param qb The qubit corresponding to the least significant bit of the
+     * matrix indices.
+     * \param epsilon The maximum difference between this matrix and a
+     * unitary matrix, and between this matrix and the decomposition, using
+     * the same metric as `approx_eq()`.
+     * \param phase If not null, the global phase of the unitary with respect
+     * to the returned gates is written here.
+     * \returns The gates, in the order in which they are to be applied.
+     * \throws std::runtime_error When the handle is invalid, the qubits are
+     * invalid or equal, the matrix is not a 4x4 unitary, or the decomposition
+     * fails.
+     */
+    std::vector<Gate> decompose_kak(
+      const QubitRef &qa,
+      const QubitRef &qb,
+      double epsilon = 0.000001,
+      double *phase = nullptr
+    ) const;
+
   };
 
   /**
@@ -3669,6 +4092,85 @@ namespace wrap {
       return measure(QubitSet(measures), Matrix(basis));
     }
 
+    /**
+     * Constructs a new Pauli-product measurement gate.
+     *
+     * \param qubits A qubit reference set with the qubits that the Pauli
+     * string applies to. A single measurement result is reported for the
+     * first qubit in the set, where zero represents the +1 (even parity)
+     * eigenvalue and one represents the -1 (odd parity) eigenvalue.
+     * \param bases The Pauli string, consisting of one Pauli basis for each
+     * qubit in `qubits`.
+     * \returns The requested Pauli-product measurement gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate pauli_measure(QubitSet &&qubits, const std::vector<PauliBasis> &bases) {
+      if (bases.size() != qubits.size()) {
+        throw std::invalid_argument("number of bases does not match number of qubits");
+      }
+      std::vector<raw::dqcs_basis_t> raw_bases;
+      for (PauliBasis basis : bases) {
+        raw_bases.push_back(to_raw(basis));
+      }
+      return Gate(check(raw::dqcs_gate_new_pauli_measurement(qubits.get_handle(), raw_bases.data())));
+    }
+
+    /**
+     * Constructs a new Pauli-product measurement gate.
+     *
+     * \param qubits A qubit reference set with the qubits that the Pauli
+     * string applies to, passed by copy. A single measurement result is
+     * reported for the first qubit in the set, where zero represents the +1
+     * (even parity) eigenvalue and one represents the -1 (odd parity)
+     * eigenvalue.
+     * \param bases The Pauli string, consisting of one Pauli basis for each
+     * qubit in `qubits`.
+     * \returns The requested Pauli-product measurement gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate pauli_measure(const QubitSet &qubits, const std::vector<PauliBasis> &bases) {
+      return pauli_measure(QubitSet(qubits), bases);
+    }
+
+    /**
+     * Constructs a new barrier gate for all qubits.
+     *
+     * \returns The requested barrier gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate barrier() {
+      return Gate(check(raw::dqcs_gate_new_barrier(0)));
+    }
+
+    /**
+     * Constructs a new barrier gate for the given qubits.
+     *
+     * \param qubits A qubit reference set with the qubits that gates may not
+     * be moved across the barrier for.
+     * \returns The requested barrier gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate barrier(QubitSet &&qubits) {
+      return Gate(check(raw::dqcs_gate_new_barrier(qubits.get_handle())));
+    }
+
+    /**
+     * Constructs a new barrier gate for the given qubits.
+     *
+     * \param qubits A qubit reference set with the qubits that gates may not
+     * be moved across the barrier for, passed by copy.
+     * \returns The requested barrier gate.
+     * \throws std::runtime_error When construction of the new handle failed
+     * for some reason.
+     */
+    static Gate barrier(const QubitSet &qubits) {
+      return barrier(QubitSet(qubits));
+    }
+
     /**
      * Constructs a new Z-axis prep gate, putting the qubits in the |0> state.
      *
@@ -4217,6 +4719,75 @@ namespace wrap {
       return check(raw::dqcs_gate_has_matrix(handle));
     }
 
+    /**
+     * Returns whether this gate is annotated with a duration.
+     *
+     * \returns Whether this gate has a duration.
+     * \throws std::runtime_error When the current handle is invalid.
+     */
+    bool has_duration() const {
+      return check(raw::dqcs_gate_has_duration(handle));
+    }
+
+    /**
+     * Returns the duration of this gate in cycles.
+     *
+     * \returns The duration of this gate in cycles.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * gate doesn't have a duration.
+     */
+    Cycle get_duration() const {
+      return check(raw::dqcs_gate_duration(handle));
+    }
+
+    /**
+     * Annotates this gate with the given duration in cycles, replacing any
+     * existing duration.
+     *
+     * \param duration The duration in cycles. Cannot be negative.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * duration is negative.
+     */
+    void set_duration(Cycle duration) {
+      check(raw::dqcs_gate_set_duration(handle, duration));
+    }
+
+    /**
+     * Builder pattern version of `set_duration()`.
+     *
+     * \param duration The duration in cycles. Cannot be negative.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the current handle is invalid or the
+     * duration is negative.
+     */
+    Gate &&with_duration(Cycle duration) {
+      set_duration(duration);
+      return std::move(*this);
+    }
+
+    /**
+     * Removes the duration annotation from this gate, if any.
+     *
+     * \throws std::runtime_error When the current handle is invalid.
+     */
+    void clear_duration() {
+      check(raw::dqcs_gate_clear_duration(handle));
+    }
+
+    /**
+     * Returns the Pauli basis for the qubit at the given index of a
+     * Pauli-product measurement gate.
+     *
+     * \param index The index of the qubit within the target set.
+     * \returns The Pauli basis for the given qubit.
+     * \throws std::runtime_error When the current handle is invalid, the
+     * gate is not a Pauli-product measurement gate, or the index is out of
+     * range.
+     */
+    PauliBasis get_pauli_basis(size_t index) const {
+      return check(raw::dqcs_gate_pauli_basis(handle, index));
+    }
+
     /**
      * Returns the name of a custom gate.
      *
@@ -4249,6 +4820,22 @@ namespace wrap {
 
   };
 
+  inline std::vector<Gate> Matrix::decompose_kak(
+    const QubitRef &qa,
+    const QubitRef &qb,
+    double epsilon,
+    double *phase
+  ) const {
+    raw::dqcs_handle_t *handles = check(raw::dqcs_mat_decompose_kak(
+      handle, epsilon, qa.get_index(), qb.get_index(), phase));
+    std::vector<Gate> gates;
+    for (raw::dqcs_handle_t *gate = handles; *gate; gate++) {
+      gates.emplace_back(Gate(*gate));
+    }
+    std::free(handles);
+    return gates;
+  }
+
   /**
    * Class that you can inherit from to make your own custom gate converter for
    * use within DQCsim.
@@ -7424,6 +8011,33 @@ namespace wrap {
       return str;
     }
 
+    /**
+     * Declares that the plugin supports the given capability. Frontends
+     * declare the kinds of gates they send, operators and backends declare
+     * the kinds of gates they accept. No capabilities are declared by
+     * default, in which case DQCsim does not check whether the plugin is
+     * compatible with its neighbours.
+     *
+     * \param capability The capability to declare.
+     * \returns `&self`, to continue building.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    Plugin &&with_capability(PluginCapability capability) {
+      check(raw::dqcs_pdef_add_capability(handle, to_raw(capability)));
+      return std::move(*this);
+    }
+
+    /**
+     * Returns whether the described plugin declares the given capability.
+     *
+     * \param capability The capability to query.
+     * \returns Whether the capability is declared.
+     * \throws std::runtime_error When the plugin definition handle is invalid.
+     */
+    bool has_capability(PluginCapability capability) const {
+      return check(raw::dqcs_pdef_has_capability(handle, to_raw(capability)));
+    }
+
     // Code below is generated using the following Python script:
     // print('    // Code below is generated using the following Python script:')
     // with open(__file__, 'r') as f:
@@ -9525,6 +10139,38 @@ namespace wrap {
       return str;
     }
 
+    /**
+     * Queries whether a plugin, referenced by instance name, declared the
+     * given capability.
+     *
+     * \param name The instance name of the plugin to query.
+     * \param capability The capability to query.
+     * \returns Whether the capability is declared.
+     * \throws std::runtime_error When the given name does not identify a
+     * plugin, or when the simulation is in an invalid state.
+     */
+    bool has_capability(const std::string &name, PluginCapability capability) {
+      return check(raw::dqcs_sim_has_capability(handle, name.c_str(), to_raw(capability)));
+    }
+
+    /**
+     * Queries whether a plugin, referenced by index, declared the given
+     * capability.
+     *
+     * \param index The index of the plugin to query. The frontend always has
+     * index 0. 1 through N are used for the operators in front to back order
+     * (where N is the number of operators). The backend is at index N+1.
+     * Python-style negative indices are also supported. That is, -1 can be
+     * used to refer to the backend, -2 to the last operator, and so on.
+     * \param capability The capability to query.
+     * \returns Whether the capability is declared.
+     * \throws std::runtime_error When the given index is out of range, or when
+     * the simulation is in an invalid state.
+     */
+    bool has_capability(ssize_t index, PluginCapability capability) {
+      return check(raw::dqcs_sim_has_capability_idx(handle, index, to_raw(capability)));
+    }
+
     /**
      * Writes a reproduction file for the simulation so far.
      *
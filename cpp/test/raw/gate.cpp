@@ -255,6 +255,85 @@ TEST(gate, prep) {
   EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
 }
 
+// Check Pauli-product measurement gate.
+TEST(gate, pauli_measurement) {
+  char *s;
+
+  dqcs_handle_t qubits = dqcs_qbset_new();
+  ASSERT_NE(qubits, 0u) << "Unexpected error: " << dqcs_error_get();
+  EXPECT_EQ(dqcs_qbset_push(qubits, 1u), dqcs_return_t::DQCS_SUCCESS) << "Unexpected error: " << dqcs_error_get();
+  EXPECT_EQ(dqcs_qbset_push(qubits, 2u), dqcs_return_t::DQCS_SUCCESS) << "Unexpected error: " << dqcs_error_get();
+
+  const dqcs_basis_t bases[] = {dqcs_basis_t::DQCS_BASIS_X, dqcs_basis_t::DQCS_BASIS_Z};
+  dqcs_handle_t a = dqcs_gate_new_pauli_measurement(qubits, bases);
+  ASSERT_NE(a, 0u) << "Unexpected error: " << dqcs_error_get();
+
+  EXPECT_EQ(dqcs_gate_type(a), dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT);
+
+  EXPECT_EQ(dqcs_gate_has_name(a), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_STREQ(s = dqcs_gate_name(a), NULL);
+  if (s) free(s);
+
+  EXPECT_EQ(dqcs_gate_has_targets(a), dqcs_bool_return_t::DQCS_TRUE);
+  EXPECT_QBSET(dqcs_gate_targets(a), 1u, 2u);
+
+  EXPECT_EQ(dqcs_gate_has_controls(a), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_QBSET(dqcs_gate_controls(a), 0u);
+
+  EXPECT_EQ(dqcs_gate_has_measures(a), dqcs_bool_return_t::DQCS_TRUE);
+  EXPECT_QBSET(dqcs_gate_measures(a), 1u);
+
+  EXPECT_EQ(dqcs_gate_pauli_basis(a, 0), dqcs_basis_t::DQCS_BASIS_X);
+  EXPECT_EQ(dqcs_gate_pauli_basis(a, 1), dqcs_basis_t::DQCS_BASIS_Z);
+  EXPECT_EQ(dqcs_gate_pauli_basis(a, 2), dqcs_basis_t::DQCS_BASIS_INVALID);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: index out of range");
+
+  EXPECT_EQ(dqcs_handle_delete(a), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
+// Check barrier gate and duration annotations.
+TEST(gate, barrier) {
+  dqcs_handle_t qubits = dqcs_qbset_new();
+  ASSERT_NE(qubits, 0u) << "Unexpected error: " << dqcs_error_get();
+  EXPECT_EQ(dqcs_qbset_push(qubits, 1u), dqcs_return_t::DQCS_SUCCESS) << "Unexpected error: " << dqcs_error_get();
+
+  dqcs_handle_t a = dqcs_gate_new_barrier(qubits);
+  ASSERT_NE(a, 0u) << "Unexpected error: " << dqcs_error_get();
+
+  EXPECT_EQ(dqcs_gate_type(a), dqcs_gate_type_t::DQCS_GATE_TYPE_BARRIER);
+  EXPECT_QBSET(dqcs_gate_targets(a), 1u);
+  EXPECT_EQ(dqcs_gate_has_matrix(a), dqcs_bool_return_t::DQCS_FALSE);
+
+  EXPECT_EQ(dqcs_gate_has_duration(a), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_gate_duration(a), -1);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: gate does not have a duration");
+
+  EXPECT_EQ(dqcs_gate_set_duration(a, 3), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_gate_has_duration(a), dqcs_bool_return_t::DQCS_TRUE);
+  EXPECT_EQ(dqcs_gate_duration(a), 3);
+
+  EXPECT_EQ(dqcs_gate_set_duration(a, -1), dqcs_return_t::DQCS_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: duration cannot be negative");
+  EXPECT_EQ(dqcs_gate_duration(a), 3);
+
+  EXPECT_EQ(dqcs_gate_clear_duration(a), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_gate_has_duration(a), dqcs_bool_return_t::DQCS_FALSE);
+
+  EXPECT_EQ(dqcs_handle_delete(a), dqcs_return_t::DQCS_SUCCESS);
+
+  // A barrier without qubits applies to all qubits.
+  dqcs_handle_t b = dqcs_gate_new_barrier(0);
+  ASSERT_NE(b, 0u) << "Unexpected error: " << dqcs_error_get();
+  EXPECT_EQ(dqcs_gate_has_targets(b), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_handle_delete(b), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
 // Check NOP custom gate.
 TEST(gate, nop) {
   char *s;
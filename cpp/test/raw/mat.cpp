@@ -0,0 +1,168 @@
+#include <cmath>
+#include <dqcsim.h>
+#include "gtest/gtest.h"
+
+const double X_MATRIX[] = {
+  0.0, 0.0,   1.0, 0.0,
+  1.0, 0.0,   0.0, 0.0,
+};
+
+const double Y_MATRIX[] = {
+  0.0, 0.0,   0.0,-1.0,
+  0.0, 1.0,   0.0, 0.0,
+};
+
+const double IZ_MATRIX[] = {
+  0.0, 1.0,   0.0, 0.0,
+  0.0, 0.0,   0.0,-1.0,
+};
+
+const double S_MATRIX[] = {
+  1.0, 0.0,   0.0, 0.0,
+  0.0, 0.0,   0.0, 1.0,
+};
+
+const double SDAG_MATRIX[] = {
+  1.0, 0.0,   0.0, 0.0,
+  0.0, 0.0,   0.0,-1.0,
+};
+
+const double X_I_MATRIX[] = {
+  0.0, 0.0,   0.0, 0.0,   1.0, 0.0,   0.0, 0.0,
+  0.0, 0.0,   0.0, 0.0,   0.0, 0.0,   1.0, 0.0,
+  1.0, 0.0,   0.0, 0.0,   0.0, 0.0,   0.0, 0.0,
+  0.0, 0.0,   1.0, 0.0,   0.0, 0.0,   0.0, 0.0,
+};
+
+// Checks that the matrix referenced by the given handle approximately equals
+// the given C array, then deletes the handle.
+#define EXPECT_MAT_EQ(handle, num_qubits, expected)                           \
+  do {                                                                        \
+    dqcs_handle_t _actual = (handle);                                         \
+    ASSERT_NE(_actual, 0u) << "Unexpected error: " << dqcs_error_get();       \
+    dqcs_handle_t _expected = dqcs_mat_new(num_qubits, expected);             \
+    EXPECT_EQ(dqcs_mat_approx_eq(_actual, _expected, 0.0001, false),         \
+              dqcs_bool_return_t::DQCS_TRUE);                                 \
+    EXPECT_EQ(dqcs_handle_delete(_actual), dqcs_return_t::DQCS_SUCCESS);      \
+    EXPECT_EQ(dqcs_handle_delete(_expected), dqcs_return_t::DQCS_SUCCESS);    \
+  } while (0)
+
+// Check matrix multiplication.
+TEST(mat, multiply) {
+  dqcs_handle_t x = dqcs_mat_new(1, X_MATRIX);
+  dqcs_handle_t y = dqcs_mat_new(1, Y_MATRIX);
+  dqcs_handle_t xi = dqcs_mat_new(2, X_I_MATRIX);
+
+  // XY = iZ
+  EXPECT_MAT_EQ(dqcs_mat_multiply(x, y), 1, IZ_MATRIX);
+
+  EXPECT_EQ(dqcs_mat_multiply(x, xi), 0u);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: cannot multiply a 2x2 matrix with a 4x4 matrix");
+
+  EXPECT_EQ(dqcs_handle_delete(x), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_handle_delete(y), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_handle_delete(xi), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
+// Check the tensor product.
+TEST(mat, kron) {
+  dqcs_handle_t x = dqcs_mat_new(1, X_MATRIX);
+  dqcs_handle_t i = dqcs_mat_predef(dqcs_predefined_gate_t::DQCS_GATE_PAULI_I, 0);
+
+  dqcs_handle_t xi = dqcs_mat_kron(x, i);
+  EXPECT_EQ(dqcs_mat_num_qubits(xi), 2);
+  EXPECT_MAT_EQ(xi, 2, X_I_MATRIX);
+
+  EXPECT_EQ(dqcs_handle_delete(x), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_handle_delete(i), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
+// Check the adjoint, trace, and power functions.
+TEST(mat, adjoint_trace_pow) {
+  double re, im;
+  dqcs_handle_t s = dqcs_mat_new(1, S_MATRIX);
+
+  EXPECT_MAT_EQ(dqcs_mat_adjoint(s), 1, SDAG_MATRIX);
+  EXPECT_MAT_EQ(dqcs_mat_pow(s, 3), 1, SDAG_MATRIX);
+
+  EXPECT_EQ(dqcs_mat_trace(s, &re, &im), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_DOUBLE_EQ(re, 1.0);
+  EXPECT_DOUBLE_EQ(im, 1.0);
+
+  EXPECT_EQ(dqcs_mat_trace(s, NULL, &im), dqcs_return_t::DQCS_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: real and imag cannot be null");
+
+  dqcs_handle_t identity = dqcs_mat_pow(s, 0);
+  EXPECT_EQ(dqcs_mat_trace(identity, &re, &im), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_DOUBLE_EQ(re, 2.0);
+  EXPECT_DOUBLE_EQ(im, 0.0);
+
+  EXPECT_EQ(dqcs_handle_delete(s), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_handle_delete(identity), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
+// Check the single-qubit decompositions.
+TEST(mat, decompose_single) {
+  double phase, a, b, c;
+  dqcs_handle_t s = dqcs_mat_new(1, S_MATRIX);
+
+  // S = e^(iπ/4) RZ(π/2).
+  EXPECT_EQ(dqcs_mat_decompose_zyz(s, 1e-9, &phase, &a, &b, &c), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_NEAR(phase, M_PI / 4, 1e-9);
+  EXPECT_NEAR(a + c, M_PI / 2, 1e-9);
+  EXPECT_NEAR(b, 0.0, 1e-9);
+
+  // S = R(0, π/2, 0).
+  EXPECT_EQ(dqcs_mat_decompose_r(s, 1e-9, &phase, &a, &b, &c), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_NEAR(phase, 0.0, 1e-9);
+  EXPECT_NEAR(a, 0.0, 1e-9);
+  EXPECT_NEAR(b + c, M_PI / 2, 1e-9);
+
+  EXPECT_EQ(dqcs_mat_decompose_r(s, 1e-9, NULL, &a, &b, &c), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_mat_decompose_r(s, 1e-9, &phase, NULL, &b, &c), dqcs_return_t::DQCS_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: theta, phi, and lambda cannot be null");
+
+  dqcs_handle_t xi = dqcs_mat_new(2, X_I_MATRIX);
+  EXPECT_EQ(dqcs_mat_decompose_zyz(xi, 1e-9, &phase, &a, &b, &c), dqcs_return_t::DQCS_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: expected a 2x2 matrix, but got a 4x4 matrix");
+
+  EXPECT_EQ(dqcs_handle_delete(s), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_handle_delete(xi), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
+
+// Check the two-qubit decomposition.
+TEST(mat, decompose_kak) {
+  double phase = 1.0;
+  dqcs_handle_t xi = dqcs_mat_new(2, X_I_MATRIX);
+
+  dqcs_handle_t *gates = dqcs_mat_decompose_kak(xi, 1e-9, 1, 2, &phase);
+  ASSERT_NE(gates, nullptr) << "Unexpected error: " << dqcs_error_get();
+  size_t num_gates = 0;
+  for (dqcs_handle_t *gate = gates; *gate; gate++) {
+    EXPECT_EQ(dqcs_gate_type(*gate), dqcs_gate_type_t::DQCS_GATE_TYPE_UNITARY);
+    EXPECT_EQ(dqcs_handle_delete(*gate), dqcs_return_t::DQCS_SUCCESS);
+    num_gates++;
+  }
+  EXPECT_GT(num_gates, 0u);
+  free(gates);
+
+  EXPECT_EQ(dqcs_mat_decompose_kak(xi, 1e-9, 1, 1, &phase), nullptr);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: cannot use qubit 1 twice");
+
+  EXPECT_EQ(dqcs_handle_delete(xi), dqcs_return_t::DQCS_SUCCESS);
+
+  // Leak check.
+  EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
+}
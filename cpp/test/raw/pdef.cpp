@@ -72,6 +72,15 @@ TEST(pdef, metadata) {
   EXPECT_STREQ(s = dqcs_pdef_version(a), "c");
   if (s) free(s);
 
+  // Check capabilities.
+  EXPECT_EQ(dqcs_pdef_has_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_pdef_add_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_pdef_add_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM), dqcs_return_t::DQCS_SUCCESS);
+  EXPECT_EQ(dqcs_pdef_has_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM), dqcs_bool_return_t::DQCS_TRUE);
+  EXPECT_EQ(dqcs_pdef_has_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_UNITARY), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_pdef_add_capability(a, dqcs_plugin_capability_t::DQCS_PCAP_INVALID), dqcs_return_t::DQCS_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: invalid plugin capability");
+
   // Delete handle.
   EXPECT_EQ(dqcs_handle_delete(a), dqcs_return_t::DQCS_SUCCESS);
 
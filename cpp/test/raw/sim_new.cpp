@@ -143,6 +143,15 @@ TEST(sim_new, reordered) {
   ASSERT_STREQ(s = dqcs_sim_get_version_idx(a, -5), NULL); if (s) free(s);
   EXPECT_STREQ(dqcs_error_get(), "Invalid argument: index -5 out of range");
 
+  EXPECT_EQ(dqcs_sim_has_capability(a, "d", dqcs_plugin_capability_t::DQCS_PCAP_UNITARY), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_sim_has_capability(a, "x", dqcs_plugin_capability_t::DQCS_PCAP_UNITARY), dqcs_bool_return_t::DQCS_BOOL_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: plugin x not found");
+  EXPECT_EQ(dqcs_sim_has_capability_idx(a, 0, dqcs_plugin_capability_t::DQCS_PCAP_UNITARY), dqcs_bool_return_t::DQCS_FALSE);
+  EXPECT_EQ(dqcs_sim_has_capability_idx(a, 4, dqcs_plugin_capability_t::DQCS_PCAP_UNITARY), dqcs_bool_return_t::DQCS_BOOL_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: index 4 out of range");
+  EXPECT_EQ(dqcs_sim_has_capability_idx(a, 0, dqcs_plugin_capability_t::DQCS_PCAP_INVALID), dqcs_bool_return_t::DQCS_BOOL_FAILURE);
+  EXPECT_STREQ(dqcs_error_get(), "Invalid argument: invalid plugin capability");
+
   EXPECT_EQ(dqcs_handle_delete(a), dqcs_return_t::DQCS_SUCCESS);
 
   EXPECT_EQ(dqcs_handle_leak_check(), dqcs_return_t::DQCS_SUCCESS) << dqcs_error_get();
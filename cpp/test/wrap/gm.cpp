@@ -373,3 +373,55 @@ ArbData(\n\
   }
   dqcsim::raw::dqcs_handle_leak_check();
 }
+
+TEST(gatemap, intrinsic_controls) {{
+  MyUnboundGate dflt = MyUnboundGate("unknown");
+  auto map = GateMap<MyUnboundGate, MyBoundGate>()
+    .with_unitary(MyUnboundGate("cnot"), PredefinedGate::CNOT, 0)
+    .with_unitary(MyUnboundGate("toffoli"), PredefinedGate::Toffoli, 0)
+    .with_unitary(MyUnboundGate("crk"), PredefinedGate::CRk, 0);
+
+  const MyUnboundGate *key;
+  QubitSet qubits = QubitSet(0);
+  ArbData params = ArbData(0);
+
+  key = &dflt;
+  EXPECT_EQ(map.detect(
+    Gate::predefined(PredefinedGate::X, QubitSet().with(1_q).with(2_q)),
+    &key, &qubits, &params
+  ), true);
+  EXPECT_EQ(key->name, "cnot");
+  EXPECT_EQ(qubits.size(), 2);
+
+  key = &dflt;
+  EXPECT_EQ(map.detect(
+    Gate::unitary(QubitSet().with(1_q).with(2_q), Matrix(PredefinedGate::CNOT)),
+    &key, &qubits, &params
+  ), true);
+  EXPECT_EQ(key->name, "cnot");
+
+  key = &dflt;
+  EXPECT_EQ(map.detect(
+    Gate::predefined(PredefinedGate::CNOT, QubitSet().with(1_q).with(2_q).with(3_q)),
+    &key, &qubits, &params
+  ), true);
+  EXPECT_EQ(key->name, "toffoli");
+  EXPECT_EQ(qubits.size(), 3);
+
+  key = &dflt;
+  EXPECT_EQ(map.detect(
+    Gate::predefined(PredefinedGate::PhaseK, QubitSet().with(1_q).with(2_q), ArbData().with_arg<uint64_t>(3)),
+    &key, &qubits, &params
+  ), true);
+  EXPECT_EQ(key->name, "crk");
+  EXPECT_EQ(params.get_arb_arg_as<uint64_t>(0), 3u);
+
+  EXPECT_EQ(
+    map.construct(MyUnboundGate("cnot"), QubitSet().with(1_q).with(2_q)).dump(),
+    Gate::predefined(PredefinedGate::X, QubitSet().with(1_q).with(2_q)).dump()
+  );
+  EXPECT_ERROR(map.construct(MyUnboundGate("cnot"), QubitSet().with(1_q)), "Invalid argument: need at least 2 qubits");
+
+  }
+  dqcsim::raw::dqcs_handle_leak_check();
+}
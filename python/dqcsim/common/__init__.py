@@ -9,6 +9,7 @@ __all__ = [ #@
     'MeasurementSet',
     'QubitSet',
     'Loglevel',
+    'Capability',
 ]
 
 __pdoc__ = { #@
@@ -51,3 +52,15 @@ class Loglevel(IntEnum):
     FATAL = raw.DQCS_LOG_FATAL
     OFF = raw.DQCS_LOG_OFF
 
+class Capability(IntEnum):
+    """Enumeration of the optional protocol features that a plugin can
+    declare support for."""
+
+    UNITARY = raw.DQCS_PCAP_UNITARY
+    MEASUREMENT = raw.DQCS_PCAP_MEASUREMENT
+    PREP = raw.DQCS_PCAP_PREP
+    PAULI_MEASUREMENT = raw.DQCS_PCAP_PAULI_MEASUREMENT
+    BARRIER = raw.DQCS_PCAP_BARRIER
+    CUSTOM = raw.DQCS_PCAP_CUSTOM
+    CONDITIONAL = raw.DQCS_PCAP_CONDITIONAL
+
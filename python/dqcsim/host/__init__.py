@@ -775,6 +775,25 @@ class Simulator(object):
                     raw.dqcs_sim_get_author(sim, str(target)),
                     raw.dqcs_sim_get_version(sim, str(target)))
 
+    def get_capabilities(self, target):
+        """Returns the set of capabilities declared by one of the plugins in
+        the pipeline.
+
+        The `target` parameter works the same as the one in `arb()`. The
+        returned set contains `Capability` values. This function only works
+        while a simulation is running, since the plugins report their
+        capabilities when they are started.
+        """
+        if self._sim_handle is None:
+            raise RuntimeError("No simulation is currently running")
+        with self._sim_handle as sim:
+            if isinstance(target, int):
+                return {capability for capability in Capability
+                    if raw.dqcs_sim_has_capability_idx(sim, int(target), capability)}
+            else:
+                return {capability for capability in Capability
+                    if raw.dqcs_sim_has_capability(sim, str(target), capability)}
+
     def __len__(self):
         """Returns the number of plugins in the pipeline."""
         l = len(self._opers)
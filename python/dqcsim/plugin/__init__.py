@@ -412,6 +412,12 @@ class Plugin(object):
             raw.dqcs_pdef_set_initialize_cb_pyfun(pd, self._cbent('initialize'))
             raw.dqcs_pdef_set_drop_cb_pyfun(pd, self._cbent('drop'))
             raw.dqcs_pdef_set_host_arb_cb_pyfun(pd, self._cbent('host_arb'))
+            try:
+                capabilities = self._cb(None, 'get_capabilities')
+            except NotImplementedError:
+                capabilities = []
+            for capability in capabilities:
+                raw.dqcs_pdef_add_capability(pd, Capability(capability))
         return pdef
 
 class GateStreamSource(Plugin):
@@ -795,6 +801,21 @@ class GateStreamSource(Plugin):
                     with gate as gate:
                         self._pc(raw.dqcs_plugin_gate, gate)
 
+    def barrier(self, *qubits):
+        """Sends a barrier downstream, forbidding downstream operators from
+        moving gates that operate on the given qubits across it.
+
+        This function takes zero or more qubits as its positional arguments,
+        or an iterable of qubits as its first and only argument. If no qubits
+        are specified, the barrier applies to all qubits.
+        """
+        if len(qubits) == 1 and not isinstance(qubits[0], int):
+            qubits = list(qubits[0])
+        with QubitSet._to_raw(qubits) as qubits:
+            gate = Handle(raw.dqcs_gate_new_barrier(qubits))
+            with gate as gate_raw:
+                self._pc(raw.dqcs_plugin_gate, gate_raw)
+
     def get_measurement(self, qubit):
         """Returns the `Measurement` representing the latest measurement result
         for the given downstream qubit."""
@@ -864,6 +885,13 @@ class Frontend(GateStreamSource):
 
     The following functions MAY be implemented by the user:
 
+     - `get_capabilities() -> [Capability]`
+
+        May return the kinds of gates that this frontend sends. DQCsim refuses
+        to start a simulation if the downstream plugin does not accept all of
+        them. If this function is not implemented, no capabilities are
+        declared, and the check is skipped.
+
      - `handle_init(cmds: [ArbCmd]) -> None`
 
         Called by the simulator to initialize this plugin. The cmds parameter
@@ -950,6 +978,13 @@ class Operator(GateStreamSource):
 
     The following functions MAY be implemented by the user:
 
+     - `get_capabilities() -> [Capability]`
+
+        May return the kinds of gates that this plugin accepts. DQCsim refuses
+        to start a simulation if the upstream plugin declares a capability that
+        is not in this list. If this function is not implemented, no
+        capabilities are declared, and the check is skipped.
+
      - `handle_init(cmds: [ArbCmd]) -> None`
 
         Called by the simulator to initialize this plugin. The cmds parameter
@@ -1157,6 +1192,10 @@ class Operator(GateStreamSource):
             fast_forward = fast_forward and not hasattr(self, 'handle_prepare_gate')
         elif typ == raw.DQCS_GATE_TYPE_CUSTOM:
             fast_forward = not hasattr(self, 'handle_{}_gate'.format(name))
+        elif typ == raw.DQCS_GATE_TYPE_PAULI_MEASUREMENT:
+            fast_forward = True
+        elif typ == raw.DQCS_GATE_TYPE_BARRIER:
+            fast_forward = True
         if fast_forward:
             raw.dqcs_plugin_gate(state_handle, gate_handle)
             return MeasurementSet._to_raw([]).take()
@@ -1319,6 +1358,13 @@ class Backend(Plugin):
 
     The following functions MAY be implemented by the user:
 
+     - `get_capabilities() -> [Capability]`
+
+        May return the kinds of gates that this plugin accepts. DQCsim refuses
+        to start a simulation if the upstream plugin declares a capability that
+        is not in this list. If this function is not implemented, no
+        capabilities are declared, and the check is skipped.
+
      - `handle_init(cmds: [ArbCmd]) -> None`
 
         Called by the simulator to initialize this plugin. The cmds parameter
@@ -1474,6 +1520,11 @@ class Backend(Plugin):
                     targets, controls, measures, matrix, *data._args, **data._json)
             except NotImplementedError:
                 raise NotImplementedError("{} gate is not implemented by this plugin".format(name))
+        elif typ == raw.DQCS_GATE_TYPE_PAULI_MEASUREMENT:
+            raise NotImplementedError("Pauli-product measurement gates are not implemented by this plugin")
+        elif typ == raw.DQCS_GATE_TYPE_BARRIER:
+            # Barriers do not operate on the quantum state.
+            pass
         else:
             raise NotImplementedError("unknown gate type")
 
@@ -111,6 +111,47 @@ class Tests(unittest.TestCase):
         self.assertEqual(repr(sim), 'Simulator()')
         self.assertEqual(str(sim), 'Simulator()')
 
+    def test_capabilities(self):
+        class UnitaryFrontend(NullFrontend):
+            def get_capabilities(self):
+                return [Capability.UNITARY]
+
+        class CustomFrontend(NullFrontend):
+            def get_capabilities(self):
+                return [Capability.UNITARY, Capability.CUSTOM]
+
+        class UnitaryBackend(NullBackend):
+            def get_capabilities(self):
+                return [Capability.UNITARY, Capability.MEASUREMENT]
+
+        sim = Simulator(
+            UnitaryFrontend(), NullOperator(), UnitaryBackend(),
+            repro=None, stderr_verbosity=Loglevel.OFF
+        )
+        with self.assertRaisesRegex(RuntimeError, "No simulation is currently running"):
+            sim.get_capabilities(0)
+        sim.simulate()
+        self.assertEqual(sim.get_capabilities(0), {Capability.UNITARY})
+        self.assertEqual(sim.get_capabilities('op1'), set())
+        self.assertEqual(sim.get_capabilities(2), {Capability.UNITARY, Capability.MEASUREMENT})
+        with self.assertRaisesRegex(RuntimeError, "not found"):
+            sim.get_capabilities('banana')
+        sim.stop()
+
+        sim = Simulator(
+            UnitaryFrontend(), UnitaryBackend(),
+            repro=None, stderr_verbosity=Loglevel.OFF
+        )
+        sim.simulate()
+        sim.stop()
+
+        sim = Simulator(
+            CustomFrontend(), UnitaryBackend(),
+            repro=None, stderr_verbosity=Loglevel.OFF
+        )
+        with self.assertRaisesRegex(RuntimeError, "plugin back does not support custom gates, but upstream plugin front does"):
+            sim.simulate()
+
     def test_log_capture_callback(self):
         msgs = []
         capture = [False]
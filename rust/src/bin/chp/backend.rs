@@ -0,0 +1,386 @@
+//! Stabilizer simulator backend based on a CHP-style tableau. Only supports
+//! Clifford gates, but scales to hundreds or thousands of qubits.
+//!
+//! Incoming gates are classified using a `ConverterMap`. The following gates
+//! are recognized, ignoring global phase:
+//!
+//!  - the Pauli gates, H, S, S^dagger, and the 90-degree X and Y rotations;
+//!  - X, Y, and Z with a single control qubit;
+//!  - SWAP;
+//!  - measurement and prep gates in the X, Y, and Z bases.
+//!
+//! Any other gate results in a gatestream failure.
+
+mod tableau;
+
+use dqcsim::{
+    common::{
+        converter::{Converter, ConverterMap, MeasurementGateConverter, PrepGateConverter},
+        error::{inv_arg, Result},
+        gates::{UnboundUnitaryGate, UnitaryGateType},
+        types::{
+            ArbData, Basis, Gate, GateType, Matrix, PluginMetadata, PluginType,
+            QubitMeasurementResult, QubitRef,
+        },
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+};
+use tableau::Tableau;
+
+/// Maximum RMS deviation between incoming gate matrices and the recognized
+/// Clifford gates.
+const EPSILON: f64 = 1.0e-6;
+
+/// The gates supported by this backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+enum CliffordGate {
+    I,
+    X,
+    Y,
+    Z,
+    H,
+    S,
+    SDAG,
+    RX90,
+    RXM90,
+    RY90,
+    RYM90,
+    CX,
+    CY,
+    CZ,
+    SWAP,
+    Measure(BasisKey),
+    Prep(BasisKey),
+}
+
+/// Hashable version of `Basis`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BasisKey {
+    X,
+    Y,
+    Z,
+}
+
+impl From<BasisKey> for Basis {
+    fn from(basis: BasisKey) -> Basis {
+        match basis {
+            BasisKey::X => Basis::X,
+            BasisKey::Y => Basis::Y,
+            BasisKey::Z => Basis::Z,
+        }
+    }
+}
+
+type GateMap = ConverterMap<'static, CliffordGate, Gate, (Vec<QubitRef>, ArbData)>;
+
+/// Constructs the gate map used to classify incoming gates.
+fn gate_map() -> GateMap {
+    let mut map = GateMap::new(Some(Box::new(|gate: &Gate| gate.without_qubit_refs())));
+    for (key, typ) in vec![
+        (CliffordGate::I, UnitaryGateType::I),
+        (CliffordGate::X, UnitaryGateType::X),
+        (CliffordGate::Y, UnitaryGateType::Y),
+        (CliffordGate::Z, UnitaryGateType::Z),
+        (CliffordGate::H, UnitaryGateType::H),
+        (CliffordGate::S, UnitaryGateType::S),
+        (CliffordGate::SDAG, UnitaryGateType::SDAG),
+        (CliffordGate::RX90, UnitaryGateType::RX90),
+        (CliffordGate::RXM90, UnitaryGateType::RXM90),
+        (CliffordGate::RY90, UnitaryGateType::RY90),
+        (CliffordGate::RYM90, UnitaryGateType::RYM90),
+        (CliffordGate::SWAP, UnitaryGateType::SWAP),
+    ] {
+        map.push(key, typ.into_gate_converter(Some(0), EPSILON, true));
+    }
+    for (key, typ) in [
+        (CliffordGate::CX, UnitaryGateType::X),
+        (CliffordGate::CY, UnitaryGateType::Y),
+        (CliffordGate::CZ, UnitaryGateType::Z),
+    ] {
+        map.push(key, typ.into_gate_converter(Some(1), EPSILON, true));
+    }
+    for basis in [BasisKey::X, BasisKey::Y, BasisKey::Z] {
+        map.push(
+            CliffordGate::Measure(basis),
+            Box::new(MeasurementGateConverter::new(
+                None,
+                Basis::from(basis).into(),
+                EPSILON,
+            )),
+        );
+        map.push(
+            CliffordGate::Prep(basis),
+            Box::new(PrepGateConverter::new(
+                None,
+                Basis::from(basis).into(),
+                EPSILON,
+            )),
+        );
+    }
+    map
+}
+
+thread_local! {
+    /// The gate map is not `Send`, so it cannot be moved into the callback
+    /// closures. The callbacks are all called from the thread that runs the
+    /// plugin though, so we can store it in a thread-local instead.
+    static GATE_MAP: GateMap = gate_map();
+}
+
+/// The complete state of the backend.
+#[derive(Debug, Default)]
+struct Backend {
+    /// The stabilizer tableau.
+    tableau: Tableau,
+
+    /// Maps upstream qubit references to tableau indices.
+    qubits: HashMap<QubitRef, usize>,
+
+    /// Tableau indices of freed qubits, which are reset to |0> and reused for
+    /// subsequent allocations.
+    free_list: Vec<usize>,
+}
+
+impl Backend {
+    /// Returns the tableau index of the given qubit.
+    fn index(&self, qubit: &QubitRef) -> Result<usize> {
+        self.qubits
+            .get(qubit)
+            .cloned()
+            .map_or_else(|| inv_arg(format!("qubit {} is not allocated", qubit)), Ok)
+    }
+
+    /// Applies the matrix of the given basis, or its adjoint, up to global
+    /// phase.
+    fn basis_change(&mut self, basis: BasisKey, a: usize, adjoint: bool) {
+        match (basis, adjoint) {
+            (BasisKey::X, _) => self.tableau.h(a),
+            (BasisKey::Y, false) => {
+                self.tableau.h(a);
+                self.tableau.z(a);
+                self.tableau.s(a);
+                self.tableau.h(a);
+            }
+            (BasisKey::Y, true) => {
+                self.tableau.h(a);
+                self.tableau.s(a);
+                self.tableau.h(a);
+            }
+            (BasisKey::Z, _) => {}
+        }
+    }
+
+    /// Executes a classified gate, returning the measurement results.
+    fn execute(
+        &mut self,
+        key: CliffordGate,
+        qubits: &[QubitRef],
+        random: &mut dyn FnMut() -> bool,
+    ) -> Result<Vec<QubitMeasurementResult>> {
+        let indices = qubits
+            .iter()
+            .map(|q| self.index(q))
+            .collect::<Result<Vec<usize>>>()?;
+        let t = &mut self.tableau;
+        match key {
+            CliffordGate::I => {}
+            CliffordGate::X => t.x(indices[0]),
+            CliffordGate::Y => t.y(indices[0]),
+            CliffordGate::Z => t.z(indices[0]),
+            CliffordGate::H => t.h(indices[0]),
+            CliffordGate::S => t.s(indices[0]),
+            CliffordGate::SDAG => {
+                t.z(indices[0]);
+                t.s(indices[0]);
+            }
+            CliffordGate::RX90 => {
+                t.h(indices[0]);
+                t.s(indices[0]);
+                t.h(indices[0]);
+            }
+            CliffordGate::RXM90 => {
+                t.h(indices[0]);
+                t.z(indices[0]);
+                t.s(indices[0]);
+                t.h(indices[0]);
+            }
+            CliffordGate::RY90 => {
+                t.z(indices[0]);
+                t.h(indices[0]);
+            }
+            CliffordGate::RYM90 => {
+                t.h(indices[0]);
+                t.z(indices[0]);
+            }
+            CliffordGate::CX => t.cnot(indices[0], indices[1]),
+            CliffordGate::CY => {
+                t.z(indices[1]);
+                t.s(indices[1]);
+                t.cnot(indices[0], indices[1]);
+                t.s(indices[1]);
+            }
+            CliffordGate::CZ => {
+                t.h(indices[1]);
+                t.cnot(indices[0], indices[1]);
+                t.h(indices[1]);
+            }
+            CliffordGate::SWAP => {
+                t.cnot(indices[0], indices[1]);
+                t.cnot(indices[1], indices[0]);
+                t.cnot(indices[0], indices[1]);
+            }
+            CliffordGate::Measure(basis) => {
+                let mut results = vec![];
+                for (qubit, a) in qubits.iter().zip(indices) {
+                    self.basis_change(basis, a, true);
+                    let value = self.tableau.measure(a, random());
+                    self.basis_change(basis, a, false);
+                    debug!("Measured qubit {}: {}", qubit, value as u8);
+                    results.push(QubitMeasurementResult::new(
+                        *qubit,
+                        value,
+                        ArbData::default(),
+                    ));
+                }
+                return Ok(results);
+            }
+            CliffordGate::Prep(basis) => {
+                for a in indices {
+                    self.tableau.reset(a, random());
+                    self.basis_change(basis, a, false);
+                }
+            }
+        }
+        Ok(vec![])
+    }
+}
+
+/// Normalizes the control qubits of a unitary gate, such that controlled
+/// gates are detected regardless of whether their controls are specified
+/// through the control list or encoded in the matrix.
+///
+/// Controls are stripped from the matrix using `Gate::with_gate_controls()`.
+/// That cannot recognize CZ, because its matrix is diagonal, so it is
+/// special-cased.
+fn normalize(gate: Gate) -> Result<Gate> {
+    if gate.get_type() != &GateType::Unitary {
+        return Ok(gate);
+    }
+    let gate = gate.with_matrix_controls();
+    let cz = Matrix::from(UnboundUnitaryGate::Z).add_controls(1);
+    if gate.get_matrix().unwrap().approx_eq(&cz, EPSILON, true) {
+        let targets = gate.get_targets();
+        Gate::new_unitary(
+            vec![targets[1]],
+            vec![targets[0]],
+            Matrix::from(UnboundUnitaryGate::Z),
+        )
+    } else {
+        Ok(gate.with_gate_controls(EPSILON, true))
+    }
+}
+
+/// Returns a description of an unsupported gate for error messages.
+fn describe(gate: &Gate) -> String {
+    match gate.get_type() {
+        GateType::Custom(name) => format!("custom gate '{}'", name),
+        GateType::Unitary => format!(
+            "unitary gate with {} target(s), {} control(s), and matrix {}",
+            gate.get_targets().len(),
+            gate.get_controls().len(),
+            gate.get_matrix().unwrap()
+        ),
+        GateType::Measurement => {
+            format!("measurement gate with basis {}", gate.get_matrix().unwrap())
+        }
+        GateType::Prep => format!("prep gate with basis {}", gate.get_matrix().unwrap()),
+        GateType::PauliMeasurement(bases) => {
+            format!("Pauli-product measurement gate for {:?}", bases)
+        }
+        GateType::Barrier => "barrier".to_string(),
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("Stabilizer backend", "TU Delft QCE", "0.1.0"),
+    );
+
+    let backend = Arc::new(Mutex::new(Backend::default()));
+
+    definition.initialize = Box::new(|_state, arb_cmds| {
+        info!("Running stabilizer backend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        Ok(())
+    });
+
+    let be_allocate = Arc::clone(&backend);
+    definition.allocate = Box::new(move |_state, qubits, _arb_cmds| {
+        let mut be = be_allocate.lock().unwrap();
+        for qubit in qubits {
+            let index = match be.free_list.pop() {
+                Some(index) => index,
+                None => be.tableau.add_qubit(),
+            };
+            be.qubits.insert(qubit, index);
+        }
+        trace!("{} qubit(s) live after allocation", be.qubits.len());
+        Ok(())
+    });
+
+    let be_free = Arc::clone(&backend);
+    definition.free = Box::new(move |state, qubits| {
+        let mut be = be_free.lock().unwrap();
+        for qubit in qubits {
+            let index = be.index(&qubit)?;
+            be.tableau.reset(index, state.random_f64() < 0.5);
+            be.qubits.remove(&qubit);
+            be.free_list.push(index);
+        }
+        trace!("{} qubit(s) live after deallocation", be.qubits.len());
+        Ok(())
+    });
+
+    let be_gate = Arc::clone(&backend);
+    definition.gate = Box::new(move |state, gate| {
+        // Pauli-product measurements are executed as the equivalent sequence
+        // of Clifford gates and a single Z-basis measurement.
+        let gates = match gate.get_type() {
+            GateType::PauliMeasurement(_) => gate.decompose_pauli_measurement()?,
+            GateType::Barrier => vec![],
+            _ => vec![gate],
+        };
+        let mut measurements = vec![];
+        for gate in gates {
+            let gate = normalize(gate)?;
+            match GATE_MAP.with(|map| map.detect(&gate))? {
+                Some((key, (qubits, _))) => {
+                    trace!("Executing {:?} on {:?}", key, qubits);
+                    measurements.extend(be_gate.lock().unwrap().execute(
+                        key,
+                        &qubits,
+                        &mut || state.random_f64() < 0.5,
+                    )?);
+                }
+                None => inv_arg(format!(
+                    "the stabilizer backend only supports Clifford gates, but received a {}",
+                    describe(&gate)
+                ))?,
+            }
+        }
+        Ok(measurements)
+    });
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
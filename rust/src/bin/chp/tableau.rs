@@ -0,0 +1,259 @@
+//! Stabilizer tableau in the style of Aaronson and Gottesman's CHP simulator.
+//!
+//! See "Improved Simulation of Stabilizer Circuits", S. Aaronson and
+//! D. Gottesman, Phys. Rev. A 70, 052328 (2004).
+
+/// A single row of the tableau, representing a Pauli product with a sign.
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    x: Vec<bool>,
+    z: Vec<bool>,
+    r: bool,
+}
+
+impl Row {
+    /// Constructs the identity row for the given number of qubits.
+    fn identity(num_qubits: usize) -> Row {
+        Row {
+            x: vec![false; num_qubits],
+            z: vec![false; num_qubits],
+            r: false,
+        }
+    }
+}
+
+/// Stabilizer tableau of `n` qubits.
+///
+/// Rows `0..n` are the destabilizers, rows `n..2n` are the stabilizers, and
+/// row `2n` is scratch space for deterministic measurements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tableau {
+    rows: Vec<Row>,
+}
+
+impl Default for Tableau {
+    fn default() -> Tableau {
+        Tableau::new(0)
+    }
+}
+
+impl Tableau {
+    /// Constructs a tableau for the given number of qubits, all in |0>.
+    pub fn new(num_qubits: usize) -> Tableau {
+        let mut tableau = Tableau {
+            rows: vec![Row::identity(0)],
+        };
+        for _ in 0..num_qubits {
+            tableau.add_qubit();
+        }
+        tableau
+    }
+
+    /// Returns the number of qubits in the tableau.
+    pub fn num_qubits(&self) -> usize {
+        self.rows.len() / 2
+    }
+
+    /// Adds a qubit in the |0> state and returns its index.
+    pub fn add_qubit(&mut self) -> usize {
+        let n = self.num_qubits();
+        for row in self.rows.iter_mut() {
+            row.x.push(false);
+            row.z.push(false);
+        }
+        let mut destabilizer = Row::identity(n + 1);
+        destabilizer.x[n] = true;
+        let mut stabilizer = Row::identity(n + 1);
+        stabilizer.z[n] = true;
+        self.rows.insert(2 * n, stabilizer);
+        self.rows.insert(n, destabilizer);
+        n
+    }
+
+    /// Applies a Hadamard gate to qubit `a`.
+    pub fn h(&mut self, a: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.x[a] && row.z[a];
+            std::mem::swap(&mut row.x[a], &mut row.z[a]);
+        }
+    }
+
+    /// Applies a phase (S) gate to qubit `a`.
+    pub fn s(&mut self, a: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.x[a] && row.z[a];
+            row.z[a] ^= row.x[a];
+        }
+    }
+
+    /// Applies a controlled-NOT gate with control `a` and target `b`.
+    pub fn cnot(&mut self, a: usize, b: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.x[a] && row.z[b] && (row.x[b] == row.z[a]);
+            row.x[b] ^= row.x[a];
+            row.z[a] ^= row.z[b];
+        }
+    }
+
+    /// Applies a Pauli X gate to qubit `a`.
+    pub fn x(&mut self, a: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.z[a];
+        }
+    }
+
+    /// Applies a Pauli Y gate to qubit `a`.
+    pub fn y(&mut self, a: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.x[a] ^ row.z[a];
+        }
+    }
+
+    /// Applies a Pauli Z gate to qubit `a`.
+    pub fn z(&mut self, a: usize) {
+        for row in self.rows.iter_mut() {
+            row.r ^= row.x[a];
+        }
+    }
+
+    /// Returns the exponent to which i is raised when multiplying the Pauli
+    /// matrices represented by (x1, z1) and (x2, z2).
+    fn g(x1: bool, z1: bool, x2: bool, z2: bool) -> i32 {
+        match (x1, z1) {
+            (false, false) => 0,
+            (true, true) => z2 as i32 - x2 as i32,
+            (true, false) => z2 as i32 * (2 * x2 as i32 - 1),
+            (false, true) => x2 as i32 * (1 - 2 * z2 as i32),
+        }
+    }
+
+    /// Left-multiplies row `h` by row `i`.
+    fn rowsum(&mut self, h: usize, i: usize) {
+        let (source, target) = if h < i {
+            let (left, right) = self.rows.split_at_mut(i);
+            (&right[0], &mut left[h])
+        } else {
+            let (left, right) = self.rows.split_at_mut(h);
+            (&left[i], &mut right[0])
+        };
+        let mut sum = 2 * target.r as i32 + 2 * source.r as i32;
+        for j in 0..source.x.len() {
+            sum += Tableau::g(source.x[j], source.z[j], target.x[j], target.z[j]);
+            target.x[j] ^= source.x[j];
+            target.z[j] ^= source.z[j];
+        }
+        target.r = sum.rem_euclid(4) == 2;
+    }
+
+    /// Measures qubit `a` in the Z basis. `random` is used as the outcome if
+    /// the outcome is not determined by the state.
+    pub fn measure(&mut self, a: usize, random: bool) -> bool {
+        let n = self.num_qubits();
+        if let Some(p) = (n..2 * n).find(|p| self.rows[*p].x[a]) {
+            // Random outcome.
+            for i in 0..2 * n {
+                if i != p && self.rows[i].x[a] {
+                    self.rowsum(i, p);
+                }
+            }
+            self.rows[p - n] = self.rows[p].clone();
+            let mut row = Row::identity(n);
+            row.z[a] = true;
+            row.r = random;
+            self.rows[p] = row;
+            random
+        } else {
+            // Deterministic outcome.
+            self.rows[2 * n] = Row::identity(n);
+            for i in 0..n {
+                if self.rows[i].x[a] {
+                    self.rowsum(2 * n, i + n);
+                }
+            }
+            self.rows[2 * n].r
+        }
+    }
+
+    /// Resets qubit `a` to |0>.
+    pub fn reset(&mut self, a: usize, random: bool) {
+        if self.measure(a, random) {
+            self.x(a);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic() {
+        let mut t = Tableau::new(2);
+        assert!(!t.measure(0, true));
+        t.x(1);
+        assert!(t.measure(1, false));
+        t.h(0);
+        t.h(0);
+        assert!(!t.measure(0, true));
+    }
+
+    #[test]
+    fn bell_pair() {
+        for random in &[false, true] {
+            let mut t = Tableau::new(2);
+            t.h(0);
+            t.cnot(0, 1);
+            let value = t.measure(0, *random);
+            assert_eq!(value, *random);
+            assert_eq!(t.measure(1, !*random), value);
+        }
+    }
+
+    #[test]
+    fn phases() {
+        // H S S H = H Z H = X.
+        let mut t = Tableau::new(1);
+        t.h(0);
+        t.s(0);
+        t.s(0);
+        t.h(0);
+        assert!(t.measure(0, false));
+
+        // Y|0> = i|1>.
+        let mut t = Tableau::new(1);
+        t.y(0);
+        assert!(t.measure(0, false));
+
+        // Z|+> = |->, which gives 1 when measured in the X basis.
+        let mut t = Tableau::new(1);
+        t.h(0);
+        t.z(0);
+        t.h(0);
+        assert!(t.measure(0, false));
+    }
+
+    #[test]
+    fn add_qubit() {
+        let mut t = Tableau::new(1);
+        t.x(0);
+        assert_eq!(t.add_qubit(), 1);
+        t.cnot(0, 1);
+        assert!(t.measure(1, false));
+        t.reset(1, false);
+        assert!(!t.measure(1, true));
+        assert!(t.measure(0, false));
+    }
+
+    #[test]
+    fn ghz() {
+        let mut t = Tableau::new(50);
+        t.h(0);
+        for i in 1..50 {
+            t.cnot(i - 1, i);
+        }
+        let value = t.measure(25, true);
+        for i in 0..50 {
+            assert_eq!(t.measure(i, false), value);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Frontend that runs cQASM 1.0 programs.
+//!
+//! The path to the program is passed as the script argument of the plugin,
+//! such that a program with the `.cq` extension can be run using
+//! `dqcsim <file>.cq <backend>`. The qubits declared by the `qubits`
+//! statement are allocated before the program starts, and subcircuits are
+//! unrolled according to their iteration counts.
+//!
+//! Gates are sent downstream as the corresponding unitary gates. The
+//! instructions of a bundle are sent downstream in order; DQCsim has no
+//! notion of parallel gates, so only `wait` advances the simulation time.
+//! The `measure*` and `prep*` instructions are sent downstream as
+//! measurement and prep gates in the corresponding basis. Measurement
+//! results are stored in the classical bit of the measured qubit, which can
+//! be inverted using `not` and used to control gates, as in
+//! `c-x b[0], q[1]`. The `display` instructions are ignored.
+//!
+//! The run callback returns the final values of the classical bits as JSON
+//! data of the form `{"b": [<bit 0>, <bit 1>, ...]}`, where each bit is 0 or
+//! 1.
+
+mod interpreter;
+mod parser;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        types::{
+            ArbData, Cycles, Gate, PluginMetadata, PluginType, QubitMeasurementValue, QubitRef,
+        },
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+};
+use interpreter::{Downstream, Interpreter};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+impl Downstream for PluginState<'_> {
+    fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>> {
+        PluginState::allocate(self, num_qubits, vec![])
+    }
+
+    fn gate(&mut self, gate: Gate) -> Result<()> {
+        PluginState::gate(self, gate)
+    }
+
+    fn advance(&mut self, cycles: Cycles) -> Result<()> {
+        PluginState::advance(self, cycles).map(|_| ())
+    }
+
+    fn measurement(&mut self, qubit: QubitRef) -> Result<bool> {
+        match self.get_measurement(qubit)?.value {
+            QubitMeasurementValue::Zero => Ok(false),
+            QubitMeasurementValue::One => Ok(true),
+            QubitMeasurementValue::Undefined => inv_arg(format!(
+                "measurement result of qubit {} is undefined",
+                qubit
+            )),
+        }
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("cQASM frontend", "TU Delft QCE", "0.1.0"),
+    );
+
+    // The program is passed as a script file, preceding the simulator
+    // address.
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let simulator = args.pop().unwrap();
+    let path = args.pop();
+
+    let program = Arc::new(Mutex::new(None));
+
+    let program_initialize = Arc::clone(&program);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running cQASM frontend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        let path = match &path {
+            Some(path) => path,
+            None => return inv_arg("the cQASM frontend requires a program file"),
+        };
+        info!("Loading cQASM program {}", path);
+        program_initialize
+            .lock()
+            .unwrap()
+            .replace(parser::load(path)?);
+        Ok(())
+    });
+
+    let program_run = Arc::clone(&program);
+    definition.run = Box::new(move |state, _args| {
+        info!("Running cQASM frontend run callback");
+        let program = match program_run.lock().unwrap().take() {
+            Some(program) => program,
+            None => return inv_arg("the program has already been run"),
+        };
+        let mut interpreter = Interpreter::default();
+        interpreter.run(state, &program)?;
+        let bits: Vec<u8> = interpreter.bits(state)?.into_iter().map(u8::from).collect();
+        ArbData::from_json(serde_json::json!({ "b": bits }).to_string(), vec![])
+    });
+
+    PluginState::run(&definition, simulator).unwrap();
+}
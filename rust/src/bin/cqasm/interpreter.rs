@@ -0,0 +1,436 @@
+//! Interpreter for parsed cQASM 1.0 programs.
+
+use crate::parser::{Bundle, Instruction, Operand, Program};
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    gates::UnboundUnitaryGate,
+    types::{Basis, Cycles, Gate, Matrix, QubitRef},
+};
+use std::{collections::HashSet, f64::consts::PI};
+
+/// The operations the interpreter needs from the downstream plugin.
+pub trait Downstream {
+    /// Allocates the given number of qubits.
+    fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>>;
+
+    /// Sends a gate downstream.
+    fn gate(&mut self, gate: Gate) -> Result<()>;
+
+    /// Advances the simulation time.
+    fn advance(&mut self, cycles: Cycles) -> Result<()>;
+
+    /// Returns the result of the most recent measurement of the given qubit.
+    fn measurement(&mut self, qubit: QubitRef) -> Result<bool>;
+}
+
+/// The parameter of a gate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Parameter {
+    None,
+    Angle,
+    Integer,
+}
+
+/// A signature of a gate: the number of qubit operands, the number of those
+/// that are control qubits, the kind of parameter, and the target gate as a
+/// function of the parameter.
+type GateSignature = (
+    usize,
+    usize,
+    Parameter,
+    fn(f64) -> UnboundUnitaryGate<'static>,
+);
+
+/// Returns the signature of a cQASM gate.
+fn gate_signature(name: &str) -> Option<GateSignature> {
+    use Parameter::*;
+    use UnboundUnitaryGate::*;
+    let gate: GateSignature = match name {
+        "i" => (1, 0, None, |_| I),
+        "x" => (1, 0, None, |_| X),
+        "y" => (1, 0, None, |_| Y),
+        "z" => (1, 0, None, |_| Z),
+        "h" => (1, 0, None, |_| H),
+        "s" => (1, 0, None, |_| S),
+        "sdag" => (1, 0, None, |_| SDAG),
+        "t" => (1, 0, None, |_| T),
+        "tdag" => (1, 0, None, |_| TDAG),
+        "x90" => (1, 0, None, |_| RX90),
+        "mx90" => (1, 0, None, |_| RXM90),
+        "y90" => (1, 0, None, |_| RY90),
+        "my90" => (1, 0, None, |_| RYM90),
+        "rx" => (1, 0, Angle, RX),
+        "ry" => (1, 0, Angle, RY),
+        "rz" => (1, 0, Angle, RZ),
+        "cnot" | "cx" => (2, 1, None, |_| X),
+        "cz" => (2, 1, None, |_| Z),
+        "swap" => (2, 0, None, |_| SWAP),
+        "cr" => (2, 1, Angle, Phase),
+        "crk" => (2, 1, Integer, |k| Phase(2.0 * PI / 2f64.powf(k))),
+        "toffoli" => (3, 2, None, |_| X),
+        _ => return Option::None,
+    };
+    Some(gate)
+}
+
+/// Returns an error at the given location.
+fn error<T>(location: &str, message: impl AsRef<str>) -> Result<T> {
+    inv_arg(format!("{}: {}", location, message.as_ref()))
+}
+
+/// A classical bit.
+#[derive(Debug, Clone, Copy)]
+enum Bit {
+    /// A known value.
+    Value(bool),
+    /// The result of the most recent measurement of the corresponding qubit,
+    /// inverted if the flag is set, which has not been requested from
+    /// downstream yet.
+    Measured(bool),
+}
+
+/// Executes cQASM programs.
+///
+/// Each qubit has a corresponding classical bit, which holds the result of
+/// its most recent measurement. Measurement results are only requested from
+/// downstream when the value of a bit is needed, such that gates can be
+/// pipelined.
+#[derive(Debug, Default)]
+pub struct Interpreter {
+    /// The downstream qubits.
+    qubits: Vec<QubitRef>,
+    /// The classical bits.
+    bits: Vec<Bit>,
+}
+
+impl Interpreter {
+    /// Executes the given program.
+    pub fn run(&mut self, downstream: &mut impl Downstream, program: &Program) -> Result<()> {
+        self.qubits = downstream.allocate(program.num_qubits)?;
+        self.bits = vec![Bit::Value(false); program.num_qubits];
+        for bundle in program.bundles.iter() {
+            self.bundle(downstream, bundle)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the values of the classical bits.
+    pub fn bits(&mut self, downstream: &mut impl Downstream) -> Result<Vec<bool>> {
+        (0..self.bits.len())
+            .map(|index| self.bit(downstream, index))
+            .collect()
+    }
+
+    /// Returns the value of a classical bit.
+    fn bit(&mut self, downstream: &mut impl Downstream, index: usize) -> Result<bool> {
+        if let Bit::Measured(inverted) = self.bits[index] {
+            let value = downstream.measurement(self.qubits[index])? != inverted;
+            self.bits[index] = Bit::Value(value);
+        }
+        match self.bits[index] {
+            Bit::Value(value) => Ok(value),
+            Bit::Measured(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the downstream qubits for the given qubit indices.
+    fn qubit_refs(&self, location: &str, indices: &[usize]) -> Result<Vec<QubitRef>> {
+        indices
+            .iter()
+            .map(|&index| match self.qubits.get(index) {
+                Some(qubit) => Ok(*qubit),
+                None => error(location, format!("qubit index {} is out of range", index)),
+            })
+            .collect()
+    }
+
+    /// Executes a bundle.
+    fn bundle(&mut self, downstream: &mut impl Downstream, bundle: &Bundle) -> Result<()> {
+        let location = bundle.location.as_str();
+
+        // The instructions of a bundle are executed in parallel, so they
+        // cannot share qubits.
+        let mut used = HashSet::new();
+        for instruction in bundle.instructions.iter() {
+            let qubits: HashSet<usize> = instruction
+                .operands
+                .iter()
+                .filter_map(|operand| match operand {
+                    Operand::Qubits(indices) => Some(indices.iter().cloned()),
+                    _ => None,
+                })
+                .flatten()
+                .collect();
+            if let Some(index) = qubits.intersection(&used).min() {
+                return error(
+                    location,
+                    format!("qubit {} is used more than once in a bundle", index),
+                );
+            }
+            used.extend(qubits);
+        }
+
+        for instruction in bundle.instructions.iter() {
+            if let Some(condition) = &instruction.condition {
+                for &index in condition {
+                    if index >= self.bits.len() {
+                        return error(location, format!("bit index {} is out of range", index));
+                    }
+                }
+                let mut enabled = true;
+                for &index in condition {
+                    enabled &= self.bit(downstream, index)?;
+                }
+                if !enabled {
+                    continue;
+                }
+            }
+            self.instruction(downstream, location, instruction)?;
+        }
+        Ok(())
+    }
+
+    /// Executes an instruction.
+    fn instruction(
+        &mut self,
+        downstream: &mut impl Downstream,
+        location: &str,
+        instruction: &Instruction,
+    ) -> Result<()> {
+        let name = instruction.name.as_str();
+        let operands = instruction.operands.as_slice();
+        let basis = match name {
+            "measure" | "measure_z" | "prep" | "prep_z" => Some(Basis::Z),
+            "measure_x" | "prep_x" => Some(Basis::X),
+            "measure_y" | "prep_y" => Some(Basis::Y),
+            _ => None,
+        };
+
+        if instruction.condition.is_some() && gate_signature(name).is_none() {
+            return error(location, format!("{} cannot be binary-controlled", name));
+        }
+
+        match (name, operands) {
+            ("measure_all", []) => {
+                for bit in self.bits.iter_mut() {
+                    *bit = Bit::Measured(false);
+                }
+                downstream.gate(Gate::new_measurement(
+                    self.qubits.clone(),
+                    Matrix::from(Basis::Z),
+                )?)?;
+            }
+            (_, [Operand::Qubits(indices)]) if name.starts_with("measure") && basis.is_some() => {
+                let qubits = self.qubit_refs(location, indices)?;
+                for &index in indices {
+                    self.bits[index] = Bit::Measured(false);
+                }
+                downstream.gate(Gate::new_measurement(qubits, Matrix::from(basis.unwrap()))?)?;
+            }
+            (_, [Operand::Qubits(indices)]) if name.starts_with("prep") && basis.is_some() => {
+                let qubits = self.qubit_refs(location, indices)?;
+                downstream.gate(Gate::new_prep(qubits, Matrix::from(basis.unwrap()))?)?;
+            }
+            ("wait", [Operand::Number(cycles)]) if *cycles >= 0.0 && cycles.fract() == 0.0 => {
+                downstream.advance(*cycles as Cycles)?;
+            }
+            ("not", [Operand::Bits(indices)]) => {
+                for &index in indices {
+                    if index >= self.bits.len() {
+                        return error(location, format!("bit index {} is out of range", index));
+                    }
+                    self.bits[index] = match self.bits[index] {
+                        Bit::Value(value) => Bit::Value(!value),
+                        Bit::Measured(inverted) => Bit::Measured(!inverted),
+                    };
+                }
+            }
+            ("display", _) | ("display_binary", _) => {}
+            _ => match gate_signature(name) {
+                Some(signature) => self.gate(downstream, location, name, signature, operands)?,
+                None => {
+                    return error(
+                        location,
+                        format!("unknown instruction or invalid operands for {}", name),
+                    )
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Executes a gate. Qubit operands that select multiple qubits are
+    /// applied pairwise.
+    fn gate(
+        &mut self,
+        downstream: &mut impl Downstream,
+        location: &str,
+        name: &str,
+        signature: GateSignature,
+        operands: &[Operand],
+    ) -> Result<()> {
+        let (num_qubits, num_controls, parameter, gate) = signature;
+        let (qubits, parameter) =
+            match (parameter, operands.split_at(num_qubits.min(operands.len()))) {
+                (Parameter::None, (qubits, [])) => (qubits, 0.0),
+                (Parameter::Angle, (qubits, [Operand::Number(angle)])) => (qubits, *angle),
+                (Parameter::Integer, (qubits, [Operand::Number(k)])) if k.fract() == 0.0 => {
+                    (qubits, *k)
+                }
+                _ => return error(location, format!("invalid operands for {}", name)),
+            };
+        let qubits = qubits
+            .iter()
+            .map(|operand| match operand {
+                Operand::Qubits(indices) => self.qubit_refs(location, indices),
+                _ => error(location, format!("invalid operands for {}", name)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if qubits.len() != num_qubits {
+            return error(location, format!("invalid operands for {}", name));
+        }
+        let size = qubits[0].len();
+        if qubits.iter().any(|qubits| qubits.len() != size) {
+            return error(
+                location,
+                format!("qubit operands of {} differ in size", name),
+            );
+        }
+
+        let matrix = Matrix::from(gate(parameter));
+        for index in 0..size {
+            let qubits: Vec<QubitRef> = qubits.iter().map(|qubits| qubits[index]).collect();
+            let (controls, targets) = qubits.split_at(num_controls);
+            downstream.gate(Gate::new_unitary(
+                targets.to_vec(),
+                controls.to_vec(),
+                matrix.clone(),
+            )?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    /// Downstream that records the gates it receives, and measures all
+    /// qubits as one.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        gates: Vec<Gate>,
+        cycles: Cycles,
+        measurements: Vec<QubitRef>,
+    }
+
+    impl Downstream for Recorder {
+        fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>> {
+            Ok((1..=num_qubits)
+                .map(|qubit| QubitRef::from_foreign(qubit as u64).unwrap())
+                .collect())
+        }
+
+        fn gate(&mut self, gate: Gate) -> Result<()> {
+            self.gates.push(gate);
+            Ok(())
+        }
+
+        fn advance(&mut self, cycles: Cycles) -> Result<()> {
+            self.cycles += cycles;
+            Ok(())
+        }
+
+        fn measurement(&mut self, qubit: QubitRef) -> Result<bool> {
+            self.measurements.push(qubit);
+            Ok(true)
+        }
+    }
+
+    fn run(source: &str) -> Result<(Recorder, Vec<bool>)> {
+        let program = parse(source, "test.cq")?;
+        let mut recorder = Recorder::default();
+        let mut interpreter = Interpreter::default();
+        interpreter.run(&mut recorder, &program)?;
+        let bits = interpreter.bits(&mut recorder)?;
+        Ok((recorder, bits))
+    }
+
+    fn q(qubit: u64) -> QubitRef {
+        QubitRef::from_foreign(qubit).unwrap()
+    }
+
+    fn unitary(targets: Vec<u64>, controls: Vec<u64>, gate: UnboundUnitaryGate) -> Gate {
+        Gate::new_unitary(
+            targets.into_iter().map(q),
+            controls.into_iter().map(q),
+            Matrix::from(gate),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn program() {
+        let (recorder, bits) = run("version 1.0\n\
+             qubits 3\n\
+             { h q[0] | x q[1:2] }\n\
+             cnot q[0], q[1]\n\
+             crk q[1,2], q[0,1], 2\n\
+             wait 5\n\
+             measure_x q[0]\n\
+             prep_y q[1]\n\
+             not b[2]\n\
+             c-x b[0], q[1]\n\
+             c-x b[0,2], q[2]\n\
+             measure_all\n")
+        .unwrap();
+        assert_eq!(
+            recorder.gates,
+            vec![
+                unitary(vec![1], vec![], UnboundUnitaryGate::H),
+                unitary(vec![2], vec![], UnboundUnitaryGate::X),
+                unitary(vec![3], vec![], UnboundUnitaryGate::X),
+                unitary(vec![2], vec![1], UnboundUnitaryGate::X),
+                unitary(vec![1], vec![2], UnboundUnitaryGate::Phase(PI / 2.0)),
+                unitary(vec![2], vec![3], UnboundUnitaryGate::Phase(PI / 2.0)),
+                Gate::new_measurement(vec![q(1)], Matrix::from(Basis::X)).unwrap(),
+                Gate::new_prep(vec![q(2)], Matrix::from(Basis::Y)).unwrap(),
+                unitary(vec![2], vec![], UnboundUnitaryGate::X),
+                unitary(vec![3], vec![], UnboundUnitaryGate::X),
+                Gate::new_measurement(vec![q(1), q(2), q(3)], Matrix::from(Basis::Z)).unwrap(),
+            ]
+        );
+        assert_eq!(recorder.cycles, 5);
+        assert_eq!(recorder.measurements, vec![q(1), q(1), q(2), q(3)]);
+        assert_eq!(bits, vec![true, true, true]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            run("version 1.0\nqubits 2\n{ x q[0] | y q[0] }")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: qubit 0 is used more than once in a bundle"
+        );
+        assert_eq!(
+            run("version 1.0\nqubits 2\nx q[2]")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: qubit index 2 is out of range"
+        );
+        assert_eq!(
+            run("version 1.0\nqubits 3\ncnot q[0], q[1:2]")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: qubit operands of cnot differ in size"
+        );
+        assert_eq!(
+            run("version 1.0\nqubits 2\nrx q[0]")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: invalid operands for rx"
+        );
+    }
+}
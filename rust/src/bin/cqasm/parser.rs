@@ -0,0 +1,471 @@
+//! Lexer and parser for cQASM 1.0 programs.
+
+use dqcsim::common::error::{inv_arg, Result};
+use std::{fs, path::Path};
+
+/// A lexical token.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier or keyword, converted to lowercase, as cQASM is case
+    /// insensitive. Identifiers may contain dashes, as in `c-x`.
+    Ident(String),
+    /// A number literal, which may be negative.
+    Number(f64),
+    /// A symbol, such as `[` or `|`.
+    Symbol(char),
+}
+
+/// The symbols of the language.
+const SYMBOLS: &[char] = &['[', ']', ',', ':', '|', '{', '}', '(', ')', '.'];
+
+/// Splits a line into tokens.
+fn tokenize(line: &str) -> std::result::Result<Vec<Token>, String> {
+    let line = match line.find('#') {
+        Some(comment) => &line[..comment],
+        None => line,
+    };
+    let mut tokens = vec![];
+    let mut rest = line.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let length = if c.is_ascii_alphabetic() {
+            let mut length = 0;
+            let bytes = rest.as_bytes();
+            while length < bytes.len()
+                && (bytes[length].is_ascii_alphanumeric()
+                    || bytes[length] == b'_'
+                    || (bytes[length] == b'-'
+                        && matches!(bytes.get(length + 1), Some(b) if b.is_ascii_alphabetic())))
+            {
+                length += 1;
+            }
+            tokens.push(Token::Ident(rest[..length].to_ascii_lowercase()));
+            length
+        } else if c.is_ascii_digit()
+            || (c == '-' && rest[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.'))
+            || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            let mut length = 1 + rest[1..]
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or_else(|| rest.len() - 1);
+            if rest[length..].starts_with(['e', 'E']) {
+                let exponent = &rest[length + 1..];
+                let sign = exponent.starts_with(['+', '-']) as usize;
+                length += 1
+                    + sign
+                    + exponent[sign..]
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or_else(|| exponent.len() - sign);
+            }
+            match rest[..length].parse() {
+                Ok(value) => tokens.push(Token::Number(value)),
+                Err(_) => return Err(format!("invalid number {}", &rest[..length])),
+            }
+            length
+        } else if SYMBOLS.contains(&c) {
+            tokens.push(Token::Symbol(c));
+            1
+        } else {
+            return Err(format!("unexpected character '{}'", c));
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// An operand of an instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    /// A list of qubit indices, as in `q[0,2:4]`.
+    Qubits(Vec<usize>),
+    /// A list of classical bit indices, as in `b[1]`.
+    Bits(Vec<usize>),
+    /// A number, used for angles and cycle counts.
+    Number(f64),
+}
+
+/// An instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    /// The lowercase name of the instruction, without the `c-` prefix of
+    /// binary-controlled gates.
+    pub name: String,
+    /// The classical bits that control the instruction, if it is a
+    /// binary-controlled gate.
+    pub condition: Option<Vec<usize>>,
+    /// The operands of the instruction, excluding the condition.
+    pub operands: Vec<Operand>,
+}
+
+/// A bundle of instructions that are executed in parallel, along with its
+/// location for error reporting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bundle {
+    pub location: String,
+    pub instructions: Vec<Instruction>,
+}
+
+/// A parsed program, with its subcircuits unrolled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    /// The number of qubits, and therefore also the number of classical
+    /// bits.
+    pub num_qubits: usize,
+    /// The bundles of the program, in execution order.
+    pub bundles: Vec<Bundle>,
+}
+
+/// Parser over the tokens of a single line.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    /// Returns the current token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// Consumes the given symbol if it is the current token.
+    fn accept(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the given symbol, or returns an error.
+    fn expect(&mut self, symbol: char) -> std::result::Result<(), String> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", symbol))
+        }
+    }
+
+    /// Consumes an identifier.
+    fn ident(&mut self) -> std::result::Result<String, String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err("expected an identifier".to_string()),
+        }
+    }
+
+    /// Consumes a nonnegative integer.
+    fn int(&mut self) -> std::result::Result<usize, String> {
+        match self.peek() {
+            Some(Token::Number(value)) if *value >= 0.0 && value.fract() == 0.0 => {
+                let value = *value as usize;
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err("expected a nonnegative integer".to_string()),
+        }
+    }
+
+    /// Consumes the end of the line.
+    fn end(&self) -> std::result::Result<(), String> {
+        if self.position < self.tokens.len() {
+            Err("unexpected trailing tokens".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Parses a bracketed list of indices and ranges, as in `[0,2:4]`.
+    fn indices(&mut self) -> std::result::Result<Vec<usize>, String> {
+        self.expect('[')?;
+        let mut indices = vec![];
+        loop {
+            let first = self.int()?;
+            if self.accept(':') {
+                let last = self.int()?;
+                if last < first {
+                    return Err(format!("invalid range {}:{}", first, last));
+                }
+                indices.extend(first..=last);
+            } else {
+                indices.push(first);
+            }
+            if !self.accept(',') {
+                break;
+            }
+        }
+        self.expect(']')?;
+        Ok(indices)
+    }
+
+    /// Parses an operand.
+    fn operand(&mut self) -> std::result::Result<Operand, String> {
+        match self.peek() {
+            Some(Token::Number(value)) => {
+                let value = *value;
+                self.position += 1;
+                Ok(Operand::Number(value))
+            }
+            Some(Token::Ident(ident)) if ident == "q" => {
+                self.position += 1;
+                Ok(Operand::Qubits(self.indices()?))
+            }
+            Some(Token::Ident(ident)) if ident == "b" => {
+                self.position += 1;
+                Ok(Operand::Bits(self.indices()?))
+            }
+            _ => Err("expected a qubit, bit, or number operand".to_string()),
+        }
+    }
+
+    /// Parses an instruction.
+    fn instruction(&mut self) -> std::result::Result<Instruction, String> {
+        let name = self.ident()?;
+        let mut operands = vec![];
+        if !matches!(
+            self.peek(),
+            None | Some(Token::Symbol('|')) | Some(Token::Symbol('}'))
+        ) {
+            operands.push(self.operand()?);
+            while self.accept(',') {
+                operands.push(self.operand()?);
+            }
+        }
+        if let Some(name) = name.strip_prefix("c-") {
+            match operands.first() {
+                Some(Operand::Bits(bits)) => {
+                    let condition = Some(bits.clone());
+                    operands.remove(0);
+                    return Ok(Instruction {
+                        name: name.to_string(),
+                        condition,
+                        operands,
+                    });
+                }
+                _ => return Err("binary-controlled gates require bit operands".to_string()),
+            }
+        }
+        Ok(Instruction {
+            name,
+            condition: None,
+            operands,
+        })
+    }
+
+    /// Parses a bundle, optionally enclosed in braces.
+    fn bundle(&mut self) -> std::result::Result<Vec<Instruction>, String> {
+        let braced = self.accept('{');
+        let mut instructions = vec![self.instruction()?];
+        while self.accept('|') {
+            instructions.push(self.instruction()?);
+        }
+        if braced {
+            self.expect('}')?;
+        }
+        self.end()?;
+        Ok(instructions)
+    }
+}
+
+/// Parses the source of a program. The name of the source is used for error
+/// reporting.
+pub fn parse(source: &str, name: &str) -> Result<Program> {
+    let mut version = false;
+    let mut num_qubits = None;
+
+    // The bundles of each subcircuit, along with the number of iterations.
+    // Bundles preceding the first subcircuit header form an implicit
+    // subcircuit.
+    let mut subcircuits: Vec<(usize, Vec<Bundle>)> = vec![(1, vec![])];
+
+    for (index, line) in source.lines().enumerate() {
+        let location = format!("{}:{}", name, index + 1);
+        let result = tokenize(line).and_then(|tokens| {
+            let mut parser = Parser {
+                tokens,
+                position: 0,
+            };
+            match parser.peek() {
+                None => return Ok(()),
+                Some(Token::Ident(ident)) if ident == "version" => {
+                    parser.position += 1;
+                    match parser.peek() {
+                        Some(Token::Number(value)) if value.floor() == 1.0 => {
+                            parser.position += 1;
+                        }
+                        _ => return Err("only cQASM 1 is supported".to_string()),
+                    }
+                    if version {
+                        return Err("duplicate version statement".to_string());
+                    }
+                    version = true;
+                    return parser.end();
+                }
+                _ if !version => return Err("expected a version statement".to_string()),
+                Some(Token::Ident(ident)) if ident == "qubits" => {
+                    parser.position += 1;
+                    if num_qubits.is_some() {
+                        return Err("duplicate qubits statement".to_string());
+                    }
+                    num_qubits = Some(parser.int()?);
+                    return parser.end();
+                }
+                _ if num_qubits.is_none() => {
+                    return Err("expected a qubits statement".to_string());
+                }
+                Some(Token::Symbol('.')) => {
+                    parser.position += 1;
+                    parser.ident()?;
+                    let iterations = if parser.accept('(') {
+                        let iterations = parser.int()?;
+                        parser.expect(')')?;
+                        iterations
+                    } else {
+                        1
+                    };
+                    subcircuits.push((iterations, vec![]));
+                    return parser.end();
+                }
+                _ => {}
+            }
+            let instructions = parser.bundle()?;
+            subcircuits.last_mut().unwrap().1.push(Bundle {
+                location: location.clone(),
+                instructions,
+            });
+            Ok(())
+        });
+        if let Err(message) = result {
+            return inv_arg(format!("{}: {}", location, message));
+        }
+    }
+
+    let num_qubits = match num_qubits {
+        Some(num_qubits) => num_qubits,
+        None => return inv_arg(format!("{}: missing qubits statement", name)),
+    };
+    let mut bundles = vec![];
+    for (iterations, subcircuit) in subcircuits {
+        for _ in 0..iterations {
+            bundles.extend(subcircuit.iter().cloned());
+        }
+    }
+    Ok(Program {
+        num_qubits,
+        bundles,
+    })
+}
+
+/// Loads the program in the given file.
+pub fn load(path: impl AsRef<Path>) -> Result<Program> {
+    let path = path.as_ref();
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => return inv_arg(format!("failed to read {}: {}", path.display(), e)),
+    };
+    parse(&source, &path.to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction(
+        name: &str,
+        condition: Option<Vec<usize>>,
+        operands: Vec<Operand>,
+    ) -> Instruction {
+        Instruction {
+            name: name.to_string(),
+            condition,
+            operands,
+        }
+    }
+
+    #[test]
+    fn program() {
+        let program = parse(
+            "version 1.0\n\
+             # comment\n\
+             qubits 4\n\
+             H q[0]\n\
+             .loop(2)\n\
+             { x q[0:2,3] | rx q[1], -1.5e-1 }\n\
+             .end\n\
+             c-x b[0, 1], q[2]\n\
+             wait 3\n",
+            "test.cq",
+        )
+        .unwrap();
+        let bundle = Bundle {
+            location: "test.cq:6".to_string(),
+            instructions: vec![
+                instruction("x", None, vec![Operand::Qubits(vec![0, 1, 2, 3])]),
+                instruction(
+                    "rx",
+                    None,
+                    vec![Operand::Qubits(vec![1]), Operand::Number(-0.15)],
+                ),
+            ],
+        };
+        assert_eq!(
+            program,
+            Program {
+                num_qubits: 4,
+                bundles: vec![
+                    Bundle {
+                        location: "test.cq:4".to_string(),
+                        instructions: vec![instruction("h", None, vec![Operand::Qubits(vec![0])])],
+                    },
+                    bundle.clone(),
+                    bundle,
+                    Bundle {
+                        location: "test.cq:8".to_string(),
+                        instructions: vec![instruction(
+                            "x",
+                            Some(vec![0, 1]),
+                            vec![Operand::Qubits(vec![2])]
+                        )],
+                    },
+                    Bundle {
+                        location: "test.cq:9".to_string(),
+                        instructions: vec![instruction("wait", None, vec![Operand::Number(3.0)])],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("qubits 2", "test.cq").unwrap_err().to_string(),
+            "Invalid argument: test.cq:1: expected a version statement"
+        );
+        assert_eq!(
+            parse("version 2.0", "test.cq").unwrap_err().to_string(),
+            "Invalid argument: test.cq:1: only cQASM 1 is supported"
+        );
+        assert_eq!(
+            parse("version 1.0\nx q[0]", "test.cq")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:2: expected a qubits statement"
+        );
+        assert_eq!(
+            parse("version 1.0\nqubits 2\n{ x q[0] | y q[1]", "test.cq")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: expected '}'"
+        );
+        assert_eq!(
+            parse("version 1.0\nqubits 2\nc-x q[0], q[1]", "test.cq")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.cq:3: binary-controlled gates require bit operands"
+        );
+    }
+}
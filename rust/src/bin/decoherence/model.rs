@@ -0,0 +1,170 @@
+//! Decoherence parameters and the error channels they result in.
+
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    types::ArbData,
+};
+use serde::Deserialize;
+
+/// How decoherence is injected into the gatestream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Amplitude-damping and phase-damping channels, sent downstream as
+    /// `channel` custom gates.
+    Channel,
+    /// Stochastic Pauli errors following the Pauli twirling approximation of
+    /// the channels.
+    Pauli,
+}
+
+/// The JSON representation of the `decoherence.config` arb.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParameterSpecification {
+    t1: Option<f64>,
+    t2: Option<f64>,
+    cycle_time: Option<f64>,
+    mode: Option<Mode>,
+}
+
+/// The decoherence parameters of the qubits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parameters {
+    /// The energy relaxation time.
+    pub t1: f64,
+    /// The dephasing time.
+    pub t2: f64,
+    /// The duration of a cycle, in the same unit as T1 and T2.
+    pub cycle_time: f64,
+    /// How decoherence is injected into the gatestream.
+    pub mode: Mode,
+}
+
+impl Default for Parameters {
+    /// Returns parameters without decoherence.
+    fn default() -> Parameters {
+        Parameters {
+            t1: f64::INFINITY,
+            t2: f64::INFINITY,
+            cycle_time: 1.0,
+            mode: Mode::Pauli,
+        }
+    }
+}
+
+impl Parameters {
+    /// Constructs a set of parameters, verifying that the times are positive
+    /// and that T2 does not exceed 2 T1.
+    pub fn new(t1: f64, t2: f64, cycle_time: f64, mode: Mode) -> Result<Parameters> {
+        for (name, time) in &[("T1", t1), ("T2", t2), ("cycle time", cycle_time)] {
+            if time.is_nan() || *time <= 0.0 {
+                return inv_arg(format!("{} must be positive", name));
+            }
+        }
+        if t2 > 2.0 * t1 {
+            return inv_arg(format!("T2 ({}) cannot exceed 2 T1 ({})", t2, 2.0 * t1));
+        }
+        Ok(Parameters {
+            t1,
+            t2,
+            cycle_time,
+            mode,
+        })
+    }
+
+    /// Parses the JSON data of a `decoherence.config` arb. T1 defaults to
+    /// infinity, T2 defaults to 2 T1, the cycle time defaults to 1, and the
+    /// mode defaults to `pauli`.
+    pub fn from_arb(data: &ArbData) -> Result<Parameters> {
+        let spec: ParameterSpecification = serde_json::from_str(&data.get_json()?)?;
+        let t1 = spec.t1.unwrap_or(f64::INFINITY);
+        Parameters::new(
+            t1,
+            spec.t2.unwrap_or(2.0 * t1),
+            spec.cycle_time.unwrap_or(1.0),
+            spec.mode.unwrap_or(Mode::Pauli),
+        )
+    }
+
+    /// Returns the amplitude-damping probability and the phase-damping
+    /// parameter, as defined by the predefined channels of the
+    /// density-matrix backend, for a qubit that idles for the given number
+    /// of cycles.
+    pub fn damping(&self, cycles: u64) -> (f64, f64) {
+        let t = cycles as f64 * self.cycle_time;
+        let gamma = 1.0 - (-t / self.t1).exp();
+
+        // Amplitude damping already reduces the coherence by a factor
+        // exp(-t / 2 T1); the remainder up to exp(-t / T2) is pure dephasing.
+        let dephasing_rate = 1.0 / self.t2 - 1.0 / (2.0 * self.t1);
+        let lambda = 1.0 - (-2.0 * t * dephasing_rate).exp();
+        (gamma, lambda.max(0.0))
+    }
+
+    /// Returns the probabilities of an X, Y, and Z error for a qubit that
+    /// idles for the given number of cycles, following the Pauli twirling
+    /// approximation of amplitude and phase damping.
+    pub fn pauli(&self, cycles: u64) -> (f64, f64, f64) {
+        let t = cycles as f64 * self.cycle_time;
+        let px = (1.0 - (-t / self.t1).exp()) / 4.0;
+        let pz = (1.0 - (-t / self.t2).exp()) / 2.0 - px;
+        (px, px, pz.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn parameters() {
+        let json = |s: &str| ArbData::from_json(s, vec![]).unwrap();
+        assert_eq!(
+            Parameters::from_arb(&json("{}")).unwrap(),
+            Parameters::default()
+        );
+        let params = Parameters::from_arb(&json(r#"{"t1": 10, "mode": "channel"}"#)).unwrap();
+        assert_eq!(params.t2, 20.0);
+        assert_eq!(params.mode, Mode::Channel);
+        assert_eq!(
+            Parameters::from_arb(&json(r#"{"t1": 10, "t2": 30}"#))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: T2 (30) cannot exceed 2 T1 (20)"
+        );
+        assert_eq!(
+            Parameters::from_arb(&json(r#"{"cycle_time": 0}"#))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: cycle time must be positive"
+        );
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(Parameters::default().damping(100), (0.0, 0.0));
+        assert_eq!(Parameters::default().pauli(100), (0.0, 0.0, 0.0));
+
+        let params = Parameters::new(10.0, 5.0, 2.0, Mode::Pauli).unwrap();
+        let (gamma, lambda) = params.damping(5);
+        assert!(approx(gamma, 1.0 - (-1.0f64).exp()));
+
+        // The coherence decays by exp(-t / T2) in total.
+        let coherence = (1.0 - gamma).sqrt() * (1.0 - lambda).sqrt();
+        assert!(approx(coherence, (-2.0f64).exp()));
+
+        let (px, py, pz) = params.pauli(5);
+        assert!(approx(px, gamma / 4.0));
+        assert!(approx(py, gamma / 4.0));
+        assert!(approx(pz, (1.0 - (-2.0f64).exp()) / 2.0 - gamma / 4.0));
+
+        // Without pure dephasing, only amplitude damping remains.
+        let params = Parameters::new(10.0, 20.0, 1.0, Mode::Channel).unwrap();
+        assert!(approx(params.damping(7).1, 0.0));
+    }
+}
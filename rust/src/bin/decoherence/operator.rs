@@ -0,0 +1,193 @@
+//! Operator that models idle decoherence of the qubits based on their T1 and
+//! T2 times, such that the effects of simulation time can be simulated.
+//!
+//! The operator keeps track of the cycle at which each qubit was last used
+//! by a gate, advancing the current cycle whenever simulation time is
+//! advanced. Before a gate is passed downstream, the decoherence that each
+//! involved qubit accumulated since it was last used is injected as an
+//! amplitude-damping channel with probability 1 - exp(-t/T1), followed by a
+//! phase-damping channel accounting for the remaining dephasing up to
+//! exp(-t/T2), where t is the number of idle cycles times the cycle time.
+//! Because these channels compose, all idle cycles of a qubit are combined
+//! into a single pair of channels.
+//!
+//! The parameters are configured through the `decoherence.config` arb,
+//! either as an initialization command or as a host arb, with JSON data of
+//! the form `{"t1": <time>, "t2": <time>, "cycle_time": <time>, "mode":
+//! <mode>}`. All entries are optional; T1 defaults to infinity, T2 defaults
+//! to 2 T1, and the cycle time defaults to 1. The mode selects how the
+//! channels are injected:
+//!
+//!  - `channel`: as `channel` custom gates acting on the qubit, with JSON
+//!    data of the form `{"channel": "amplitude_damping", "p": <p>}` or
+//!    `{"channel": "phase_damping", "p": <p>}`. These are understood by the
+//!    density-matrix backend.
+//!  - `pauli` (default): as X, Y, or Z gates sampled according to the Pauli
+//!    twirling approximation of the channels, such that the decoherence can
+//!    be simulated using any backend.
+
+mod model;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        gates::UnboundUnitaryGate,
+        types::{ArbCmd, ArbData, Gate, Matrix, PluginMetadata, PluginType, QubitRef},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use model::{Mode, Parameters};
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// The complete state of the operator.
+#[derive(Debug, Default)]
+struct Operator {
+    /// The decoherence parameters.
+    params: Parameters,
+
+    /// The current cycle.
+    cycle: u64,
+
+    /// The cycle at which each live qubit was last used.
+    last_used: HashMap<QubitRef, u64>,
+}
+
+impl Operator {
+    /// Handles the `decoherence` arb interface.
+    fn arb(&mut self, cmd: &ArbCmd) -> Result<ArbData> {
+        if cmd.interface_identifier() != "decoherence" {
+            return Ok(ArbData::default());
+        }
+        match cmd.operation_identifier() {
+            "config" => {
+                self.params = Parameters::from_arb(cmd.data())?;
+                Ok(ArbData::default())
+            }
+            op => inv_arg(format!("unknown operation decoherence.{}", op)),
+        }
+    }
+
+    /// Returns the gates modeling the decoherence that the given qubit
+    /// accumulated since it was last used, and marks it as used.
+    fn decohere(&mut self, qubit: QubitRef, random: f64) -> Result<Vec<Gate>> {
+        let last_used = self
+            .last_used
+            .insert(qubit, self.cycle)
+            .unwrap_or(self.cycle);
+        let cycles = self.cycle - last_used;
+        if cycles == 0 {
+            return Ok(vec![]);
+        }
+        trace!("Qubit {} idled for {} cycle(s)", qubit, cycles);
+        match self.params.mode {
+            Mode::Channel => {
+                let (gamma, lambda) = self.params.damping(cycles);
+                [("amplitude_damping", gamma), ("phase_damping", lambda)]
+                    .iter()
+                    .filter(|(_, p)| *p > 0.0)
+                    .map(|(channel, p)| {
+                        let data = serde_json::json!({ "channel": channel, "p": p });
+                        Gate::new_custom(
+                            "channel",
+                            vec![qubit],
+                            vec![],
+                            vec![],
+                            None::<Matrix>,
+                            ArbData::from_json(data.to_string(), vec![])?,
+                        )
+                    })
+                    .collect()
+            }
+            Mode::Pauli => {
+                let (px, py, pz) = self.params.pauli(cycles);
+                let error = if random < px {
+                    UnboundUnitaryGate::X
+                } else if random < px + py {
+                    UnboundUnitaryGate::Y
+                } else if random < px + py + pz {
+                    UnboundUnitaryGate::Z
+                } else {
+                    return Ok(vec![]);
+                };
+                trace!("Inserting {:?} error on qubit {}", error, qubit);
+                Ok(vec![Gate::new_unitary(
+                    vec![qubit],
+                    vec![],
+                    Matrix::from(error),
+                )?])
+            }
+        }
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Decoherence operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let operator = Arc::new(Mutex::new(Operator::default()));
+
+    let op_initialize = Arc::clone(&operator);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running decoherence operator initialization callback");
+        let mut op = op_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            op.arb(&arb_cmd)?;
+        }
+        Ok(())
+    });
+
+    let op_allocate = Arc::clone(&operator);
+    definition.allocate = Box::new(move |state, qubits, arb_cmds| {
+        let mut op = op_allocate.lock().unwrap();
+        for qubit in &qubits {
+            let cycle = op.cycle;
+            op.last_used.insert(*qubit, cycle);
+        }
+        state.allocate(qubits.len(), arb_cmds).map(|_| ())
+    });
+
+    let op_free = Arc::clone(&operator);
+    definition.free = Box::new(move |state, qubits| {
+        let mut op = op_free.lock().unwrap();
+        for qubit in &qubits {
+            op.last_used.remove(qubit);
+        }
+        state.free(qubits)
+    });
+
+    let op_gate = Arc::clone(&operator);
+    definition.gate = Box::new(move |state, gate| {
+        let mut op = op_gate.lock().unwrap();
+        for qubit in gate
+            .get_targets()
+            .iter()
+            .chain(gate.get_controls())
+            .chain(gate.get_measures())
+        {
+            for error in op.decohere(*qubit, state.random_f64())? {
+                state.gate(error)?;
+            }
+        }
+        state.gate(gate).map(|_| vec![])
+    });
+
+    let op_advance = Arc::clone(&operator);
+    definition.advance = Box::new(move |state, cycles| {
+        op_advance.lock().unwrap().cycle += cycles;
+        state.advance(cycles).map(|_| ())
+    });
+
+    let op_host_arb = Arc::clone(&operator);
+    definition.host_arb = Box::new(move |_state, cmd| op_host_arb.lock().unwrap().arb(&cmd));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
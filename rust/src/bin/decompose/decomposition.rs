@@ -0,0 +1,311 @@
+//! Exact decompositions of unitary gates into arbitrary single-qubit gates
+//! and CNOTs.
+//!
+//! Single-qubit and two-qubit gates are decomposed using the ZYZ and KAK
+//! decompositions from `dqcsim::common::decomposition`. Controlled gates are
+//! expanded using the constructions from "Elementary gates for quantum
+//! computation", A. Barenco et al., Phys. Rev. A 52, 3457 (1995).
+
+use dqcsim::common::{
+    decomposition::{EulerAngles, TwoQubitDecomposition},
+    error::{inv_arg, Result},
+    gates::UnboundUnitaryGate,
+    types::{Gate, Matrix, QubitRef},
+};
+use num_complex::Complex64;
+
+/// Tolerance used for internal consistency checks.
+const TOLERANCE: f64 = 1.0e-9;
+
+/// A primitive operation of a decomposed gate.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Primitive {
+    /// An arbitrary single-qubit unitary.
+    Single(QubitRef, Matrix),
+
+    /// A CNOT gate with the given control and target qubit.
+    CNOT(QubitRef, QubitRef),
+}
+
+/// Returns a matrix multiplied by a scalar.
+fn scale(a: &Matrix, factor: Complex64) -> Matrix {
+    Matrix::new(a.clone().into_iter().map(|x| x * factor)).unwrap()
+}
+
+/// Returns a square root of a single-qubit unitary.
+fn sqrt(u: &Matrix) -> Matrix {
+    let trace = u.trace();
+    let det = u[(0, 0)] * u[(1, 1)] - u[(0, 1)] * u[(1, 0)];
+    let discriminant = (trace * trace - 4.0 * det).sqrt();
+    let l1 = (trace + discriminant) / 2.0;
+    let l2 = (trace - discriminant) / 2.0;
+    if (l1 - l2).norm() < TOLERANCE {
+        return scale(&Matrix::new_identity(2), l1.sqrt());
+    }
+    // Spectral decomposition using the projectors onto the eigenspaces.
+    let identity = Matrix::new_identity(2);
+    let p1 = Matrix::new((0..4).map(|i| (u[i] - l2 * identity[i]) / (l1 - l2))).unwrap();
+    let p2 = Matrix::new((0..4).map(|i| (u[i] - l1 * identity[i]) / (l2 - l1))).unwrap();
+    Matrix::new((0..4).map(|i| l1.sqrt() * p1[i] + l2.sqrt() * p2[i])).unwrap()
+}
+
+/// Decomposes a single-qubit unitary controlled by any number of qubits
+/// exactly, i.e. including the phase of the unitary.
+pub fn controlled(
+    controls: &[QubitRef],
+    target: QubitRef,
+    u: &Matrix,
+    epsilon: f64,
+) -> Result<Vec<Primitive>> {
+    let x = Matrix::from(UnboundUnitaryGate::X);
+    match controls {
+        [] => Ok(vec![Primitive::Single(target, u.clone())]),
+        [control] if u.approx_eq(&x, TOLERANCE, false) => {
+            Ok(vec![Primitive::CNOT(*control, target)])
+        }
+        [control] => {
+            // U = e^(iα) A X B X C, with A B C = I.
+            let EulerAngles {
+                phase,
+                beta,
+                gamma,
+                delta,
+            } = EulerAngles::zyz(u, epsilon)?;
+            let rz = |theta| Matrix::from(UnboundUnitaryGate::RZ(theta));
+            let ry = |theta| Matrix::from(UnboundUnitaryGate::RY(theta));
+            let a = rz(beta).multiply(&ry(gamma / 2.0))?;
+            let b = ry(-gamma / 2.0).multiply(&rz(-(delta + beta) / 2.0))?;
+            let c = rz((delta - beta) / 2.0);
+            Ok(vec![
+                Primitive::Single(target, c),
+                Primitive::CNOT(*control, target),
+                Primitive::Single(target, b),
+                Primitive::CNOT(*control, target),
+                Primitive::Single(target, a),
+                Primitive::Single(*control, UnboundUnitaryGate::Phase(phase).into()),
+            ])
+        }
+        _ => {
+            // Lemma 7.5 of Barenco et al., using V^2 = U.
+            let (last, rest) = controls.split_last().unwrap();
+            let v = sqrt(u);
+            let mut ops = controlled(&[*last], target, &v, epsilon)?;
+            ops.extend(controlled(rest, *last, &x, epsilon)?);
+            ops.extend(controlled(&[*last], target, &v.adjoint(), epsilon)?);
+            ops.extend(controlled(rest, *last, &x, epsilon)?);
+            ops.extend(controlled(rest, target, &v, epsilon)?);
+            Ok(ops)
+        }
+    }
+}
+
+/// Decomposes a two-qubit unitary acting on the given qubits into single
+/// qubit gates and at most three CNOTs. Returns the primitives and the
+/// global phase φ, such that U = e^(iφ) times the matrix of the primitives.
+pub fn two_qubit(
+    q0: QubitRef,
+    q1: QubitRef,
+    u: &Matrix,
+    epsilon: f64,
+) -> Result<(Vec<Primitive>, f64)> {
+    let qubits = [q0, q1];
+    let decomposition = TwoQubitDecomposition::kak(u, epsilon)?;
+    let ops = decomposition
+        .gates
+        .iter()
+        .map(|gate| match gate.control {
+            Some(control) => Primitive::CNOT(qubits[control], qubits[gate.target]),
+            None => Primitive::Single(qubits[gate.target], gate.gate.into()),
+        })
+        .collect();
+    Ok((ops, decomposition.phase))
+}
+
+/// Decomposes a unitary gate into primitives, up to global phase.
+///
+/// Control qubits encoded in the matrix are first moved to the control list.
+/// Gates with one or two target qubits and any number of control qubits are
+/// supported.
+pub fn decompose(gate: &Gate, epsilon: f64) -> Result<Vec<Primitive>> {
+    let gate = gate.with_gate_controls(epsilon, gate.get_controls().is_empty());
+    let controls = gate.get_controls();
+    let matrix = gate.get_matrix().unwrap();
+    match gate.get_targets() {
+        [target] => controlled(controls, *target, matrix, epsilon),
+        [t0, t1] => {
+            let (ops, phase) = two_qubit(*t0, *t1, matrix, epsilon)?;
+            if controls.is_empty() {
+                return Ok(ops);
+            }
+            let x = Matrix::from(UnboundUnitaryGate::X);
+            let mut result = vec![];
+            for op in ops {
+                match op {
+                    Primitive::Single(qubit, u) => {
+                        result.extend(controlled(controls, qubit, &u, epsilon)?)
+                    }
+                    Primitive::CNOT(control, target) => {
+                        let mut controls = controls.to_vec();
+                        controls.push(control);
+                        result.extend(controlled(&controls, target, &x, epsilon)?);
+                    }
+                }
+            }
+            // Controlling the global phase of the decomposition results in a
+            // phase gate on the control qubits.
+            let (last, rest) = controls.split_last().unwrap();
+            result.extend(controlled(
+                rest,
+                *last,
+                &UnboundUnitaryGate::Phase(phase).into(),
+                epsilon,
+            )?);
+            Ok(result)
+        }
+        targets => inv_arg(format!(
+            "cannot decompose unitary gates with {} target qubits; at most two are supported",
+            targets.len()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qref(q: u64) -> QubitRef {
+        QubitRef::from_foreign(q).unwrap()
+    }
+
+    /// Returns the matrix of a list of primitives acting on the given qubits,
+    /// with the first qubit corresponding to the most significant bit.
+    fn circuit_matrix(qubits: &[QubitRef], ops: &[Primitive]) -> Matrix {
+        let dimension = 1 << qubits.len();
+        let bit = |qubit: &QubitRef| {
+            1 << (qubits.len() - 1 - qubits.iter().position(|q| q == qubit).unwrap())
+        };
+        let mut result = Matrix::new_identity(dimension);
+        for op in ops {
+            let matrix = match op {
+                Primitive::Single(qubit, u) => {
+                    let bit = bit(qubit);
+                    Matrix::new((0..dimension * dimension).map(|i| {
+                        let (row, col) = (i / dimension, i % dimension);
+                        if row & !bit == col & !bit {
+                            u[((row & bit != 0) as usize, (col & bit != 0) as usize)]
+                        } else {
+                            Complex64::new(0.0, 0.0)
+                        }
+                    }))
+                    .unwrap()
+                }
+                Primitive::CNOT(control, target) => {
+                    let (control, target) = (bit(control), bit(target));
+                    Matrix::new((0..dimension * dimension).map(|i| {
+                        let (row, col) = (i / dimension, i % dimension);
+                        let image = if col & control != 0 {
+                            col ^ target
+                        } else {
+                            col
+                        };
+                        Complex64::new((row == image) as u8 as f64, 0.0)
+                    }))
+                    .unwrap()
+                }
+            };
+            result = matrix.multiply(&result).unwrap();
+        }
+        result
+    }
+
+    /// Returns a pseudo-random single-qubit unitary.
+    fn random_single(seed: f64) -> Matrix {
+        let (a, b, c, d) = (seed * 1.3, seed * 2.7 + 0.4, seed * 0.9 + 1.1, seed * 5.1);
+        EulerAngles {
+            phase: a,
+            beta: b,
+            gamma: c,
+            delta: d,
+        }
+        .matrix()
+    }
+
+    /// Returns a pseudo-random two-qubit unitary.
+    fn random_two(seed: f64) -> Matrix {
+        let q = [qref(1), qref(2)];
+        let mut ops = vec![];
+        for i in 0..4 {
+            let s = seed + i as f64;
+            ops.push(Primitive::Single(q[0], random_single(s)));
+            ops.push(Primitive::Single(q[1], random_single(s * 1.7)));
+            ops.push(Primitive::CNOT(q[i % 2], q[1 - i % 2]));
+        }
+        circuit_matrix(&q, &ops)
+    }
+
+    #[test]
+    fn square_root() {
+        for u in &[
+            random_single(0.7),
+            Matrix::from(UnboundUnitaryGate::X),
+            Matrix::from(UnboundUnitaryGate::I),
+            scale(
+                &Matrix::from(UnboundUnitaryGate::I),
+                Complex64::new(-1.0, 0.0),
+            ),
+        ] {
+            let v = sqrt(u);
+            assert!(v.multiply(&v).unwrap().approx_eq(u, TOLERANCE, false));
+        }
+    }
+
+    #[test]
+    fn controlled_exact() {
+        let q: Vec<QubitRef> = (1..=4).map(qref).collect();
+        let u = random_single(1.2);
+        for num_controls in 0..4 {
+            let controls = &q[..num_controls];
+            let target = q[num_controls];
+            let ops = controlled(controls, target, &u, 1.0e-9).unwrap();
+            let expected = u.add_controls(num_controls);
+            let actual = circuit_matrix(&q[..=num_controls], &ops);
+            assert!(actual.approx_eq(&expected, 1.0e-6, false));
+        }
+    }
+
+    #[test]
+    fn decompose_gates() {
+        let q: Vec<QubitRef> = (1..=4).map(qref).collect();
+
+        // Fredkin gate, with one of the controls encoded in the matrix.
+        let swap = Matrix::from(UnboundUnitaryGate::SWAP);
+        let gate =
+            Gate::new_unitary(vec![q[1], q[2], q[3]], vec![q[0]], swap.add_controls(1)).unwrap();
+        let ops = decompose(&gate, 1.0e-6).unwrap();
+        let actual = circuit_matrix(&q, &ops);
+        assert!(actual.approx_eq(&swap.add_controls(2), 1.0e-6, true));
+
+        // Controlled arbitrary two-qubit gate.
+        let u = random_two(0.8);
+        let gate = Gate::new_unitary(vec![q[1], q[2]], vec![q[0]], u.clone()).unwrap();
+        let ops = decompose(&gate, 1.0e-6).unwrap();
+        let actual = circuit_matrix(&q[..3], &ops);
+        assert!(actual.approx_eq(&u.add_controls(1), 1.0e-6, true));
+
+        // Three target qubits cannot be decomposed.
+        let gate = Gate::new_unitary(vec![q[0], q[1], q[2]], vec![], {
+            let mut m = Matrix::new_identity(8);
+            m[(0, 0)] = Complex64::new(0.0, 0.0);
+            m[(0, 1)] = Complex64::new(1.0, 0.0);
+            m[(1, 0)] = Complex64::new(0.0, 1.0);
+            m[(1, 1)] = Complex64::new(0.0, 0.0);
+            m
+        })
+        .unwrap();
+        assert_eq!(
+            decompose(&gate, 1.0e-6).unwrap_err().to_string(),
+            "Invalid argument: cannot decompose unitary gates with 3 target qubits; at most two are supported"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! The native gate set targeted by the decomposition operator.
+
+use crate::decomposition::Primitive;
+use dqcsim::common::{
+    converter::ConverterMap,
+    decomposition::EulerAngles,
+    error::{inv_arg, Result},
+    gates::{UnboundUnitaryGate, UnitaryGateType},
+    types::{ArbData, Gate, Matrix, QubitRef},
+};
+use serde::Deserialize;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// The gates that can be part of the native gate set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum NativeGate {
+    /// Rotation around the X axis.
+    RX,
+    /// Rotation around the Y axis.
+    RY,
+    /// Rotation around the Z axis.
+    RZ,
+    /// Arbitrary single-qubit rotation, parameterized by three Euler angles.
+    R,
+    /// Any single-qubit unitary.
+    U,
+    /// Controlled X.
+    CNOT,
+    /// Controlled Z.
+    CZ,
+}
+
+impl NativeGate {
+    /// Parses a gate name as used in the `decompose.gate_set` arb.
+    fn from_name(name: &str) -> Result<NativeGate> {
+        match name.to_lowercase().as_str() {
+            "rx" => Ok(NativeGate::RX),
+            "ry" => Ok(NativeGate::RY),
+            "rz" => Ok(NativeGate::RZ),
+            "r" => Ok(NativeGate::R),
+            "u" => Ok(NativeGate::U),
+            "cnot" | "cx" => Ok(NativeGate::CNOT),
+            "cz" => Ok(NativeGate::CZ),
+            _ => inv_arg(format!("unknown native gate '{}'", name)),
+        }
+    }
+}
+
+/// Converter map used to detect gates that are already native.
+pub type NativeMap = ConverterMap<'static, NativeGate, Gate, (Vec<QubitRef>, ArbData)>;
+
+/// The JSON representation of the `decompose.gate_set` arb.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GateSetSpecification {
+    gates: Vec<String>,
+}
+
+/// A native gate set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateSet {
+    gates: Vec<NativeGate>,
+}
+
+impl Default for GateSet {
+    /// Returns the default gate set, consisting of RX, RY, RZ, and CZ.
+    fn default() -> GateSet {
+        GateSet {
+            gates: vec![
+                NativeGate::RX,
+                NativeGate::RY,
+                NativeGate::RZ,
+                NativeGate::CZ,
+            ],
+        }
+    }
+}
+
+impl GateSet {
+    /// Constructs a gate set, verifying that it can express any single-qubit
+    /// gate.
+    pub fn new(gates: Vec<NativeGate>) -> Result<GateSet> {
+        let set = GateSet { gates };
+        let rotations = [NativeGate::RX, NativeGate::RY, NativeGate::RZ]
+            .iter()
+            .filter(|g| set.contains(**g))
+            .count();
+        if rotations < 2 && !set.contains(NativeGate::R) && !set.contains(NativeGate::U) {
+            return inv_arg(
+                "the native gate set must contain two of rx, ry, and rz, or either r or u",
+            );
+        }
+        Ok(set)
+    }
+
+    /// Constructs a gate set from the JSON data of a `decompose.gate_set`
+    /// arb.
+    pub fn from_arb(data: &ArbData) -> Result<GateSet> {
+        let spec: GateSetSpecification = serde_json::from_str(&data.get_json()?)?;
+        GateSet::new(
+            spec.gates
+                .iter()
+                .map(|name| NativeGate::from_name(name))
+                .collect::<Result<Vec<_>>>()?,
+        )
+    }
+
+    /// Returns whether the gate set contains the given gate.
+    pub fn contains(&self, gate: NativeGate) -> bool {
+        self.gates.contains(&gate)
+    }
+
+    /// Constructs the converter map that detects the native gates.
+    pub fn converter_map(&self, epsilon: f64) -> NativeMap {
+        let mut map = NativeMap::new(Some(Box::new(|gate: &Gate| gate.without_qubit_refs())));
+        for gate in &self.gates {
+            let (typ, num_controls) = match gate {
+                NativeGate::RX => (UnitaryGateType::RX, 0),
+                NativeGate::RY => (UnitaryGateType::RY, 0),
+                NativeGate::RZ => (UnitaryGateType::RZ, 0),
+                NativeGate::R => (UnitaryGateType::R, 0),
+                NativeGate::U => (UnitaryGateType::U(1), 0),
+                NativeGate::CNOT => (UnitaryGateType::X, 1),
+                NativeGate::CZ => (UnitaryGateType::Z, 1),
+            };
+            map.push(
+                *gate,
+                typ.into_gate_converter(Some(num_controls), epsilon, true),
+            );
+        }
+        map
+    }
+
+    /// Converts a single-qubit unitary to native gates, up to global phase.
+    fn lower_single(&self, qubit: QubitRef, u: &Matrix, epsilon: f64) -> Result<Vec<Gate>> {
+        if u.approx_eq(&Matrix::new_identity(2), epsilon, true) {
+            return Ok(vec![]);
+        }
+        let gate = |g: UnboundUnitaryGate| Gate::new_unitary(vec![qubit], vec![], Matrix::from(g));
+        if self.contains(NativeGate::U) {
+            return Ok(vec![gate(UnboundUnitaryGate::U(u))?]);
+        }
+        if self.contains(NativeGate::R) {
+            let (r, _) = EulerAngles::zyz(u, epsilon)?.to_r();
+            return Ok(vec![gate(r)?]);
+        }
+
+        // Rotations by multiples of 2π only affect global phase, so they
+        // can be dropped.
+        let rotation = |angle: f64, g: fn(f64) -> UnboundUnitaryGate<'static>| {
+            let angle = angle - 2.0 * PI * (angle / (2.0 * PI)).round();
+            if angle.abs() < epsilon {
+                None
+            } else {
+                Some(gate(g(angle)))
+            }
+        };
+        let sequence = if self.contains(NativeGate::RZ) {
+            let EulerAngles {
+                beta, gamma, delta, ..
+            } = EulerAngles::zyz(u, epsilon)?;
+            if gamma.abs() < epsilon {
+                vec![rotation(beta + delta, UnboundUnitaryGate::RZ)]
+            } else if self.contains(NativeGate::RY) {
+                vec![
+                    rotation(delta, UnboundUnitaryGate::RZ),
+                    rotation(gamma, UnboundUnitaryGate::RY),
+                    rotation(beta, UnboundUnitaryGate::RZ),
+                ]
+            } else {
+                // RY(γ) = RZ(π/2) RX(γ) RZ(-π/2).
+                vec![
+                    rotation(delta - FRAC_PI_2, UnboundUnitaryGate::RZ),
+                    rotation(gamma, UnboundUnitaryGate::RX),
+                    rotation(beta + FRAC_PI_2, UnboundUnitaryGate::RZ),
+                ]
+            }
+        } else {
+            // Decompose RY(-π/2) U RY(π/2) into ZYZ angles instead, which
+            // yields the XYX angles of U.
+            let ry = |theta| Matrix::from(UnboundUnitaryGate::RY(theta));
+            let conjugated = ry(-FRAC_PI_2).multiply(u)?.multiply(&ry(FRAC_PI_2))?;
+            let EulerAngles {
+                beta, gamma, delta, ..
+            } = EulerAngles::zyz(&conjugated, epsilon)?;
+            vec![
+                rotation(delta, UnboundUnitaryGate::RX),
+                rotation(gamma, UnboundUnitaryGate::RY),
+                rotation(beta, UnboundUnitaryGate::RX),
+            ]
+        };
+        sequence.into_iter().flatten().collect()
+    }
+
+    /// Converts decomposed primitives to native gates, up to global phase.
+    pub fn lower(&self, ops: Vec<Primitive>, epsilon: f64) -> Result<Vec<Gate>> {
+        let mut gates = vec![];
+        for op in ops {
+            match op {
+                Primitive::Single(qubit, u) => {
+                    gates.extend(self.lower_single(qubit, &u, epsilon)?);
+                }
+                Primitive::CNOT(control, target) => {
+                    if self.contains(NativeGate::CNOT) {
+                        gates.push(Gate::new_unitary(
+                            vec![target],
+                            vec![control],
+                            Matrix::from(UnboundUnitaryGate::X),
+                        )?);
+                    } else if self.contains(NativeGate::CZ) {
+                        let h = Matrix::from(UnboundUnitaryGate::H);
+                        gates.extend(self.lower_single(target, &h, epsilon)?);
+                        gates.push(Gate::new_unitary(
+                            vec![target],
+                            vec![control],
+                            Matrix::from(UnboundUnitaryGate::Z),
+                        )?);
+                        gates.extend(self.lower_single(target, &h, epsilon)?);
+                    } else {
+                        return inv_arg("the native gate set does not contain a two-qubit gate");
+                    }
+                }
+            }
+        }
+        Ok(gates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::converter::Converter;
+    use num_complex::Complex64;
+
+    fn qref(q: u64) -> QubitRef {
+        QubitRef::from_foreign(q).unwrap()
+    }
+
+    /// Returns the product of the matrices of single-qubit gates, applied
+    /// in order.
+    fn product(gates: &[Gate]) -> Matrix {
+        let mut result = Matrix::new_identity(2);
+        for gate in gates {
+            let m = gate.get_matrix().unwrap();
+            result = Matrix::new((0..4).map(|i| {
+                (0..2)
+                    .map(|k| m[(i / 2, k)] * result[(k, i % 2)])
+                    .sum::<Complex64>()
+            }))
+            .unwrap();
+        }
+        result
+    }
+
+    #[test]
+    fn gate_sets() {
+        assert!(GateSet::new(vec![NativeGate::RX, NativeGate::CZ]).is_err());
+        let data = ArbData::from_json(r#"{"gates": ["rx", "rz", "cnot"]}"#, vec![]).unwrap();
+        let set = GateSet::from_arb(&data).unwrap();
+        assert!(set.contains(NativeGate::CNOT));
+        assert!(!set.contains(NativeGate::RY));
+        let data = ArbData::from_json(r#"{"gates": ["rx", "toffoli"]}"#, vec![]).unwrap();
+        assert_eq!(
+            GateSet::from_arb(&data).unwrap_err().to_string(),
+            "Invalid argument: unknown native gate 'toffoli'"
+        );
+    }
+
+    #[test]
+    fn lower_single() {
+        let q = qref(1);
+        let u = Matrix::from(UnboundUnitaryGate::R(0.3, 1.2, -0.7));
+        for gates in &[
+            vec![NativeGate::RY, NativeGate::RZ],
+            vec![NativeGate::RX, NativeGate::RZ],
+            vec![NativeGate::RX, NativeGate::RY],
+            vec![NativeGate::R],
+            vec![NativeGate::U],
+        ] {
+            let set = GateSet::new(gates.clone()).unwrap();
+            let lowered = set
+                .lower(vec![Primitive::Single(q, u.clone())], 1.0e-9)
+                .unwrap();
+            let map = set.converter_map(1.0e-6);
+            for gate in &lowered {
+                assert!(map.detect(gate).unwrap().is_some());
+            }
+            assert!(product(&lowered).approx_eq(&u, 1.0e-6, true));
+        }
+        let set = GateSet::default();
+        let lowered = set
+            .lower(
+                vec![Primitive::Single(
+                    q,
+                    Matrix::from(UnboundUnitaryGate::RZ(0.5)),
+                )],
+                1.0e-9,
+            )
+            .unwrap();
+        assert_eq!(lowered.len(), 1);
+    }
+
+    #[test]
+    fn lower_cnot() {
+        let cnot = vec![Primitive::CNOT(qref(1), qref(2))];
+        let set = GateSet::new(vec![NativeGate::U]).unwrap();
+        assert!(set.lower(cnot.clone(), 1.0e-9).is_err());
+        let set = GateSet::new(vec![NativeGate::U, NativeGate::CNOT]).unwrap();
+        assert_eq!(set.lower(cnot.clone(), 1.0e-9).unwrap().len(), 1);
+        let set = GateSet::default();
+        assert_eq!(set.lower(cnot, 1.0e-9).unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,108 @@
+//! Operator that rewrites unitary gates into a configurable native gate set.
+//!
+//! The native gate set is configured using a `decompose.gate_set`
+//! initialization command, with JSON data of the form
+//! `{"gates": [<name>, ...]}`. The following names are recognized:
+//!
+//!  - `rx`, `ry`, and `rz`: rotations around the X, Y, and Z axes;
+//!  - `r`: the three-parameter single-qubit rotation gate;
+//!  - `u`: any single-qubit unitary;
+//!  - `cnot` (or `cx`) and `cz`: the controlled X and Z gates.
+//!
+//! The gate set must contain two of the rotation gates, or either `r` or `u`,
+//! such that any single-qubit gate can be expressed. Without configuration,
+//! the gate set consists of `rx`, `ry`, `rz`, and `cz`.
+//!
+//! Unitary gates that are already native are passed through unchanged. Any
+//! other unitary gate with one or two target qubits and any number of control
+//! qubits is decomposed: single-qubit gates into ZYZ Euler rotations,
+//! controlled gates using the constructions of Barenco et al., and two-qubit
+//! gates using the KAK decomposition into at most three CNOTs. The
+//! decomposition is exact up to global phase. Measurement, prep, and custom
+//! gates are passed through unchanged.
+
+mod decomposition;
+mod native;
+
+use dqcsim::{
+    common::{
+        converter::Converter,
+        error::{inv_arg, Result},
+        types::{ArbCmd, GateType, PluginMetadata, PluginType},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use native::{GateSet, NativeMap};
+use std::{
+    cell::RefCell,
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// Maximum RMS deviation between gate matrices when detecting native gates
+/// and control qubits.
+const EPSILON: f64 = 1.0e-6;
+
+thread_local! {
+    /// The converter map used to detect native gates. It is not `Send`, so it
+    /// cannot be moved into the callback closures. It is replaced by the
+    /// initialization callback when the gate set is configured, which runs in
+    /// the same thread as the gate callback.
+    static NATIVE_MAP: RefCell<NativeMap> = RefCell::new(GateSet::default().converter_map(EPSILON));
+}
+
+/// Handles the `decompose` initialization commands.
+fn configure(gate_set: &mut GateSet, cmd: &ArbCmd) -> Result<()> {
+    if cmd.interface_identifier() != "decompose" {
+        return Ok(());
+    }
+    match cmd.operation_identifier() {
+        "gate_set" => {
+            *gate_set = GateSet::from_arb(cmd.data())?;
+            NATIVE_MAP.with(|map| map.replace(gate_set.converter_map(EPSILON)));
+            Ok(())
+        }
+        op => inv_arg(format!("unknown operation decompose.{}", op)),
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Decomposition operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let gate_set = Arc::new(Mutex::new(GateSet::default()));
+
+    let gs_initialize = Arc::clone(&gate_set);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running decomposition operator initialization callback");
+        let mut gate_set = gs_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            configure(&mut gate_set, &arb_cmd)?;
+        }
+        Ok(())
+    });
+
+    let gs_gate = Arc::clone(&gate_set);
+    definition.gate = Box::new(move |state, gate| {
+        if gate.get_type() != &GateType::Unitary
+            || NATIVE_MAP.with(|map| map.borrow().detect(&gate))?.is_some()
+        {
+            return state.gate(gate).map(|_| vec![]);
+        }
+        let gate_set = gs_gate.lock().unwrap();
+        let gates = gate_set.lower(decomposition::decompose(&gate, EPSILON)?, EPSILON)?;
+        trace!("Decomposed gate into {} native gate(s)", gates.len());
+        // The decomposed gates inherit the condition of the original gate.
+        for native in gates {
+            state.gate(native.with_condition(gate.get_condition().iter().cloned())?)?;
+        }
+        Ok(vec![])
+    });
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
@@ -0,0 +1,259 @@
+//! Density-matrix simulator backend with support for single-qubit Kraus
+//! noise channels. Keeps track of the full (mixed) quantum state of the
+//! allocated qubits as a dense 2^n by 2^n complex matrix.
+//!
+//! Noise channels are configured through the `dm` arb interface. These
+//! commands can be passed along with a qubit allocation, in which case they
+//! apply to the newly allocated qubits, or sent as upstream or host arbs.
+//!
+//!  - `dm.gate_noise`: attaches a channel that is applied to every qubit
+//!    involved in a unitary or prep gate, after the gate is applied.
+//!  - `dm.idle_noise`: attaches a channel that is applied to every live qubit
+//!    once for each cycle that simulation time is advanced.
+//!
+//! The JSON data of these commands either selects a predefined channel using
+//! `{"channel": <name>, "p": <probability>}`, where the name is one of
+//! `depolarizing`, `bit_flip`, `phase_flip`, `amplitude_damping`, or
+//! `phase_damping`, or specifies the Kraus operators directly using
+//! `{"kraus": [<matrix>, ...]}`, where each matrix is a row-major list of
+//! four `[re, im]` pairs. For arbs, an optional `"qubits": [...]` entry
+//! restricts the channel to the given qubits; without it, the channel
+//! replaces the channel of that kind for all qubits.
+//!
+//!  - `dm.clear_noise`: removes the channels attached to the qubits listed in
+//!    the optional `"qubits"` entry, or all channels if it is not specified.
+//!  - `dm.state`: returns the current state as a JSON object with a `qubits`
+//!    list, ordered from least to most significant bit, and a row-major `rho`
+//!    list of `[re, im]` pairs.
+//!
+//! Channels can also be applied explicitly using custom gates named
+//! `channel`, with the channel specified in the JSON data of the gate in the
+//! same way as above, except that the `"qubits"` entry is not allowed. The
+//! channel is applied to each target qubit of the gate. This allows noise
+//! operators, such as the decoherence operator, to inject noise into the
+//! simulation.
+
+mod densitymatrix;
+mod noise;
+
+use densitymatrix::DensityMatrix;
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        types::{
+            ArbCmd, ArbData, GateType, PluginMetadata, PluginType, QubitMeasurementResult, QubitRef,
+        },
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use noise::{parse_channel, parse_clear, NoiseKind, NoiseModel};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// The complete state of the backend.
+#[derive(Debug, Default)]
+struct Backend {
+    dm: DensityMatrix,
+    noise: NoiseModel,
+}
+
+impl Backend {
+    /// Applies the noise channel of the given kind to the given qubits.
+    fn apply_noise<'a>(
+        &mut self,
+        kind: NoiseKind,
+        qubits: impl IntoIterator<Item = &'a QubitRef>,
+    ) -> Result<()> {
+        for qubit in qubits {
+            if let Some(channel) = self.noise.get(*qubit, kind) {
+                self.dm.apply_channel(*qubit, channel)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the `dm` arb interface. `allocated` is set to the newly
+    /// allocated qubits for arbs passed along with an allocation.
+    fn arb(&mut self, cmd: &ArbCmd, allocated: Option<&[QubitRef]>) -> Result<ArbData> {
+        if cmd.interface_identifier() != "dm" {
+            return Ok(ArbData::default());
+        }
+        match cmd.operation_identifier() {
+            op @ "gate_noise" | op @ "idle_noise" => {
+                let kind = if op == "gate_noise" {
+                    NoiseKind::Gate
+                } else {
+                    NoiseKind::Idle
+                };
+                let (channel, qubits) = parse_channel(cmd.data())?;
+                let qubits = allocated.map(|q| q.to_vec()).or(qubits);
+                self.noise.set(kind, channel, qubits);
+                Ok(ArbData::default())
+            }
+            "clear_noise" => {
+                let qubits = match allocated {
+                    Some(allocated) => Some(allocated.to_vec()),
+                    None => parse_clear(cmd.data())?,
+                };
+                self.noise.clear(qubits);
+                Ok(ArbData::default())
+            }
+            "state" => {
+                let json = serde_json::json!({
+                    "qubits": self.dm.qubits().iter().map(|q| q.to_foreign()).collect::<Result<Vec<u64>>>()?,
+                    "rho": self.dm.rho().iter().map(|a| [a.re, a.im]).collect::<Vec<_>>(),
+                });
+                ArbData::from_json(json.to_string(), vec![])
+            }
+            op => inv_arg(format!("unknown operation dm.{}", op)),
+        }
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("Density-matrix backend", "TU Delft QCE", "0.1.0"),
+    );
+
+    let backend = Arc::new(Mutex::new(Backend::default()));
+
+    definition.initialize = Box::new(|_state, arb_cmds| {
+        info!("Running density-matrix backend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        Ok(())
+    });
+
+    let be_allocate = Arc::clone(&backend);
+    definition.allocate = Box::new(move |_state, qubits, arb_cmds| {
+        let mut be = be_allocate.lock().unwrap();
+        be.dm.allocate(qubits.iter().cloned())?;
+        for cmd in arb_cmds {
+            be.arb(&cmd, Some(&qubits))?;
+        }
+        trace!("{} qubit(s) live after allocation", be.dm.num_qubits());
+        Ok(())
+    });
+
+    let be_free = Arc::clone(&backend);
+    definition.free = Box::new(move |_state, qubits| {
+        let mut be = be_free.lock().unwrap();
+        for qubit in &qubits {
+            be.noise.forget(*qubit);
+        }
+        be.dm.free(qubits)?;
+        trace!("{} qubit(s) live after deallocation", be.dm.num_qubits());
+        Ok(())
+    });
+
+    let be_gate = Arc::clone(&backend);
+    definition.gate = Box::new(move |state, gate| {
+        let mut be = be_gate.lock().unwrap();
+        match gate.get_type() {
+            GateType::Unitary => {
+                be.dm.apply_unitary(
+                    gate.get_targets(),
+                    gate.get_controls(),
+                    gate.get_matrix().unwrap(),
+                )?;
+                be.apply_noise(
+                    NoiseKind::Gate,
+                    gate.get_targets().iter().chain(gate.get_controls()),
+                )?;
+                Ok(vec![])
+            }
+            GateType::Measurement => {
+                let basis = gate.get_matrix().unwrap();
+                gate.get_measures()
+                    .iter()
+                    .map(|qubit| {
+                        let value = be.dm.measure(*qubit, basis, state.random_f64())?;
+                        debug!("Measured qubit {}: {}", qubit, value as u8);
+                        Ok(QubitMeasurementResult::new(
+                            *qubit,
+                            value,
+                            ArbData::default(),
+                        ))
+                    })
+                    .collect()
+            }
+            GateType::Prep => {
+                let basis = gate.get_matrix().unwrap();
+                for qubit in gate.get_targets() {
+                    be.dm.prep(*qubit, basis)?;
+                }
+                be.apply_noise(NoiseKind::Gate, gate.get_targets())?;
+                Ok(vec![])
+            }
+            GateType::PauliMeasurement(_) => {
+                // Pauli-product measurements are executed as the equivalent
+                // sequence of unitaries and a single Z-basis measurement.
+                // Noise is only applied once for the whole measurement, as
+                // for regular gates.
+                let mut measurements = vec![];
+                for gate in gate.decompose_pauli_measurement()? {
+                    if gate.get_type() == &GateType::Unitary {
+                        be.dm.apply_unitary(
+                            gate.get_targets(),
+                            gate.get_controls(),
+                            gate.get_matrix().unwrap(),
+                        )?;
+                    } else {
+                        let qubit = gate.get_measures()[0];
+                        let value =
+                            be.dm
+                                .measure(qubit, gate.get_matrix().unwrap(), state.random_f64())?;
+                        debug!("Measured Pauli product on qubit {}: {}", qubit, value as u8);
+                        measurements.push(QubitMeasurementResult::new(
+                            qubit,
+                            value,
+                            ArbData::default(),
+                        ));
+                    }
+                }
+                be.apply_noise(NoiseKind::Gate, gate.get_targets())?;
+                Ok(measurements)
+            }
+            GateType::Barrier => Ok(vec![]),
+            GateType::Custom(name) if name == "channel" => {
+                let (channel, qubits) = parse_channel(&gate.data)?;
+                if qubits.is_some() {
+                    return inv_arg("channel gates cannot specify qubits in their data");
+                }
+                for qubit in gate.get_targets() {
+                    be.dm.apply_channel(*qubit, &channel)?;
+                }
+                Ok(vec![])
+            }
+            GateType::Custom(name) => inv_arg(format!(
+                "the density-matrix backend does not support custom gate '{}'",
+                name
+            )),
+        }
+    });
+
+    let be_advance = Arc::clone(&backend);
+    definition.advance = Box::new(move |_state, cycles| {
+        let mut be = be_advance.lock().unwrap();
+        let qubits = be.dm.qubits().to_vec();
+        for _ in 0..cycles {
+            be.apply_noise(NoiseKind::Idle, &qubits)?;
+        }
+        Ok(())
+    });
+
+    let be_upstream_arb = Arc::clone(&backend);
+    definition.upstream_arb =
+        Box::new(move |_state, cmd| be_upstream_arb.lock().unwrap().arb(&cmd, None));
+
+    let be_host_arb = Arc::clone(&backend);
+    definition.host_arb = Box::new(move |_state, cmd| be_host_arb.lock().unwrap().arb(&cmd, None));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
@@ -0,0 +1,358 @@
+//! Dense density-matrix representation of the simulated qubit register.
+
+use crate::noise::KrausChannel;
+use dqcsim::common::{
+    error::{inv_arg, inv_op, Result},
+    types::{Matrix, QubitRef},
+};
+use num_complex::Complex64;
+
+/// The maximum number of qubits that can be live at the same time. The
+/// density matrix has 4^n entries, so this is half of what a state-vector
+/// simulator can handle in the same amount of memory.
+pub const MAX_QUBITS: usize = 14;
+
+/// Dense density-matrix simulator state.
+///
+/// The qubits are stored in allocation order, such that the index of a qubit
+/// in `qubits` is the bit position of that qubit in the row and column
+/// indices of the density matrix. The matrix itself is stored row-major,
+/// which means that it can be treated as a state vector of 2n qubits, where
+/// bit `q` refers to the column index and bit `q + n` refers to the row index
+/// of qubit `q`. Applying operator A to the rows and the complex conjugate of
+/// A to the columns thus yields A rho A^dagger.
+#[derive(Debug)]
+pub struct DensityMatrix {
+    /// The upstream qubit references of the live qubits, in bit order.
+    qubits: Vec<QubitRef>,
+
+    /// The row-major density matrix entries.
+    rho: Vec<Complex64>,
+}
+
+impl Default for DensityMatrix {
+    fn default() -> DensityMatrix {
+        DensityMatrix::new()
+    }
+}
+
+/// Applies the operator described by `element(row, col)` to the given bits of
+/// a vector, conditioned on all bits in `control_mask` being set. The first
+/// target corresponds to the most significant bit of the operator indices.
+fn apply_operator(
+    vector: &mut [Complex64],
+    targets: &[usize],
+    control_mask: usize,
+    element: impl Fn(usize, usize) -> Complex64,
+) {
+    let dimension = 1 << targets.len();
+    let target_mask = targets.iter().fold(0, |acc, i| acc | (1 << i));
+    let offsets: Vec<usize> = (0..dimension)
+        .map(|j| {
+            targets
+                .iter()
+                .enumerate()
+                .filter(|(t, _)| j & (1 << (targets.len() - t - 1)) != 0)
+                .fold(0, |acc, (_, i)| acc | (1 << i))
+        })
+        .collect();
+    let mut input = vec![Complex64::new(0.0, 0.0); dimension];
+    for base in 0..vector.len() {
+        if base & target_mask != 0 || base & control_mask != control_mask {
+            continue;
+        }
+        for (value, offset) in input.iter_mut().zip(offsets.iter()) {
+            *value = vector[base | offset];
+        }
+        for (row, offset) in offsets.iter().enumerate() {
+            vector[base | offset] = input
+                .iter()
+                .enumerate()
+                .map(|(col, value)| element(row, col) * value)
+                .sum();
+        }
+    }
+}
+
+impl DensityMatrix {
+    /// Constructs a density matrix without any qubits.
+    pub fn new() -> DensityMatrix {
+        DensityMatrix {
+            qubits: vec![],
+            rho: vec![Complex64::new(1.0, 0.0)],
+        }
+    }
+
+    /// Returns the number of live qubits.
+    pub fn num_qubits(&self) -> usize {
+        self.qubits.len()
+    }
+
+    /// Returns the qubits in bit order.
+    pub fn qubits(&self) -> &[QubitRef] {
+        &self.qubits
+    }
+
+    /// Returns the row-major density matrix entries.
+    pub fn rho(&self) -> &[Complex64] {
+        &self.rho
+    }
+
+    /// Returns the bit index of the given qubit.
+    fn index(&self, qubit: QubitRef) -> Result<usize> {
+        self.qubits
+            .iter()
+            .position(|q| *q == qubit)
+            .map_or_else(|| inv_arg(format!("qubit {} is not allocated", qubit)), Ok)
+    }
+
+    /// Adds the given qubits to the density matrix, initialized to |0>.
+    pub fn allocate(&mut self, qubits: impl IntoIterator<Item = QubitRef>) -> Result<()> {
+        for qubit in qubits {
+            if self.qubits.contains(&qubit) {
+                return inv_arg(format!("qubit {} is already allocated", qubit));
+            }
+            if self.qubits.len() >= MAX_QUBITS {
+                return inv_op(format!(
+                    "cannot allocate qubit {}: the density-matrix backend supports at most {} qubits",
+                    qubit, MAX_QUBITS
+                ));
+            }
+            // The new qubit becomes the most significant bit, so the existing
+            // matrix becomes the top-left quadrant of the new one.
+            let dimension = 1 << self.qubits.len();
+            let mut rho = vec![Complex64::new(0.0, 0.0); dimension * dimension * 4];
+            for row in 0..dimension {
+                rho[row * dimension * 2..row * dimension * 2 + dimension]
+                    .copy_from_slice(&self.rho[row * dimension..(row + 1) * dimension]);
+            }
+            self.rho = rho;
+            self.qubits.push(qubit);
+        }
+        Ok(())
+    }
+
+    /// Removes the given qubits from the density matrix by tracing them out.
+    pub fn free(&mut self, qubits: impl IntoIterator<Item = QubitRef>) -> Result<()> {
+        for qubit in qubits {
+            let index = self.index(qubit)?;
+            let dimension = 1 << (self.qubits.len() - 1);
+            let low = (1 << index) - 1;
+            let expand = |i: usize, bit: usize| ((i & !low) << 1) | (bit << index) | (i & low);
+            let mut rho = Vec::with_capacity(dimension * dimension);
+            for row in 0..dimension {
+                for col in 0..dimension {
+                    rho.push(
+                        (0..2)
+                            .map(|b| {
+                                self.rho[(expand(row, b) << (self.qubits.len())) | expand(col, b)]
+                            })
+                            .sum(),
+                    );
+                }
+            }
+            self.rho = rho;
+            self.qubits.remove(index);
+        }
+        Ok(())
+    }
+
+    /// Returns the row and column bit indices of the given qubits, and the
+    /// row and column control masks for the given controls.
+    fn bits(
+        &self,
+        targets: &[QubitRef],
+        controls: &[QubitRef],
+    ) -> Result<(Vec<usize>, Vec<usize>, usize, usize)> {
+        let n = self.qubits.len();
+        let cols = targets
+            .iter()
+            .map(|q| self.index(*q))
+            .collect::<Result<Vec<usize>>>()?;
+        let rows = cols.iter().map(|i| i + n).collect();
+        let col_mask = controls
+            .iter()
+            .map(|q| self.index(*q).map(|i| 1 << i))
+            .collect::<Result<Vec<usize>>>()?
+            .into_iter()
+            .fold(0, |acc, bit| acc | bit);
+        Ok((rows, cols, col_mask << n, col_mask))
+    }
+
+    /// Applies a unitary matrix to the given target qubits, conditioned on
+    /// the given control qubits all being |1>.
+    ///
+    /// The first target qubit corresponds to the most significant bit of the
+    /// matrix row/column indices.
+    pub fn apply_unitary(
+        &mut self,
+        targets: &[QubitRef],
+        controls: &[QubitRef],
+        matrix: &Matrix,
+    ) -> Result<()> {
+        if matrix.num_qubits() != Some(targets.len()) {
+            return inv_arg(format!(
+                "matrix of dimension {} cannot be applied to {} qubit(s)",
+                matrix.dimension(),
+                targets.len()
+            ));
+        }
+        let (rows, cols, row_mask, col_mask) = self.bits(targets, controls)?;
+        apply_operator(&mut self.rho, &rows, row_mask, |r, c| matrix[(r, c)]);
+        apply_operator(&mut self.rho, &cols, col_mask, |r, c| matrix[(r, c)].conj());
+        Ok(())
+    }
+
+    /// Applies a single-qubit Kraus channel to the given qubit.
+    pub fn apply_channel(&mut self, qubit: QubitRef, channel: &KrausChannel) -> Result<()> {
+        let (rows, cols, _, _) = self.bits(&[qubit], &[])?;
+        let mut output = vec![Complex64::new(0.0, 0.0); self.rho.len()];
+        for operator in channel.operators() {
+            let mut term = self.rho.clone();
+            apply_operator(&mut term, &rows, 0, |r, c| operator[(r, c)]);
+            apply_operator(&mut term, &cols, 0, |r, c| operator[(r, c)].conj());
+            for (o, t) in output.iter_mut().zip(term) {
+                *o += t;
+            }
+        }
+        self.rho = output;
+        Ok(())
+    }
+
+    /// Measures a qubit in the basis described by the given 2x2 matrix, as
+    /// defined by the semantics of `GateType::Measurement`. `random` must be
+    /// uniformly distributed in [0, 1).
+    pub fn measure(&mut self, qubit: QubitRef, basis: &Matrix, random: f64) -> Result<bool> {
+        let index = self.index(qubit)?;
+        let n = self.qubits.len();
+        self.apply_unitary(&[qubit], &[], &basis.adjoint())?;
+
+        let dimension = 1 << n;
+        let mask = 1 << index;
+        let p_one: f64 = (0..dimension)
+            .filter(|i| i & mask != 0)
+            .map(|i| self.rho[i * dimension + i].re)
+            .sum();
+        let value = random < p_one;
+        let p = if value { p_one } else { 1.0 - p_one };
+        for (i, x) in self.rho.iter_mut().enumerate() {
+            let row = (i >> n) & mask != 0;
+            let col = i & mask != 0;
+            if row == value && col == value {
+                *x /= p;
+            } else {
+                *x = Complex64::new(0.0, 0.0);
+            }
+        }
+
+        self.apply_unitary(&[qubit], &[], basis)?;
+        Ok(value)
+    }
+
+    /// Resets a qubit to |0> and then applies the given 2x2 matrix to it, as
+    /// defined by the semantics of `GateType::Prep`.
+    pub fn prep(&mut self, qubit: QubitRef, basis: &Matrix) -> Result<()> {
+        let reset = KrausChannel::new(vec![
+            Matrix::new(vec![
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0),
+            ])?,
+            Matrix::new(vec![
+                Complex64::new(0.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 0.0),
+                Complex64::new(0.0, 0.0),
+            ])?,
+        ])?;
+        self.apply_channel(qubit, &reset)?;
+        self.apply_unitary(&[qubit], &[], basis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::{gates::UnboundUnitaryGate, types::Basis};
+
+    fn qref(q: u64) -> QubitRef {
+        QubitRef::from_foreign(q).unwrap()
+    }
+
+    fn approx(a: Complex64, b: f64) -> bool {
+        (a - Complex64::new(b, 0.0)).norm() < 1e-9
+    }
+
+    #[test]
+    fn allocate_free() {
+        let mut dm = DensityMatrix::new();
+        dm.allocate(vec![qref(1), qref(2)]).unwrap();
+        assert_eq!(dm.rho().len(), 16);
+        assert!(approx(dm.rho()[0], 1.0));
+        dm.apply_unitary(&[qref(2)], &[], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        dm.free(vec![qref(1)]).unwrap();
+        assert_eq!(dm.qubits(), &[qref(2)]);
+        assert!(approx(dm.rho()[0], 0.0));
+        assert!(approx(dm.rho()[3], 1.0));
+        assert!(dm.free(vec![qref(1)]).is_err());
+    }
+
+    #[test]
+    fn bell_state_partial_trace() {
+        let mut dm = DensityMatrix::new();
+        dm.allocate(vec![qref(1), qref(2)]).unwrap();
+        dm.apply_unitary(&[qref(1)], &[], &UnboundUnitaryGate::H.into())
+            .unwrap();
+        dm.apply_unitary(&[qref(2)], &[qref(1)], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        for i in &[0, 3, 12, 15] {
+            assert!(approx(dm.rho()[*i], 0.5));
+        }
+
+        // Tracing out half of a Bell pair leaves a maximally mixed state.
+        dm.free(vec![qref(2)]).unwrap();
+        assert!(approx(dm.rho()[0], 0.5));
+        assert!(approx(dm.rho()[1], 0.0));
+        assert!(approx(dm.rho()[2], 0.0));
+        assert!(approx(dm.rho()[3], 0.5));
+    }
+
+    #[test]
+    fn channels() {
+        let mut dm = DensityMatrix::new();
+        dm.allocate(vec![qref(1)]).unwrap();
+        dm.apply_unitary(&[qref(1)], &[], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        dm.apply_channel(
+            qref(1),
+            &KrausChannel::predefined("amplitude_damping", 0.25).unwrap(),
+        )
+        .unwrap();
+        assert!(approx(dm.rho()[0], 0.25));
+        assert!(approx(dm.rho()[3], 0.75));
+
+        dm.apply_channel(
+            qref(1),
+            &KrausChannel::predefined("depolarizing", 1.0).unwrap(),
+        )
+        .unwrap();
+        assert!(approx(dm.rho()[0], 0.5));
+        assert!(approx(dm.rho()[3], 0.5));
+    }
+
+    #[test]
+    fn measure_and_prep() {
+        let mut dm = DensityMatrix::new();
+        dm.allocate(vec![qref(1)]).unwrap();
+        let x: Matrix = Basis::X.into();
+        dm.prep(qref(1), &x).unwrap();
+        assert!(approx(dm.rho()[1], 0.5));
+        assert!(!dm.measure(qref(1), &x, 0.999).unwrap());
+        assert!(dm.measure(qref(1), &Basis::Z.into(), 0.4).unwrap());
+        assert!(approx(dm.rho()[3], 1.0));
+        dm.prep(qref(1), &Basis::Z.into()).unwrap();
+        assert!(approx(dm.rho()[0], 1.0));
+    }
+}
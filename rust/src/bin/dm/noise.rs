@@ -0,0 +1,294 @@
+//! Single-qubit Kraus-operator noise channels and their configuration.
+
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    types::{ArbData, Matrix, QubitRef},
+};
+use num_complex::Complex64;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A completely positive trace-preserving map on a single qubit, represented
+/// by its Kraus operators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KrausChannel {
+    operators: Vec<Matrix>,
+}
+
+impl KrausChannel {
+    /// Constructs a channel from its Kraus operators, verifying that they are
+    /// 2x2 and that the sum of K^dagger K is the identity within 1e-6.
+    pub fn new(operators: Vec<Matrix>) -> Result<KrausChannel> {
+        if operators.is_empty() {
+            return inv_arg("a Kraus channel needs at least one operator");
+        }
+        let mut sum = [Complex64::new(0.0, 0.0); 4];
+        for operator in &operators {
+            if operator.dimension() != 2 {
+                return inv_arg(format!(
+                    "Kraus operators must be 2x2, but found one of dimension {}",
+                    operator.dimension()
+                ));
+            }
+            for row in 0..2 {
+                for col in 0..2 {
+                    sum[row * 2 + col] += (0..2)
+                        .map(|k| operator[(k, row)].conj() * operator[(k, col)])
+                        .sum::<Complex64>();
+                }
+            }
+        }
+        if !Matrix::new(sum.iter().cloned())?.approx_eq(&Matrix::new_identity(2), 1.0e-6, false) {
+            return inv_arg("the Kraus operators are not trace-preserving within 1e-6 tolerance");
+        }
+        Ok(KrausChannel { operators })
+    }
+
+    /// Returns the Kraus operators of this channel.
+    pub fn operators(&self) -> &[Matrix] {
+        &self.operators
+    }
+
+    /// Builds Kraus operators from a list of real-valued matrices, each
+    /// scaled by the square root of the accompanying weight.
+    fn weighted(terms: &[(f64, [f64; 4])]) -> Result<Vec<Matrix>> {
+        terms
+            .iter()
+            .map(|(w, m)| Matrix::new(m.iter().map(|x| Complex64::new(x * w.sqrt(), 0.0))))
+            .collect()
+    }
+
+    /// Constructs one of the predefined channels by name, parameterized by
+    /// `p`.
+    ///
+    /// The following channels are supported:
+    ///
+    ///  - `depolarizing`: (1-p) rho + p I/2;
+    ///  - `bit_flip`: X is applied with probability p;
+    ///  - `phase_flip`: Z is applied with probability p;
+    ///  - `amplitude_damping`: |1> decays to |0> with probability p;
+    ///  - `phase_damping`: the coherence is reduced by a factor sqrt(1-p).
+    pub fn predefined(name: &str, p: f64) -> Result<KrausChannel> {
+        if !(0.0..=1.0).contains(&p) {
+            return inv_arg(format!("channel parameter {} is not within [0, 1]", p));
+        }
+        const I: [f64; 4] = [1.0, 0.0, 0.0, 1.0];
+        const X: [f64; 4] = [0.0, 1.0, 1.0, 0.0];
+        const Z: [f64; 4] = [1.0, 0.0, 0.0, -1.0];
+        match name {
+            "depolarizing" => {
+                let mut operators =
+                    KrausChannel::weighted(&[(1.0 - 0.75 * p, I), (p / 4.0, X), (p / 4.0, Z)])?;
+                operators.push(Matrix::new(
+                    [0.0, -1.0, 1.0, 0.0]
+                        .iter()
+                        .map(|x| Complex64::new(0.0, x * (p / 4.0).sqrt())),
+                )?);
+                KrausChannel::new(operators)
+            }
+            "bit_flip" => KrausChannel::new(KrausChannel::weighted(&[(1.0 - p, I), (p, X)])?),
+            "phase_flip" => KrausChannel::new(KrausChannel::weighted(&[(1.0 - p, I), (p, Z)])?),
+            "amplitude_damping" => KrausChannel::new(KrausChannel::weighted(&[
+                (1.0, [1.0, 0.0, 0.0, (1.0 - p).sqrt()]),
+                (p, [0.0, 1.0, 0.0, 0.0]),
+            ])?),
+            "phase_damping" => KrausChannel::new(KrausChannel::weighted(&[
+                (1.0, [1.0, 0.0, 0.0, (1.0 - p).sqrt()]),
+                (p, [0.0, 0.0, 0.0, 1.0]),
+            ])?),
+            _ => inv_arg(format!("unknown noise channel '{}'", name)),
+        }
+    }
+}
+
+/// The JSON representation of a channel in the `dm.gate_noise` and
+/// `dm.idle_noise` arbs.
+///
+/// Either `channel` and `p` are specified to select one of the predefined
+/// channels, or `kraus` is specified as a list of row-major 2x2 matrices of
+/// `[re, im]` pairs. `qubits` optionally restricts the channel to the given
+/// qubits; it is ignored for arbs passed along with an allocation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChannelSpecification {
+    channel: Option<String>,
+    p: Option<f64>,
+    kraus: Option<Vec<Vec<[f64; 2]>>>,
+    qubits: Option<Vec<u64>>,
+}
+
+/// The JSON representation of the `dm.clear_noise` arb.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClearSpecification {
+    qubits: Option<Vec<u64>>,
+}
+
+/// Converts a list of foreign qubit references.
+fn qubit_refs(qubits: Vec<u64>) -> Result<Vec<QubitRef>> {
+    qubits
+        .into_iter()
+        .map(|q| {
+            QubitRef::from_foreign(q).map_or_else(|| inv_arg("qubit references cannot be zero"), Ok)
+        })
+        .collect()
+}
+
+/// Parses the channel specification of a `dm.gate_noise` or `dm.idle_noise`
+/// arb, returning the channel and the qubits it applies to, if specified.
+pub fn parse_channel(data: &ArbData) -> Result<(KrausChannel, Option<Vec<QubitRef>>)> {
+    let spec: ChannelSpecification = serde_json::from_str(&data.get_json()?)?;
+    let channel =
+        match (spec.channel, spec.p, spec.kraus) {
+            (Some(name), Some(p), None) => KrausChannel::predefined(&name, p)?,
+            (None, None, Some(kraus)) => KrausChannel::new(
+                kraus
+                    .into_iter()
+                    .map(|m| Matrix::new(m.into_iter().map(|[re, im]| Complex64::new(re, im))))
+                    .collect::<Result<Vec<Matrix>>>()?,
+            )?,
+            _ => return inv_arg(
+                "noise channels must be specified using either \"channel\" and \"p\" or \"kraus\"",
+            ),
+        };
+    Ok((channel, spec.qubits.map(qubit_refs).transpose()?))
+}
+
+/// Parses the qubit list of a `dm.clear_noise` arb, if specified.
+pub fn parse_clear(data: &ArbData) -> Result<Option<Vec<QubitRef>>> {
+    let spec: ClearSpecification = serde_json::from_str(&data.get_json()?)?;
+    spec.qubits.map(qubit_refs).transpose()
+}
+
+/// The kind of noise a channel models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoiseKind {
+    /// Applied to each qubit involved in a unitary or prep gate, after the
+    /// gate.
+    Gate,
+
+    /// Applied to every live qubit for each cycle that time advances.
+    Idle,
+}
+
+/// Keeps track of the channels attached to the qubits.
+#[derive(Debug, Default)]
+pub struct NoiseModel {
+    /// Channels applying to all qubits without a specific channel.
+    defaults: HashMap<NoiseKind, KrausChannel>,
+
+    /// Channels attached to specific qubits.
+    qubits: HashMap<(QubitRef, NoiseKind), KrausChannel>,
+}
+
+impl NoiseModel {
+    /// Attaches a channel to the given qubits, or to all qubits if `None`.
+    /// In the latter case, any qubit-specific channels of this kind are
+    /// removed.
+    pub fn set(&mut self, kind: NoiseKind, channel: KrausChannel, qubits: Option<Vec<QubitRef>>) {
+        if let Some(qubits) = qubits {
+            for qubit in qubits {
+                self.qubits.insert((qubit, kind), channel.clone());
+            }
+        } else {
+            self.qubits.retain(|(_, k), _| *k != kind);
+            self.defaults.insert(kind, channel);
+        }
+    }
+
+    /// Removes all channels from the given qubits, or all channels if `None`.
+    pub fn clear(&mut self, qubits: Option<Vec<QubitRef>>) {
+        if let Some(qubits) = qubits {
+            self.qubits.retain(|(q, _), _| !qubits.contains(q));
+        } else {
+            self.defaults.clear();
+            self.qubits.clear();
+        }
+    }
+
+    /// Forgets the qubit-specific channels of a freed qubit.
+    pub fn forget(&mut self, qubit: QubitRef) {
+        self.qubits.retain(|(q, _), _| *q != qubit);
+    }
+
+    /// Returns the channel of the given kind that applies to the given qubit.
+    pub fn get(&self, qubit: QubitRef, kind: NoiseKind) -> Option<&KrausChannel> {
+        self.qubits
+            .get(&(qubit, kind))
+            .or_else(|| self.defaults.get(&kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predefined_channels() {
+        for name in &[
+            "depolarizing",
+            "bit_flip",
+            "phase_flip",
+            "amplitude_damping",
+            "phase_damping",
+        ] {
+            for p in &[0.0, 0.1, 1.0] {
+                assert!(KrausChannel::predefined(name, *p).is_ok());
+            }
+        }
+        assert_eq!(
+            KrausChannel::predefined("depolarizing", 1.5)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: channel parameter 1.5 is not within [0, 1]"
+        );
+        assert_eq!(
+            KrausChannel::predefined("hello", 0.1)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: unknown noise channel 'hello'"
+        );
+    }
+
+    #[test]
+    fn custom_channel() {
+        let data = ArbData::from_json(
+            r#"{"kraus": [[[1,0],[0,0],[0,0],[1,0]]], "qubits": [3]}"#,
+            vec![],
+        )
+        .unwrap();
+        let (channel, qubits) = parse_channel(&data).unwrap();
+        assert_eq!(channel.operators().len(), 1);
+        assert_eq!(qubits, Some(vec![QubitRef::from_foreign(3).unwrap()]));
+
+        let data = ArbData::from_json(r#"{"kraus": [[[1,0],[0,0],[0,0],[0,0]]]}"#, vec![]).unwrap();
+        assert_eq!(
+            parse_channel(&data).unwrap_err().to_string(),
+            "Invalid argument: the Kraus operators are not trace-preserving within 1e-6 tolerance"
+        );
+
+        let data = ArbData::from_json(r#"{"channel": "bit_flip"}"#, vec![]).unwrap();
+        assert!(parse_channel(&data).is_err());
+    }
+
+    #[test]
+    fn noise_model() {
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let q2 = QubitRef::from_foreign(2).unwrap();
+        let flip = KrausChannel::predefined("bit_flip", 0.1).unwrap();
+        let damp = KrausChannel::predefined("amplitude_damping", 0.1).unwrap();
+        let mut model = NoiseModel::default();
+        assert!(model.get(q1, NoiseKind::Idle).is_none());
+        model.set(NoiseKind::Idle, damp.clone(), Some(vec![q2]));
+        model.set(NoiseKind::Idle, flip.clone(), None);
+        assert_eq!(model.get(q1, NoiseKind::Idle), Some(&flip));
+        assert_eq!(model.get(q2, NoiseKind::Idle), Some(&flip));
+        model.set(NoiseKind::Idle, damp.clone(), Some(vec![q2]));
+        assert_eq!(model.get(q2, NoiseKind::Idle), Some(&damp));
+        assert!(model.get(q2, NoiseKind::Gate).is_none());
+        model.forget(q2);
+        assert_eq!(model.get(q2, NoiseKind::Idle), Some(&flip));
+        model.clear(None);
+        assert!(model.get(q1, NoiseKind::Idle).is_none());
+    }
+}
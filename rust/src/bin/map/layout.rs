@@ -0,0 +1,328 @@
+//! Coupling graphs and the mapping of upstream qubits onto physical qubits.
+
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    types::{ArbData, QubitRef},
+};
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+
+/// The JSON representation of the `map.coupling_graph` arb.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GraphSpecification {
+    qubits: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+/// An undirected graph describing which physical qubits can interact.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CouplingGraph {
+    /// The neighbors of each physical qubit.
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl CouplingGraph {
+    /// Constructs a coupling graph for the given number of physical qubits
+    /// from a list of edges.
+    pub fn new(num_qubits: usize, edges: impl IntoIterator<Item = (usize, usize)>) -> Result<Self> {
+        let mut neighbors = vec![vec![]; num_qubits];
+        for (a, b) in edges {
+            if a >= num_qubits || b >= num_qubits {
+                return inv_arg(format!(
+                    "edge ({}, {}) refers to a physical qubit that does not exist",
+                    a, b
+                ));
+            }
+            if a == b {
+                return inv_arg(format!("edge ({}, {}) is a self-loop", a, b));
+            }
+            if !neighbors[a].contains(&b) {
+                neighbors[a].push(b);
+                neighbors[b].push(a);
+            }
+        }
+        Ok(CouplingGraph { neighbors })
+    }
+
+    /// Constructs a coupling graph from the JSON data of a
+    /// `map.coupling_graph` arb.
+    pub fn from_arb(data: &ArbData) -> Result<Self> {
+        let spec: GraphSpecification = serde_json::from_str(&data.get_json()?)?;
+        CouplingGraph::new(spec.qubits, spec.edges)
+    }
+
+    /// Returns the number of physical qubits.
+    pub fn num_qubits(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Returns whether the given physical qubits can interact.
+    pub fn adjacent(&self, a: usize, b: usize) -> bool {
+        self.neighbors[a].contains(&b)
+    }
+
+    /// Returns a shortest path from physical qubit `a` to `b`, including both
+    /// endpoints.
+    pub fn path(&self, a: usize, b: usize) -> Result<Vec<usize>> {
+        let mut previous = vec![None; self.num_qubits()];
+        let mut queue = VecDeque::new();
+        previous[a] = Some(a);
+        queue.push_back(a);
+        while let Some(current) = queue.pop_front() {
+            if current == b {
+                let mut path = vec![b];
+                let mut current = b;
+                while current != a {
+                    current = previous[current].unwrap();
+                    path.push(current);
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for &next in &self.neighbors[current] {
+                if previous[next].is_none() {
+                    previous[next] = Some(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        inv_arg(format!("physical qubits {} and {} are not connected", a, b))
+    }
+}
+
+/// Keeps track of which upstream qubit lives on which physical qubit, and
+/// which downstream qubit represents each physical qubit.
+#[derive(Debug, Default)]
+pub struct Layout {
+    /// The coupling graph of the physical qubits.
+    graph: CouplingGraph,
+
+    /// The upstream qubit currently stored in each physical qubit.
+    upstream: Vec<Option<QubitRef>>,
+
+    /// The downstream qubit representing each physical qubit, if it has been
+    /// allocated.
+    downstream: Vec<Option<QubitRef>>,
+
+    /// The physical qubit of each upstream qubit.
+    positions: HashMap<QubitRef, usize>,
+
+    /// The upstream qubits awaiting a measurement result from each downstream
+    /// qubit, in the order in which they were measured.
+    measured: HashMap<QubitRef, VecDeque<QubitRef>>,
+}
+
+impl Layout {
+    /// Constructs an empty layout for the given coupling graph.
+    pub fn new(graph: CouplingGraph) -> Self {
+        let num_qubits = graph.num_qubits();
+        Layout {
+            graph,
+            upstream: vec![None; num_qubits],
+            downstream: vec![None; num_qubits],
+            positions: HashMap::new(),
+            measured: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of physical qubits.
+    pub fn num_qubits(&self) -> usize {
+        self.graph.num_qubits()
+    }
+
+    /// Places newly allocated upstream qubits on the free physical qubits
+    /// with the lowest indices, returning their positions.
+    pub fn place(&mut self, qubits: &[QubitRef]) -> Result<Vec<usize>> {
+        let free: Vec<usize> = (0..self.upstream.len())
+            .filter(|&p| self.upstream[p].is_none())
+            .take(qubits.len())
+            .collect();
+        if free.len() < qubits.len() {
+            return inv_arg(format!(
+                "cannot allocate {} qubit(s); only {} of the {} physical qubits are free",
+                qubits.len(),
+                free.len(),
+                self.upstream.len()
+            ));
+        }
+        for (&qubit, &position) in qubits.iter().zip(free.iter()) {
+            self.upstream[position].replace(qubit);
+            self.positions.insert(qubit, position);
+        }
+        Ok(free)
+    }
+
+    /// Removes a freed upstream qubit from the layout. The physical qubit
+    /// remains allocated downstream.
+    pub fn remove(&mut self, qubit: QubitRef) -> Result<usize> {
+        let position = self.position(qubit)?;
+        self.positions.remove(&qubit);
+        self.upstream[position].take();
+        Ok(position)
+    }
+
+    /// Returns the physical qubit storing the given upstream qubit.
+    pub fn position(&self, qubit: QubitRef) -> Result<usize> {
+        self.positions
+            .get(&qubit)
+            .cloned()
+            .map_or_else(|| inv_arg(format!("qubit {} is not mapped", qubit)), Ok)
+    }
+
+    /// Returns the downstream qubit representing the given physical qubit.
+    pub fn downstream(&self, position: usize) -> Option<QubitRef> {
+        self.downstream[position]
+    }
+
+    /// Sets the downstream qubit representing the given physical qubit.
+    pub fn set_downstream(&mut self, position: usize, qubit: Option<QubitRef>) {
+        self.downstream[position] = qubit;
+    }
+
+    /// Records that the given upstream qubit is measured through the
+    /// downstream qubit of its current physical qubit.
+    ///
+    /// Measurement results are returned asynchronously, so by the time they
+    /// arrive, later SWAPs may have moved another upstream qubit onto the
+    /// physical qubit. The results are therefore attributed using
+    /// `measured()` rather than the current layout.
+    pub fn measure(&mut self, qubit: QubitRef) -> Result<()> {
+        let position = self.position(qubit)?;
+        let downstream = self.downstream[position]
+            .map_or_else(|| inv_arg(format!("qubit {} is not allocated", qubit)), Ok)?;
+        self.measured
+            .entry(downstream)
+            .or_default()
+            .push_back(qubit);
+        Ok(())
+    }
+
+    /// Returns the upstream qubit that a measurement result received for the
+    /// given downstream qubit belongs to, if it was measured.
+    pub fn measured(&mut self, downstream: QubitRef) -> Option<QubitRef> {
+        let queue = self.measured.get_mut(&downstream)?;
+        let qubit = queue.pop_front();
+        if queue.is_empty() {
+            self.measured.remove(&downstream);
+        }
+        qubit
+    }
+
+    /// Returns the SWAPs between physical qubits needed to make the given
+    /// physical qubits adjacent. The first qubit is moved towards the second.
+    pub fn route(&self, a: usize, b: usize) -> Result<Vec<(usize, usize)>> {
+        if a == b || self.graph.adjacent(a, b) {
+            return Ok(vec![]);
+        }
+        let path = self.graph.path(a, b)?;
+        Ok(path[..path.len() - 1]
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .collect())
+    }
+
+    /// Exchanges the upstream qubits stored in two physical qubits, after a
+    /// SWAP gate has been applied to them.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.upstream.swap(a, b);
+        for position in &[a, b] {
+            if let Some(qubit) = self.upstream[*position] {
+                self.positions.insert(qubit, *position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qref(q: u64) -> QubitRef {
+        QubitRef::from_foreign(q).unwrap()
+    }
+
+    /// Returns a linear coupling graph.
+    fn line(num_qubits: usize) -> CouplingGraph {
+        CouplingGraph::new(num_qubits, (1..num_qubits).map(|q| (q - 1, q))).unwrap()
+    }
+
+    #[test]
+    fn graph() {
+        let data = ArbData::from_json(
+            r#"{"qubits": 4, "edges": [[0, 1], [1, 2], [2, 3]]}"#,
+            vec![],
+        )
+        .unwrap();
+        let graph = CouplingGraph::from_arb(&data).unwrap();
+        assert_eq!(graph, line(4));
+        assert!(graph.adjacent(2, 1));
+        assert!(!graph.adjacent(0, 2));
+        assert_eq!(graph.path(3, 0).unwrap(), vec![3, 2, 1, 0]);
+
+        let graph = CouplingGraph::new(3, vec![(0, 1)]).unwrap();
+        assert_eq!(
+            graph.path(0, 2).unwrap_err().to_string(),
+            "Invalid argument: physical qubits 0 and 2 are not connected"
+        );
+        assert_eq!(
+            CouplingGraph::new(2, vec![(0, 2)]).unwrap_err().to_string(),
+            "Invalid argument: edge (0, 2) refers to a physical qubit that does not exist"
+        );
+        assert!(CouplingGraph::new(2, vec![(1, 1)]).is_err());
+    }
+
+    #[test]
+    fn layout() {
+        let mut layout = Layout::new(line(4));
+        assert_eq!(layout.place(&[qref(1), qref(2)]).unwrap(), vec![0, 1]);
+        assert_eq!(layout.place(&[qref(3)]).unwrap(), vec![2]);
+        assert_eq!(layout.remove(qref(2)).unwrap(), 1);
+        assert_eq!(layout.place(&[qref(4), qref(5)]).unwrap(), vec![1, 3]);
+        assert_eq!(
+            layout.place(&[qref(6)]).unwrap_err().to_string(),
+            "Invalid argument: cannot allocate 1 qubit(s); only 0 of the 4 physical qubits are free"
+        );
+
+        // Move qubit 1 next to qubit 5.
+        let swaps = layout
+            .route(
+                layout.position(qref(1)).unwrap(),
+                layout.position(qref(5)).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(swaps, vec![(0, 1), (1, 2)]);
+        for (a, b) in swaps {
+            layout.swap(a, b);
+        }
+        assert_eq!(layout.position(qref(1)).unwrap(), 2);
+        assert_eq!(layout.position(qref(4)).unwrap(), 0);
+        assert_eq!(layout.position(qref(3)).unwrap(), 1);
+        assert!(layout.route(2, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn measured() {
+        let mut layout = Layout::new(line(3));
+        layout.place(&[qref(1), qref(2), qref(3)]).unwrap();
+        for position in 0..3 {
+            layout.set_downstream(position, Some(qref(10 + position as u64)));
+        }
+
+        // Measure qubit 1, and then route a SWAP through its physical qubit
+        // before the result arrives.
+        layout.measure(qref(1)).unwrap();
+        for (a, b) in layout.route(0, 2).unwrap() {
+            layout.swap(a, b);
+        }
+        assert_eq!(layout.position(qref(2)).unwrap(), 0);
+        layout.measure(qref(2)).unwrap();
+
+        // The results are attributed to the qubits stored in the physical
+        // qubit at the time of the measurement.
+        assert_eq!(layout.measured(qref(10)), Some(qref(1)));
+        assert_eq!(layout.measured(qref(10)), Some(qref(2)));
+        assert_eq!(layout.measured(qref(10)), None);
+        assert_eq!(layout.measured(qref(11)), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Operator that maps upstream qubits onto the physical qubits of a device
+//! with limited connectivity, inserting SWAP gates where necessary.
+//!
+//! The device is described using a `map.coupling_graph` initialization
+//! command, with JSON data of the form
+//! `{"qubits": <num>, "edges": [[<a>, <b>], ...]}`. The physical qubits are
+//! numbered from 0 to `qubits - 1`, and each edge indicates that the two
+//! physical qubits can interact. This command is required.
+//!
+//! Upstream qubits are placed on the free physical qubits with the lowest
+//! indices when they are allocated. Each physical qubit is represented by a
+//! downstream qubit; a fresh downstream qubit is allocated whenever an
+//! upstream qubit is placed, such that allocation commands are passed along.
+//! Freed physical qubits remain allocated downstream, as SWAPs may still
+//! route through them.
+//!
+//! When a gate acts on two physical qubits that are not adjacent, the first
+//! qubit is moved along a shortest path towards the second using SWAP gates.
+//! Gates acting on more than two target and control qubits cannot be routed;
+//! these must be decomposed upstream, for instance using the decomposition
+//! operator. Measurement results are translated back to the upstream qubits
+//! that were stored in the measured physical qubits at the time of the
+//! measurement.
+//!
+//! The conditions of conditional gates are resolved by this operator, as the
+//! physical qubits do not retain the measurement results of the upstream
+//! qubits when these are moved. Only the gates that are executed are routed
+//! and passed downstream.
+
+mod layout;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        gates::UnboundUnitaryGate,
+        types::{
+            ArbCmd, Gate, Matrix, PluginMetadata, PluginType, QubitMeasurementResult, QubitRef,
+        },
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use layout::{CouplingGraph, Layout};
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// Handles the `map` initialization commands.
+fn configure(layout: &mut Layout, cmd: &ArbCmd) -> Result<()> {
+    if cmd.interface_identifier() != "map" {
+        return Ok(());
+    }
+    match cmd.operation_identifier() {
+        "coupling_graph" => {
+            *layout = Layout::new(CouplingGraph::from_arb(cmd.data())?);
+            Ok(())
+        }
+        op => inv_arg(format!("unknown operation map.{}", op)),
+    }
+}
+
+/// Returns the downstream qubit representing the given physical qubit,
+/// allocating it if it has not been allocated yet.
+fn physical(state: &mut PluginState, layout: &mut Layout, position: usize) -> Result<QubitRef> {
+    if let Some(qubit) = layout.downstream(position) {
+        return Ok(qubit);
+    }
+    let qubit = state.allocate(1, vec![])?[0];
+    layout.set_downstream(position, Some(qubit));
+    Ok(qubit)
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Mapping operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let layout = Arc::new(Mutex::new(Layout::default()));
+
+    let layout_initialize = Arc::clone(&layout);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running mapping operator initialization callback");
+        let mut layout = layout_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            configure(&mut layout, &arb_cmd)?;
+        }
+        if layout.num_qubits() == 0 {
+            return inv_arg("the mapping operator requires a map.coupling_graph command");
+        }
+        Ok(())
+    });
+
+    let layout_allocate = Arc::clone(&layout);
+    definition.allocate = Box::new(move |state, qubits, arb_cmds| {
+        let mut layout = layout_allocate.lock().unwrap();
+        let positions = layout.place(&qubits)?;
+
+        // Replace the downstream qubits of the physical qubits, such that the
+        // new qubits start out in the ground state and receive the commands.
+        let stale: Vec<_> = positions
+            .iter()
+            .filter_map(|&position| layout.downstream(position))
+            .collect();
+        if !stale.is_empty() {
+            state.free(stale)?;
+        }
+        let fresh = state.allocate(positions.len(), arb_cmds)?;
+        for (position, qubit) in positions.into_iter().zip(fresh) {
+            trace!("Placing qubit {} on physical qubit {}", qubit, position);
+            layout.set_downstream(position, Some(qubit));
+        }
+        Ok(())
+    });
+
+    let layout_free = Arc::clone(&layout);
+    definition.free = Box::new(move |_state, qubits| {
+        let mut layout = layout_free.lock().unwrap();
+        for qubit in qubits {
+            layout.remove(qubit)?;
+        }
+        Ok(())
+    });
+
+    let layout_gate = Arc::clone(&layout);
+    definition.gate = Box::new(move |state, gate| {
+        if !state.resolve_condition(&gate)? {
+            trace!("Condition of gate is false; not executing it");
+            return Ok(gate.undefined_measurements());
+        }
+        let gate = gate.without_condition();
+        let mut layout = layout_gate.lock().unwrap();
+        let interacting: Vec<QubitRef> = gate
+            .get_targets()
+            .iter()
+            .chain(gate.get_controls().iter())
+            .cloned()
+            .collect();
+        match interacting[..] {
+            [a, b] => {
+                for (p, q) in layout.route(layout.position(a)?, layout.position(b)?)? {
+                    trace!("Swapping physical qubits {} and {}", p, q);
+                    let qubits = vec![
+                        physical(state, &mut layout, p)?,
+                        physical(state, &mut layout, q)?,
+                    ];
+                    state.gate(Gate::new_unitary(
+                        qubits,
+                        vec![],
+                        Matrix::from(UnboundUnitaryGate::SWAP),
+                    )?)?;
+                    layout.swap(p, q);
+                }
+            }
+            [_, _, _, ..] => {
+                return inv_arg(format!(
+                    "cannot route gates acting on {} qubits; decompose them first",
+                    interacting.len()
+                ));
+            }
+            _ => {}
+        }
+
+        let mut mapping = HashMap::new();
+        for qubit in interacting.iter().chain(gate.get_measures().iter()) {
+            let position = layout.position(*qubit)?;
+            mapping.insert(*qubit, layout.downstream(position).unwrap());
+        }
+        for qubit in gate.get_measures() {
+            layout.measure(*qubit)?;
+        }
+        state.gate(gate.with_qubit_refs(|qubit| mapping[&qubit]))?;
+        Ok(vec![])
+    });
+
+    let layout_modify_measurement = Arc::clone(&layout);
+    definition.modify_measurement = Box::new(move |_state, measurement| {
+        let mut layout = layout_modify_measurement.lock().unwrap();
+        Ok(layout
+            .measured(measurement.qubit)
+            .map(|qubit| QubitMeasurementResult {
+                qubit,
+                ..measurement
+            })
+            .into_iter()
+            .collect())
+    });
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
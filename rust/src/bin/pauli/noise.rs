@@ -0,0 +1,257 @@
+//! Pauli error rates and their configuration.
+
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    types::{ArbData, Gate, GateType, QubitRef},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The probabilities of an X, Y, or Z error occurring on a qubit.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PauliRates {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl PauliRates {
+    /// Constructs a set of error rates, verifying that each rate is within
+    /// [0, 1] and that their sum does not exceed 1.
+    pub fn new(x: f64, y: f64, z: f64) -> Result<PauliRates> {
+        for p in &[x, y, z] {
+            if !(0.0..=1.0).contains(p) {
+                return inv_arg(format!("error rate {} is not within [0, 1]", p));
+            }
+        }
+        if x + y + z > 1.0 {
+            return inv_arg(format!(
+                "the sum of the error rates ({}) exceeds 1",
+                x + y + z
+            ));
+        }
+        Ok(PauliRates { x, y, z })
+    }
+
+    /// Constructs the error rates of a depolarizing channel, where each
+    /// Pauli error occurs with probability p/3.
+    pub fn depolarizing(p: f64) -> Result<PauliRates> {
+        if !(0.0..=1.0).contains(&p) {
+            return inv_arg(format!("error rate {} is not within [0, 1]", p));
+        }
+        PauliRates::new(p / 3.0, p / 3.0, p / 3.0)
+    }
+
+    /// Samples an error using a uniformly distributed random number in
+    /// [0, 1).
+    pub fn sample(&self, random: f64) -> Option<Pauli> {
+        if random < self.x {
+            Some(Pauli::X)
+        } else if random < self.x + self.y {
+            Some(Pauli::Y)
+        } else if random < self.x + self.y + self.z {
+            Some(Pauli::Z)
+        } else {
+            None
+        }
+    }
+}
+
+/// A Pauli error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    X,
+    Y,
+    Z,
+}
+
+/// Returns the key used to configure error rates for the type of the given
+/// gate: `unitary`, `measurement`, `pauli_measurement`, `prep`, or the name
+/// of a custom gate.
+pub fn gate_key(gate: &Gate) -> &str {
+    match gate.get_type() {
+        GateType::Unitary => "unitary",
+        GateType::Measurement => "measurement",
+        GateType::PauliMeasurement(_) => "pauli_measurement",
+        GateType::Prep => "prep",
+        GateType::Barrier => "barrier",
+        GateType::Custom(name) => name,
+    }
+}
+
+/// Returns the qubits on which errors are inserted before and after the given
+/// gate, respectively. Errors on measured qubits are inserted before the
+/// gate, and errors on target and control qubits after it. Pauli
+/// measurements only report their result for the first qubit, but errors on
+/// any of their qubits flip the measured parity, so these are all inserted
+/// before the gate.
+pub fn error_qubits(gate: &Gate) -> (Vec<QubitRef>, Vec<QubitRef>) {
+    match gate.get_type() {
+        GateType::PauliMeasurement(_) => (gate.get_targets().to_vec(), vec![]),
+        _ => (
+            gate.get_measures().to_vec(),
+            gate.get_targets()
+                .iter()
+                .chain(gate.get_controls())
+                .cloned()
+                .collect(),
+        ),
+    }
+}
+
+/// The JSON representation of the `pauli.error_rate` arb.
+///
+/// Either `p` is specified for a depolarizing error, or any of `x`, `y`, and
+/// `z` for the individual Pauli errors. `gate` optionally restricts the rates
+/// to a gate type, and `qubits` to a set of qubits.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateSpecification {
+    p: Option<f64>,
+    x: Option<f64>,
+    y: Option<f64>,
+    z: Option<f64>,
+    gate: Option<String>,
+    qubits: Option<Vec<u64>>,
+}
+
+/// Keeps track of the error rates for each qubit and gate type.
+///
+/// The most specific configured rates apply: rates for a qubit and gate type
+/// take precedence over rates for just the qubit, which take precedence over
+/// rates for just the gate type, which take precedence over the default
+/// rates.
+#[derive(Debug, Default)]
+pub struct NoiseModel {
+    rates: HashMap<(Option<QubitRef>, Option<String>), PauliRates>,
+}
+
+impl NoiseModel {
+    /// Configures error rates based on the JSON data of a `pauli.error_rate`
+    /// arb.
+    pub fn configure(&mut self, data: &ArbData) -> Result<()> {
+        let spec: RateSpecification = serde_json::from_str(&data.get_json()?)?;
+        let rates = match (spec.p, spec.x, spec.y, spec.z) {
+            (Some(p), None, None, None) => PauliRates::depolarizing(p)?,
+            (None, x, y, z) if x.is_some() || y.is_some() || z.is_some() => {
+                PauliRates::new(x.unwrap_or(0.0), y.unwrap_or(0.0), z.unwrap_or(0.0))?
+            }
+            _ => return inv_arg(
+                "error rates must be specified using either \"p\" or \"x\", \"y\", and/or \"z\"",
+            ),
+        };
+        match spec.qubits {
+            Some(qubits) => {
+                for qubit in qubits {
+                    let qubit = QubitRef::from_foreign(qubit)
+                        .map_or_else(|| inv_arg("qubit references cannot be zero"), Ok)?;
+                    self.rates.insert((Some(qubit), spec.gate.clone()), rates);
+                }
+            }
+            None => {
+                self.rates.insert((None, spec.gate), rates);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all configured error rates.
+    pub fn clear(&mut self) {
+        self.rates.clear();
+    }
+
+    /// Forgets the rates configured for a freed qubit.
+    pub fn forget(&mut self, qubit: QubitRef) {
+        self.rates.retain(|(q, _), _| *q != Some(qubit));
+    }
+
+    /// Returns the error rates for the given qubit and gate type.
+    pub fn get(&self, qubit: QubitRef, gate: &str) -> Option<&PauliRates> {
+        let gate = Some(gate.to_string());
+        self.rates
+            .get(&(Some(qubit), gate.clone()))
+            .or_else(|| self.rates.get(&(Some(qubit), None)))
+            .or_else(|| self.rates.get(&(None, gate)))
+            .or_else(|| self.rates.get(&(None, None)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::{
+        gates::UnboundUnitaryGate,
+        types::{Basis, Matrix},
+    };
+
+    #[test]
+    fn rates() {
+        let rates = PauliRates::new(0.1, 0.2, 0.3).unwrap();
+        assert_eq!(rates.sample(0.05), Some(Pauli::X));
+        assert_eq!(rates.sample(0.25), Some(Pauli::Y));
+        assert_eq!(rates.sample(0.55), Some(Pauli::Z));
+        assert_eq!(rates.sample(0.65), None);
+        assert_eq!(
+            PauliRates::new(0.5, 0.5, 0.5).unwrap_err().to_string(),
+            "Invalid argument: the sum of the error rates (1.5) exceeds 1"
+        );
+        assert_eq!(
+            PauliRates::depolarizing(-0.1).unwrap_err().to_string(),
+            "Invalid argument: error rate -0.1 is not within [0, 1]"
+        );
+    }
+
+    #[test]
+    fn model() {
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let q2 = QubitRef::from_foreign(2).unwrap();
+        let mut model = NoiseModel::default();
+        assert!(model.get(q1, "unitary").is_none());
+
+        let json = |s: &str| ArbData::from_json(s, vec![]).unwrap();
+        model.configure(&json(r#"{"p": 0.3}"#)).unwrap();
+        model
+            .configure(&json(r#"{"x": 0.5, "gate": "measurement"}"#))
+            .unwrap();
+        model
+            .configure(&json(r#"{"z": 0.2, "qubits": [2]}"#))
+            .unwrap();
+        model
+            .configure(&json(r#"{"y": 0.4, "qubits": [2], "gate": "prep"}"#))
+            .unwrap();
+
+        let depolarizing = PauliRates::depolarizing(0.3).unwrap();
+        assert_eq!(model.get(q1, "unitary"), Some(&depolarizing));
+        assert_eq!(model.get(q1, "measurement").unwrap().x, 0.5);
+        assert_eq!(model.get(q2, "measurement").unwrap().z, 0.2);
+        assert_eq!(model.get(q2, "prep").unwrap().y, 0.4);
+
+        model.forget(q2);
+        assert_eq!(model.get(q2, "prep"), Some(&depolarizing));
+        model.clear();
+        assert!(model.get(q1, "unitary").is_none());
+
+        assert!(model.configure(&json(r#"{"p": 0.1, "x": 0.1}"#)).is_err());
+        assert!(model.configure(&json(r#"{"gate": "prep"}"#)).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let q2 = QubitRef::from_foreign(2).unwrap();
+        let q3 = QubitRef::from_foreign(3).unwrap();
+
+        let cz =
+            Gate::new_unitary(vec![q2], vec![q1], Matrix::from(UnboundUnitaryGate::Z)).unwrap();
+        assert_eq!(error_qubits(&cz), (vec![], vec![q2, q1]));
+
+        let measure = Gate::new_measurement(vec![q1, q2], Matrix::from(Basis::Z)).unwrap();
+        assert_eq!(error_qubits(&measure), (vec![q1, q2], vec![]));
+
+        let parity =
+            Gate::new_pauli_measurement(vec![(q1, Basis::X), (q2, Basis::Z), (q3, Basis::Z)])
+                .unwrap();
+        assert_eq!(parity.get_measures(), &[q1]);
+        assert_eq!(error_qubits(&parity), (vec![q1, q2, q3], vec![]));
+    }
+}
@@ -0,0 +1,169 @@
+//! Operator that adds stochastic Pauli noise to the gatestream, such that
+//! noise can be simulated using any backend.
+//!
+//! For each gate passing through, a random Pauli error may be inserted on
+//! each involved qubit. Errors on measured qubits are inserted before the
+//! gate; errors on target and control qubits are inserted after the gate.
+//! Errors on all the qubits of a Pauli measurement are inserted before it.
+//! Barriers do not operate on the qubits, and are passed on without noise.
+//! The conditions of conditional gates are resolved by this operator, such
+//! that errors are only inserted for the gates that are executed. Note that
+//! this requires waiting for the results of outstanding measurements.
+//!
+//! Error rates are configured through the `pauli` arb interface, either as
+//! initialization commands or as host arbs:
+//!
+//!  - `pauli.error_rate`: sets error rates. The JSON data either specifies a
+//!    depolarizing error rate using `{"p": <probability>}`, in which case
+//!    each Pauli error occurs with probability p/3, or the individual rates
+//!    using any of `"x"`, `"y"`, and `"z"`. An optional `"gate"` entry
+//!    restricts the rates to `unitary`, `measurement`, or `prep` gates, or to
+//!    custom gates with the given name. An optional `"qubits": [...]` entry
+//!    restricts the rates to the given qubits. The most specific rates
+//!    apply; qubit-specific rates take precedence over gate-specific rates.
+//!  - `pauli.clear`: removes all error rates.
+//!  - `pauli.stats`: returns the number of inserted X, Y, and Z errors as a
+//!    JSON object of the form `{"x": <count>, "y": <count>, "z": <count>}`.
+
+mod noise;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        gates::UnboundUnitaryGate,
+        types::{ArbCmd, ArbData, Gate, GateType, Matrix, PluginMetadata, PluginType, QubitRef},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use noise::{error_qubits, gate_key, NoiseModel, Pauli};
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// The complete state of the operator.
+#[derive(Debug, Default)]
+struct Operator {
+    /// The configured error rates.
+    noise: NoiseModel,
+
+    /// The number of inserted errors of each kind.
+    stats: HashMap<Pauli, u64>,
+}
+
+impl Operator {
+    /// Handles the `pauli` arb interface.
+    fn arb(&mut self, cmd: &ArbCmd) -> Result<ArbData> {
+        if cmd.interface_identifier() != "pauli" {
+            return Ok(ArbData::default());
+        }
+        match cmd.operation_identifier() {
+            "error_rate" => {
+                self.noise.configure(cmd.data())?;
+                Ok(ArbData::default())
+            }
+            "clear" => {
+                self.noise.clear();
+                Ok(ArbData::default())
+            }
+            "stats" => {
+                let count = |pauli| self.stats.get(&pauli).cloned().unwrap_or(0);
+                let json = serde_json::json!({
+                    "x": count(Pauli::X),
+                    "y": count(Pauli::Y),
+                    "z": count(Pauli::Z),
+                });
+                ArbData::from_json(json.to_string(), vec![])
+            }
+            op => inv_arg(format!("unknown operation pauli.{}", op)),
+        }
+    }
+
+    /// Samples errors for the given qubits after (or before) a gate of the
+    /// given type, returning the gates that apply them.
+    fn sample<'a>(
+        &mut self,
+        state: &mut PluginState,
+        gate: &str,
+        qubits: impl IntoIterator<Item = &'a QubitRef>,
+    ) -> Result<Vec<Gate>> {
+        let mut errors = vec![];
+        for qubit in qubits {
+            if let Some(rates) = self.noise.get(*qubit, gate) {
+                if let Some(pauli) = rates.sample(state.random_f64()) {
+                    trace!("Inserting {:?} error on qubit {}", pauli, qubit);
+                    *self.stats.entry(pauli).or_insert(0) += 1;
+                    let matrix = Matrix::from(match pauli {
+                        Pauli::X => UnboundUnitaryGate::X,
+                        Pauli::Y => UnboundUnitaryGate::Y,
+                        Pauli::Z => UnboundUnitaryGate::Z,
+                    });
+                    errors.push(Gate::new_unitary(vec![*qubit], vec![], matrix)?);
+                }
+            }
+        }
+        Ok(errors)
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Pauli noise operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let operator = Arc::new(Mutex::new(Operator::default()));
+
+    let op_initialize = Arc::clone(&operator);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running Pauli noise operator initialization callback");
+        let mut op = op_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            op.arb(&arb_cmd)?;
+        }
+        Ok(())
+    });
+
+    let op_free = Arc::clone(&operator);
+    definition.free = Box::new(move |state, qubits| {
+        let mut op = op_free.lock().unwrap();
+        for qubit in &qubits {
+            op.noise.forget(*qubit);
+        }
+        state.free(qubits)
+    });
+
+    let op_gate = Arc::clone(&operator);
+    definition.gate = Box::new(move |state, gate| {
+        if gate.get_type() == &GateType::Barrier {
+            return state.gate(gate).map(|_| vec![]);
+        }
+        if !state.resolve_condition(&gate)? {
+            trace!("Condition of gate is false; not executing it");
+            return Ok(gate.undefined_measurements());
+        }
+        let gate = gate.without_condition();
+        let mut op = op_gate.lock().unwrap();
+        let key = gate_key(&gate).to_string();
+        let (before, after) = error_qubits(&gate);
+        let before = op.sample(state, &key, &before)?;
+        let after = op.sample(state, &key, &after)?;
+        for error in before {
+            state.gate(error)?;
+        }
+        state.gate(gate)?;
+        for error in after {
+            state.gate(error)?;
+        }
+        Ok(vec![])
+    });
+
+    let op_host_arb = Arc::clone(&operator);
+    definition.host_arb = Box::new(move |_state, cmd| op_host_arb.lock().unwrap().arb(&cmd));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
@@ -0,0 +1,110 @@
+//! Frontend that runs OpenQASM 2.0 programs.
+//!
+//! The path to the program is passed as the script argument of the plugin,
+//! such that a program with the `.qasm` extension can be run using
+//! `dqcsim <file>.qasm <backend>`. The standard gate library `qelib1.inc` is
+//! built in; other included files are read relative to the including file.
+//!
+//! Each `qreg` is allocated downstream when it is declared. The built-in `U`
+//! and `CX` gates and the gates of `qelib1.inc` are sent downstream as the
+//! corresponding unitary gates, user-defined gates are expanded, and opaque
+//! gates are sent downstream as custom gates, with JSON data of the form
+//! `{"params": [...]}`. Measurements are performed in the Z basis, `reset`
+//! is performed using a Z-basis prep gate, and barriers are ignored.
+//!
+//! The run callback returns the final contents of the classical registers as
+//! JSON data of the form `{"<creg>": [<bit 0>, <bit 1>, ...], ...}`, where
+//! each bit is 0 or 1.
+
+mod interpreter;
+mod parser;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        types::{ArbData, Gate, PluginMetadata, PluginType, QubitMeasurementValue, QubitRef},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+};
+use interpreter::{Downstream, Interpreter};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+impl Downstream for PluginState<'_> {
+    fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>> {
+        PluginState::allocate(self, num_qubits, vec![])
+    }
+
+    fn gate(&mut self, gate: Gate) -> Result<()> {
+        PluginState::gate(self, gate)
+    }
+
+    fn measurement(&mut self, qubit: QubitRef) -> Result<bool> {
+        match self.get_measurement(qubit)?.value {
+            QubitMeasurementValue::Zero => Ok(false),
+            QubitMeasurementValue::One => Ok(true),
+            QubitMeasurementValue::Undefined => inv_arg(format!(
+                "measurement result of qubit {} is undefined",
+                qubit
+            )),
+        }
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("OpenQASM frontend", "TU Delft QCE", "0.1.0"),
+    );
+
+    // The program is passed as a script file, preceding the simulator
+    // address.
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let simulator = args.pop().unwrap();
+    let path = args.pop();
+
+    let program = Arc::new(Mutex::new(None));
+
+    let program_initialize = Arc::clone(&program);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running OpenQASM frontend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        let path = match &path {
+            Some(path) => path,
+            None => return inv_arg("the OpenQASM frontend requires a program file"),
+        };
+        info!("Loading OpenQASM program {}", path);
+        program_initialize
+            .lock()
+            .unwrap()
+            .replace(parser::load(path)?);
+        Ok(())
+    });
+
+    let program_run = Arc::clone(&program);
+    definition.run = Box::new(move |state, _args| {
+        info!("Running OpenQASM frontend run callback");
+        let statements = match program_run.lock().unwrap().take() {
+            Some(statements) => statements,
+            None => return inv_arg("the program has already been run"),
+        };
+        let mut interpreter = Interpreter::default();
+        interpreter.run(state, &statements)?;
+        let registers: serde_json::Map<String, serde_json::Value> = interpreter
+            .registers(state)?
+            .into_iter()
+            .map(|(name, bits)| {
+                let bits: Vec<u8> = bits.into_iter().map(u8::from).collect();
+                (name, bits.into())
+            })
+            .collect();
+        ArbData::from_json(serde_json::Value::from(registers).to_string(), vec![])
+    });
+
+    PluginState::run(&definition, simulator).unwrap();
+}
@@ -0,0 +1,661 @@
+//! Interpreter for parsed OpenQASM 2.0 programs.
+
+use crate::parser::{
+    Argument, BinaryOp, Expr, Function, GateDefinition, Operation, Statement, StatementKind,
+};
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    gates::UnboundUnitaryGate,
+    types::{ArbData, Basis, Gate, Matrix, QubitRef},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::{FRAC_PI_2, PI},
+};
+
+/// The operations the interpreter needs from the downstream plugin.
+pub trait Downstream {
+    /// Allocates the given number of qubits.
+    fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>>;
+
+    /// Sends a gate downstream.
+    fn gate(&mut self, gate: Gate) -> Result<()>;
+
+    /// Returns the result of the most recent measurement of the given qubit.
+    fn measurement(&mut self, qubit: QubitRef) -> Result<bool>;
+}
+
+/// A signature of a standard gate: the number of parameters, the number of
+/// control qubits, and the target gate as a function of the parameters.
+type StandardGate = (usize, usize, fn(&[f64]) -> UnboundUnitaryGate<'static>);
+
+/// Returns the signature of a gate from `qelib1.inc`, or of one of the
+/// built-in `U` and `CX` gates.
+fn standard_gate(name: &str) -> Option<StandardGate> {
+    use UnboundUnitaryGate::*;
+    let gate: StandardGate = match name {
+        "U" | "u3" | "u" => (3, 0, |p| R(p[0], p[1], p[2])),
+        "u2" => (2, 0, |p| R(FRAC_PI_2, p[0], p[1])),
+        "u1" | "p" => (1, 0, |p| Phase(p[0])),
+        "u0" => (1, 0, |_| I),
+        "id" => (0, 0, |_| I),
+        "x" => (0, 0, |_| X),
+        "y" => (0, 0, |_| Y),
+        "z" => (0, 0, |_| Z),
+        "h" => (0, 0, |_| H),
+        "s" => (0, 0, |_| S),
+        "sdg" => (0, 0, |_| SDAG),
+        "t" => (0, 0, |_| T),
+        "tdg" => (0, 0, |_| TDAG),
+        "sx" => (0, 0, |_| RX90),
+        "sxdg" => (0, 0, |_| RXM90),
+        "rx" => (1, 0, |p| RX(p[0])),
+        "ry" => (1, 0, |p| RY(p[0])),
+        "rz" => (1, 0, |p| RZ(p[0])),
+        "swap" => (0, 0, |_| SWAP),
+        "CX" | "cx" => (0, 1, |_| X),
+        "cy" => (0, 1, |_| Y),
+        "cz" => (0, 1, |_| Z),
+        "ch" => (0, 1, |_| H),
+        "crx" => (1, 1, |p| RX(p[0])),
+        "cry" => (1, 1, |p| RY(p[0])),
+        "crz" => (1, 1, |p| RZ(p[0])),
+        "cu1" | "cp" => (1, 1, |p| Phase(p[0])),
+        "cu3" => (3, 1, |p| R(p[0], p[1], p[2])),
+        "cswap" => (0, 1, |_| SWAP),
+        "ccx" => (0, 2, |_| X),
+        _ => return None,
+    };
+    Some(gate)
+}
+
+/// Evaluates an expression, returning `None` if it refers to an unknown
+/// parameter.
+fn evaluate(expr: &Expr, params: &HashMap<&str, f64>) -> Option<f64> {
+    Some(match expr {
+        Expr::Real(value) => *value,
+        Expr::Pi => PI,
+        Expr::Param(name) => *params.get(name.as_str())?,
+        Expr::Neg(expr) => -evaluate(expr, params)?,
+        Expr::Binary(op, lhs, rhs) => {
+            let lhs = evaluate(lhs, params)?;
+            let rhs = evaluate(rhs, params)?;
+            match op {
+                BinaryOp::Add => lhs + rhs,
+                BinaryOp::Sub => lhs - rhs,
+                BinaryOp::Mul => lhs * rhs,
+                BinaryOp::Div => lhs / rhs,
+                BinaryOp::Pow => lhs.powf(rhs),
+            }
+        }
+        Expr::Call(function, expr) => {
+            let value = evaluate(expr, params)?;
+            match function {
+                Function::Sin => value.sin(),
+                Function::Cos => value.cos(),
+                Function::Tan => value.tan(),
+                Function::Exp => value.exp(),
+                Function::Ln => value.ln(),
+                Function::Sqrt => value.sqrt(),
+            }
+        }
+    })
+}
+
+/// Returns an error at the given location.
+fn error<T>(location: &str, message: impl AsRef<str>) -> Result<T> {
+    inv_arg(format!("{}: {}", location, message.as_ref()))
+}
+
+/// A classical bit.
+#[derive(Debug, Clone, Copy)]
+enum Bit {
+    /// A known value.
+    Value(bool),
+    /// The result of the most recent measurement of the given qubit, which
+    /// has not been requested from downstream yet.
+    Measured(QubitRef),
+}
+
+/// Executes OpenQASM statements.
+///
+/// Measurement results are only requested from downstream when the value of
+/// a classical register is needed, such that gates can be pipelined.
+#[derive(Debug, Default)]
+pub struct Interpreter {
+    /// The quantum registers.
+    qregs: HashMap<String, Vec<QubitRef>>,
+    /// The classical registers, in declaration order.
+    cregs: Vec<(String, Vec<Bit>)>,
+    /// The user-defined and opaque gates.
+    gates: HashMap<String, GateDefinition>,
+    /// Whether `qelib1.inc` has been included.
+    qelib: bool,
+}
+
+impl Interpreter {
+    /// Executes the given statements.
+    pub fn run(
+        &mut self,
+        downstream: &mut impl Downstream,
+        statements: &[Statement],
+    ) -> Result<()> {
+        for statement in statements {
+            self.execute(downstream, statement)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the contents of the classical registers, in declaration order.
+    pub fn registers(
+        &mut self,
+        downstream: &mut impl Downstream,
+    ) -> Result<Vec<(String, Vec<bool>)>> {
+        self.resolve(downstream, |_| true)?;
+        Ok(self
+            .cregs
+            .iter()
+            .map(|(name, bits)| {
+                let bits = bits
+                    .iter()
+                    .map(|bit| match bit {
+                        Bit::Value(value) => *value,
+                        Bit::Measured(_) => unreachable!(),
+                    })
+                    .collect();
+                (name.clone(), bits)
+            })
+            .collect())
+    }
+
+    /// Requests the pending measurement results of the qubits selected by
+    /// the given predicate from downstream.
+    fn resolve(
+        &mut self,
+        downstream: &mut impl Downstream,
+        select: impl Fn(QubitRef) -> bool,
+    ) -> Result<()> {
+        for (_, bits) in self.cregs.iter_mut() {
+            for bit in bits.iter_mut() {
+                if let Bit::Measured(qubit) = *bit {
+                    if select(qubit) {
+                        *bit = Bit::Value(downstream.measurement(qubit)?);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a gate with the given name can be used.
+    fn is_defined(&self, name: &str) -> bool {
+        self.gates.contains_key(name)
+            || (standard_gate(name).is_some() && (self.qelib || name == "U" || name == "CX"))
+    }
+
+    /// Returns the qubits referred to by an argument.
+    fn qubits(&self, location: &str, argument: &Argument) -> Result<Vec<QubitRef>> {
+        let (name, index) = match argument {
+            Argument::Register(name) => (name, None),
+            Argument::Bit(name, index) => (name, Some(*index)),
+        };
+        let qubits = match self.qregs.get(name) {
+            Some(qubits) => qubits,
+            None => return error(location, format!("undefined quantum register {}", name)),
+        };
+        match index {
+            None => Ok(qubits.clone()),
+            Some(index) if index < qubits.len() => Ok(vec![qubits[index]]),
+            Some(index) => error(
+                location,
+                format!(
+                    "index {} is out of range for quantum register {}",
+                    index, name
+                ),
+            ),
+        }
+    }
+
+    /// Returns the index of the classical register and the bit indices
+    /// referred to by an argument.
+    fn bits(&self, location: &str, argument: &Argument) -> Result<(usize, Vec<usize>)> {
+        let (name, index) = match argument {
+            Argument::Register(name) => (name, None),
+            Argument::Bit(name, index) => (name, Some(*index)),
+        };
+        let register = match self.cregs.iter().position(|(n, _)| n == name) {
+            Some(register) => register,
+            None => return error(location, format!("undefined classical register {}", name)),
+        };
+        let size = self.cregs[register].1.len();
+        match index {
+            None => Ok((register, (0..size).collect())),
+            Some(index) if index < size => Ok((register, vec![index])),
+            Some(index) => error(
+                location,
+                format!(
+                    "index {} is out of range for classical register {}",
+                    index, name
+                ),
+            ),
+        }
+    }
+
+    /// Declares a register, checking that its name is unique.
+    fn declare(&self, location: &str, name: &str) -> Result<()> {
+        if self.qregs.contains_key(name) || self.cregs.iter().any(|(n, _)| n == name) {
+            error(location, format!("register {} is already defined", name))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Executes a statement.
+    fn execute(&mut self, downstream: &mut impl Downstream, statement: &Statement) -> Result<()> {
+        let location = statement.location.as_str();
+        match &statement.kind {
+            StatementKind::IncludeQelib => {
+                self.qelib = true;
+            }
+            StatementKind::QReg(name, size) => {
+                self.declare(location, name)?;
+                let qubits = downstream.allocate(*size)?;
+                self.qregs.insert(name.clone(), qubits);
+            }
+            StatementKind::CReg(name, size) => {
+                self.declare(location, name)?;
+                self.cregs
+                    .push((name.clone(), vec![Bit::Value(false); *size]));
+            }
+            StatementKind::Gate(definition) => self.define(location, definition)?,
+            StatementKind::Operation(operation) => {
+                self.operation(downstream, location, operation)?
+            }
+            StatementKind::If(name, value, operation) => {
+                let value = *value;
+                let (register, indices) = self.bits(location, &Argument::Register(name.clone()))?;
+                let qubits: HashSet<QubitRef> = self.cregs[register]
+                    .1
+                    .iter()
+                    .filter_map(|bit| match bit {
+                        Bit::Measured(qubit) => Some(*qubit),
+                        Bit::Value(_) => None,
+                    })
+                    .collect();
+                self.resolve(downstream, |qubit| qubits.contains(&qubit))?;
+                let bits = &self.cregs[register].1;
+                let equal = indices.into_iter().all(|index| {
+                    let expected = index < 64 && (value >> index) & 1 == 1;
+                    matches!(bits[index], Bit::Value(bit) if bit == expected)
+                }) && (bits.len() >= 64 || value >> bits.len() == 0);
+                if equal {
+                    self.operation(downstream, location, operation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Defines a gate, checking that it only uses previously defined gates.
+    fn define(&mut self, location: &str, definition: &GateDefinition) -> Result<()> {
+        if self.is_defined(&definition.name) {
+            return error(
+                location,
+                format!("gate {} is already defined", definition.name),
+            );
+        }
+        for operation in definition.body.iter().flatten() {
+            if let Operation::Gate { name, .. } = operation {
+                if !self.is_defined(name) {
+                    return error(location, format!("undefined gate {}", name));
+                }
+            }
+        }
+        self.gates
+            .insert(definition.name.clone(), definition.clone());
+        Ok(())
+    }
+
+    /// Executes a top-level quantum operation.
+    fn operation(
+        &mut self,
+        downstream: &mut impl Downstream,
+        location: &str,
+        operation: &Operation,
+    ) -> Result<()> {
+        match operation {
+            Operation::Gate { name, params, args } => {
+                let params = params
+                    .iter()
+                    .map(|param| match evaluate(param, &HashMap::new()) {
+                        Some(value) => Ok(value),
+                        None => error(
+                            location,
+                            "parameters can only be used within gate definitions",
+                        ),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let resolved = args
+                    .iter()
+                    .map(|arg| self.qubits(location, arg))
+                    .collect::<Result<Vec<_>>>()?;
+
+                // Registers are broadcast over, and single qubits are reused
+                // for each application.
+                let mut size = None;
+                for (arg, qubits) in args.iter().zip(resolved.iter()) {
+                    if let Argument::Register(register) = arg {
+                        if matches!(size, Some(size) if qubits.len() != size) {
+                            return error(
+                                location,
+                                format!(
+                                    "quantum register {} differs in size from the other arguments",
+                                    register
+                                ),
+                            );
+                        }
+                        size = Some(qubits.len());
+                    }
+                }
+                for index in 0..size.unwrap_or(1) {
+                    let qubits = resolved
+                        .iter()
+                        .map(|qubits| qubits[if qubits.len() == 1 { 0 } else { index }])
+                        .collect();
+                    self.apply(downstream, location, name, &params, qubits)?;
+                }
+            }
+            Operation::Measure(qubits, bits) => {
+                let qubits = self.qubits(location, qubits)?;
+                let (register, indices) = self.bits(location, bits)?;
+                if qubits.len() != indices.len() {
+                    return error(location, "quantum and classical arguments differ in size");
+                }
+
+                // Results of earlier measurements of these qubits that have
+                // not been requested yet would be overwritten.
+                self.resolve(downstream, |qubit| qubits.contains(&qubit))?;
+                downstream.gate(Gate::new_measurement(
+                    qubits.clone(),
+                    Matrix::from(Basis::Z),
+                )?)?;
+                for (qubit, index) in qubits.into_iter().zip(indices) {
+                    self.cregs[register].1[index] = Bit::Measured(qubit);
+                }
+            }
+            Operation::Reset(qubits) => {
+                let qubits = self.qubits(location, qubits)?;
+                downstream.gate(Gate::new_prep(qubits, Matrix::from(Basis::Z))?)?;
+            }
+            Operation::Barrier(args) => {
+                for arg in args {
+                    self.qubits(location, arg)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a gate to the given qubits, expanding user-defined gates.
+    fn apply(
+        &self,
+        downstream: &mut impl Downstream,
+        location: &str,
+        name: &str,
+        params: &[f64],
+        qubits: Vec<QubitRef>,
+    ) -> Result<()> {
+        if qubits.iter().collect::<HashSet<_>>().len() != qubits.len() {
+            return error(
+                location,
+                format!("gate {} is applied to the same qubit more than once", name),
+            );
+        }
+
+        if let Some(definition) = self.gates.get(name) {
+            if params.len() != definition.params.len() || qubits.len() != definition.qubits.len() {
+                return error(
+                    location,
+                    format!(
+                        "gate {} expects {} parameter(s) and {} qubit(s)",
+                        name,
+                        definition.params.len(),
+                        definition.qubits.len()
+                    ),
+                );
+            }
+            let body = match &definition.body {
+                Some(body) => body,
+                None => {
+                    // Opaque gates are passed downstream as custom gates.
+                    let data = ArbData::from_json(
+                        serde_json::json!({ "params": params }).to_string(),
+                        vec![],
+                    )?;
+                    return downstream.gate(Gate::new_custom(
+                        name,
+                        qubits,
+                        vec![],
+                        vec![],
+                        None::<Matrix>,
+                        data,
+                    )?);
+                }
+            };
+            let param_values: HashMap<&str, f64> = definition
+                .params
+                .iter()
+                .map(String::as_str)
+                .zip(params.iter().cloned())
+                .collect();
+            let qubit_values: HashMap<&str, QubitRef> = definition
+                .qubits
+                .iter()
+                .map(String::as_str)
+                .zip(qubits)
+                .collect();
+            for operation in body {
+                if let Operation::Gate {
+                    name: inner,
+                    params,
+                    args,
+                } = operation
+                {
+                    let params = params
+                        .iter()
+                        .map(|param| match evaluate(param, &param_values) {
+                            Some(value) => Ok(value),
+                            None => {
+                                error(location, format!("undefined parameter in gate {}", name))
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    let qubits = args
+                        .iter()
+                        .map(|arg| match arg {
+                            Argument::Register(arg) if qubit_values.contains_key(arg.as_str()) => {
+                                Ok(qubit_values[arg.as_str()])
+                            }
+                            _ => error(
+                                location,
+                                format!("undefined qubit argument in gate {}", name),
+                            ),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    self.apply(downstream, location, inner, &params, qubits)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let (num_params, num_controls, gate) = match standard_gate(name) {
+            Some(gate) if self.is_defined(name) => gate,
+            _ => return error(location, format!("undefined gate {}", name)),
+        };
+        if params.len() != num_params {
+            return error(
+                location,
+                format!("gate {} expects {} parameter(s)", name, num_params),
+            );
+        }
+        let matrix = Matrix::from(gate(params));
+        let num_qubits = num_controls + matrix.num_qubits().unwrap();
+        if qubits.len() != num_qubits {
+            return error(
+                location,
+                format!("gate {} expects {} qubit(s)", name, num_qubits),
+            );
+        }
+        let (controls, targets) = qubits.split_at(num_controls);
+        downstream.gate(Gate::new_unitary(
+            targets.to_vec(),
+            controls.to_vec(),
+            matrix,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use std::path::Path;
+
+    /// Downstream that records the gates it receives, and measures all
+    /// qubits as one.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        num_qubits: usize,
+        gates: Vec<Gate>,
+        measurements: Vec<QubitRef>,
+    }
+
+    impl Downstream for Recorder {
+        fn allocate(&mut self, num_qubits: usize) -> Result<Vec<QubitRef>> {
+            let qubits = (self.num_qubits + 1..=self.num_qubits + num_qubits)
+                .map(|qubit| QubitRef::from_foreign(qubit as u64).unwrap())
+                .collect();
+            self.num_qubits += num_qubits;
+            Ok(qubits)
+        }
+
+        fn gate(&mut self, gate: Gate) -> Result<()> {
+            self.gates.push(gate);
+            Ok(())
+        }
+
+        fn measurement(&mut self, qubit: QubitRef) -> Result<bool> {
+            self.measurements.push(qubit);
+            Ok(true)
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn run(source: &str) -> Result<(Recorder, Vec<(String, Vec<bool>)>)> {
+        let statements = parse(source, "test.qasm", Path::new("."))?;
+        let mut recorder = Recorder::default();
+        let mut interpreter = Interpreter::default();
+        interpreter.run(&mut recorder, &statements)?;
+        let registers = interpreter.registers(&mut recorder)?;
+        Ok((recorder, registers))
+    }
+
+    fn q(qubit: u64) -> QubitRef {
+        QubitRef::from_foreign(qubit).unwrap()
+    }
+
+    fn unitary(targets: Vec<u64>, controls: Vec<u64>, gate: UnboundUnitaryGate) -> Gate {
+        Gate::new_unitary(
+            targets.into_iter().map(q),
+            controls.into_iter().map(q),
+            Matrix::from(gate),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn measure_and_if() {
+        let (recorder, registers) = run(r#"
+            OPENQASM 2.0;
+            include "qelib1.inc";
+            qreg q[2];
+            creg c[2];
+            h q[0];
+            cx q[0], q[1];
+            measure q -> c;
+            if (c == 3) x q[0];
+            if (c == 1) x q[1];
+            measure q[0] -> c[0];
+            "#)
+        .unwrap();
+        let z = Matrix::from(Basis::Z);
+        assert_eq!(
+            recorder.gates,
+            vec![
+                unitary(vec![1], vec![], UnboundUnitaryGate::H),
+                unitary(vec![2], vec![1], UnboundUnitaryGate::X),
+                Gate::new_measurement(vec![q(1), q(2)], z.clone()).unwrap(),
+                unitary(vec![1], vec![], UnboundUnitaryGate::X),
+                Gate::new_measurement(vec![q(1)], z).unwrap(),
+            ]
+        );
+        assert_eq!(recorder.measurements, vec![q(1), q(2), q(1)]);
+        assert_eq!(registers, vec![("c".to_string(), vec![true, true])]);
+    }
+
+    #[test]
+    fn gate_definitions() {
+        let (recorder, _) = run(r#"
+            include "qelib1.inc";
+            qreg a[2];
+            qreg b[2];
+            gate g(theta) x, y { rz(theta / 2) x; CX x, y; barrier x, y; }
+            opaque o(phi) x;
+            g(pi) a, b[1];
+            o(1) a[0];
+            "#)
+        .unwrap();
+        assert_eq!(
+            recorder.gates,
+            vec![
+                unitary(vec![1], vec![], UnboundUnitaryGate::RZ(PI / 2.0)),
+                unitary(vec![4], vec![1], UnboundUnitaryGate::X),
+                unitary(vec![2], vec![], UnboundUnitaryGate::RZ(PI / 2.0)),
+                unitary(vec![4], vec![2], UnboundUnitaryGate::X),
+                Gate::new_custom(
+                    "o",
+                    vec![q(1)],
+                    vec![],
+                    vec![],
+                    None::<Matrix>,
+                    ArbData::from_json("{\"params\":[1.0]}", vec![]).unwrap()
+                )
+                .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            run("qreg q[1];\nh q[0];").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:2: undefined gate h"
+        );
+        assert_eq!(
+            run("qreg q[2];\nqreg r[3];\nCX q, r;").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:3: quantum register r differs in size from the other arguments"
+        );
+        assert_eq!(
+            run("qreg q[1];\nqreg r[2];\nCX q, r;").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:3: quantum register r differs in size from the other arguments"
+        );
+        assert_eq!(
+            run("qreg q[2];\nCX q[1], q[1];").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:2: gate CX is applied to the same qubit more than once"
+        );
+        assert_eq!(
+            run("gate g a { g a; }").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:1: undefined gate g"
+        );
+    }
+}
@@ -0,0 +1,688 @@
+//! Lexer and parser for OpenQASM 2.0 programs.
+
+use dqcsim::common::error::{inv_arg, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A lexical token.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier or keyword.
+    Ident(String),
+    /// A real number literal.
+    Real(f64),
+    /// A nonnegative integer literal.
+    Int(u64),
+    /// A string literal, used for include statements.
+    Str(String),
+    /// A symbol, such as `;` or `->`.
+    Symbol(&'static str),
+}
+
+/// The symbols of the language. Longer symbols that start with the same
+/// character as a shorter symbol must come first.
+const SYMBOLS: &[&str] = &[
+    "->", "==", ";", ",", "(", ")", "[", "]", "{", "}", "+", "-", "*", "/", "^",
+];
+
+/// Splits the source of a program into tokens and their line numbers.
+fn tokenize(source: &str, name: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = vec![];
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = match line.find("//") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+        let mut rest = line.trim_start();
+        while !rest.is_empty() {
+            let c = rest.chars().next().unwrap();
+            let length = if c.is_ascii_alphabetic() {
+                let length = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+                tokens.push((Token::Ident(rest[..length].to_string()), line_number));
+                length
+            } else if c.is_ascii_digit() || c == '.' {
+                let mut length = rest
+                    .find(|c: char| !c.is_ascii_digit() && c != '.')
+                    .unwrap_or(rest.len());
+                if rest[length..].starts_with(['e', 'E']) {
+                    let exponent = &rest[length + 1..];
+                    let sign = exponent.starts_with(['+', '-']) as usize;
+                    length += 1
+                        + sign
+                        + exponent[sign..]
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or_else(|| exponent.len() - sign);
+                }
+                let literal = &rest[..length];
+                let token = if literal.chars().all(|c| c.is_ascii_digit()) {
+                    literal.parse().ok().map(Token::Int)
+                } else {
+                    literal.parse().ok().map(Token::Real)
+                };
+                let token = match token {
+                    Some(token) => token,
+                    None => {
+                        return inv_arg(format!(
+                            "{}:{}: invalid number {}",
+                            name, line_number, literal
+                        ))
+                    }
+                };
+                tokens.push((token, line_number));
+                length
+            } else if c == '"' {
+                let length = match rest[1..].find('"') {
+                    Some(end) => end + 2,
+                    None => {
+                        return inv_arg(format!("{}:{}: unterminated string", name, line_number))
+                    }
+                };
+                tokens.push((Token::Str(rest[1..length - 1].to_string()), line_number));
+                length
+            } else if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(*s)) {
+                tokens.push((Token::Symbol(symbol), line_number));
+                symbol.len()
+            } else {
+                return inv_arg(format!(
+                    "{}:{}: unexpected character '{}'",
+                    name, line_number, c
+                ));
+            };
+            rest = rest[length..].trim_start();
+        }
+    }
+    Ok(tokens)
+}
+
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// A unary function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Ln,
+    Sqrt,
+}
+
+/// A real-valued expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// A literal.
+    Real(f64),
+    /// The constant π.
+    Pi,
+    /// A parameter of the enclosing gate definition.
+    Param(String),
+    /// Negation.
+    Neg(Box<Expr>),
+    /// A binary operation.
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// A function call.
+    Call(Function, Box<Expr>),
+}
+
+/// A quantum or classical argument, referring to either a whole register or
+/// a single bit of a register. Within gate definitions, arguments always
+/// refer to whole "registers", which are the qubit parameters of the gate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Argument {
+    Register(String),
+    Bit(String, usize),
+}
+
+/// A quantum operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Application of a gate, including the built-in `U` and `CX` gates.
+    Gate {
+        name: String,
+        params: Vec<Expr>,
+        args: Vec<Argument>,
+    },
+    /// Measurement of a qubit into a classical bit.
+    Measure(Argument, Argument),
+    /// Reset of a qubit to the ground state.
+    Reset(Argument),
+    /// A barrier.
+    Barrier(Vec<Argument>),
+}
+
+/// A gate definition, or an opaque gate declaration if there is no body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateDefinition {
+    pub name: String,
+    pub params: Vec<String>,
+    pub qubits: Vec<String>,
+    pub body: Option<Vec<Operation>>,
+}
+
+/// A statement of a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementKind {
+    /// Inclusion of the standard gate library `qelib1.inc`. Other includes
+    /// are expanded while loading the program.
+    IncludeQelib,
+    /// A quantum register declaration.
+    QReg(String, usize),
+    /// A classical register declaration.
+    CReg(String, usize),
+    /// A gate definition or opaque gate declaration.
+    Gate(GateDefinition),
+    /// A quantum operation.
+    Operation(Operation),
+    /// A quantum operation that is only performed if the value of the
+    /// classical register equals the given value.
+    If(String, u64, Operation),
+}
+
+/// A statement of a program, along with its location for error reporting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub location: String,
+    pub kind: StatementKind,
+}
+
+/// A statement that still has to be processed by the loader.
+enum Parsed {
+    Include(String),
+    Statement(StatementKind),
+}
+
+/// Recursive-descent parser over a token stream.
+struct Parser<'a> {
+    name: &'a str,
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns the line number of the current token.
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+
+    /// Returns an error at the current token.
+    fn error<T>(&self, message: impl AsRef<str>) -> Result<T> {
+        inv_arg(format!(
+            "{}:{}: {}",
+            self.name,
+            self.line(),
+            message.as_ref()
+        ))
+    }
+
+    /// Returns the current token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Consumes and returns the current token.
+    fn next(&mut self) -> Result<Token> {
+        match self.tokens.get(self.position) {
+            Some((token, _)) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => self.error("unexpected end of file"),
+        }
+    }
+
+    /// Consumes the given symbol if it is the current token.
+    fn accept(&mut self, symbol: &str) -> bool {
+        if let Some(Token::Symbol(s)) = self.peek() {
+            if *s == symbol {
+                self.position += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Consumes the given symbol, or returns an error.
+    fn expect(&mut self, symbol: &str) -> Result<()> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            self.error(format!("expected '{}'", symbol))
+        }
+    }
+
+    /// Consumes an identifier.
+    fn ident(&mut self) -> Result<String> {
+        match self.next()? {
+            Token::Ident(ident) => Ok(ident),
+            _ => {
+                self.position -= 1;
+                self.error("expected an identifier")
+            }
+        }
+    }
+
+    /// Consumes a nonnegative integer.
+    fn int(&mut self) -> Result<u64> {
+        match self.next()? {
+            Token::Int(value) => Ok(value),
+            _ => {
+                self.position -= 1;
+                self.error("expected an integer")
+            }
+        }
+    }
+
+    /// Parses a comma-separated list of at least one item.
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.accept(",") {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Parses an optional parenthesized, comma-separated list.
+    fn parenthesized<T>(&mut self, item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        if !self.accept("(") {
+            return Ok(vec![]);
+        }
+        if self.accept(")") {
+            return Ok(vec![]);
+        }
+        let items = self.list(item)?;
+        self.expect(")")?;
+        Ok(items)
+    }
+
+    /// Parses an argument, i.e. a register or a bit of a register.
+    fn argument(&mut self) -> Result<Argument> {
+        let name = self.ident()?;
+        if self.accept("[") {
+            let index = self.int()? as usize;
+            self.expect("]")?;
+            Ok(Argument::Bit(name, index))
+        } else {
+            Ok(Argument::Register(name))
+        }
+    }
+
+    /// Parses an expression.
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.accept("+") {
+                BinaryOp::Add
+            } else if self.accept("-") {
+                BinaryOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    /// Parses a product or quotient.
+    fn term(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.accept("*") {
+                BinaryOp::Mul
+            } else if self.accept("/") {
+                BinaryOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    /// Parses a negation, or a power.
+    fn unary(&mut self) -> Result<Expr> {
+        if self.accept("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.primary()?;
+        if self.accept("^") {
+            Ok(Expr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    /// Parses a literal, parameter, function call, or parenthesized
+    /// expression.
+    fn primary(&mut self) -> Result<Expr> {
+        match self.next()? {
+            Token::Real(value) => Ok(Expr::Real(value)),
+            Token::Int(value) => Ok(Expr::Real(value as f64)),
+            Token::Symbol("(") => {
+                let expr = self.expr()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Ident(ident) => {
+                let function = match ident.as_str() {
+                    "pi" => return Ok(Expr::Pi),
+                    "sin" => Function::Sin,
+                    "cos" => Function::Cos,
+                    "tan" => Function::Tan,
+                    "exp" => Function::Exp,
+                    "ln" => Function::Ln,
+                    "sqrt" => Function::Sqrt,
+                    _ => return Ok(Expr::Param(ident)),
+                };
+                self.expect("(")?;
+                let expr = self.expr()?;
+                self.expect(")")?;
+                Ok(Expr::Call(function, Box::new(expr)))
+            }
+            _ => {
+                self.position -= 1;
+                self.error("expected an expression")
+            }
+        }
+    }
+
+    /// Parses a quantum operation, after its first identifier.
+    fn operation(&mut self, keyword: String) -> Result<Operation> {
+        let operation = match keyword.as_str() {
+            "measure" => {
+                let qubit = self.argument()?;
+                self.expect("->")?;
+                Operation::Measure(qubit, self.argument()?)
+            }
+            "reset" => Operation::Reset(self.argument()?),
+            "barrier" => Operation::Barrier(self.list(Self::argument)?),
+            _ => Operation::Gate {
+                name: keyword,
+                params: self.parenthesized(Self::expr)?,
+                args: self.list(Self::argument)?,
+            },
+        };
+        self.expect(";")?;
+        Ok(operation)
+    }
+
+    /// Parses a gate definition or opaque gate declaration, after the
+    /// keyword.
+    fn gate(&mut self, opaque: bool) -> Result<GateDefinition> {
+        let name = self.ident()?;
+        let params = self.parenthesized(Self::ident)?;
+        let qubits = self.list(Self::ident)?;
+        let body = if opaque {
+            self.expect(";")?;
+            None
+        } else {
+            self.expect("{")?;
+            let mut body = vec![];
+            while !self.accept("}") {
+                let keyword = self.ident()?;
+                let operation = self.operation(keyword)?;
+                match &operation {
+                    Operation::Gate { args, .. } | Operation::Barrier(args) => {
+                        if args.iter().any(|arg| matches!(arg, Argument::Bit(_, _))) {
+                            return self.error("gate bodies cannot index qubits");
+                        }
+                    }
+                    _ => {
+                        return self.error("gate bodies can only contain gates and barriers");
+                    }
+                }
+                body.push(operation);
+            }
+            Some(body)
+        };
+        Ok(GateDefinition {
+            name,
+            params,
+            qubits,
+            body,
+        })
+    }
+
+    /// Parses a statement.
+    fn statement(&mut self) -> Result<Parsed> {
+        let keyword = self.ident()?;
+        let kind = match keyword.as_str() {
+            "include" => {
+                let file = match self.next()? {
+                    Token::Str(file) => file,
+                    _ => return self.error("expected a file name"),
+                };
+                self.expect(";")?;
+                return Ok(Parsed::Include(file));
+            }
+            "qreg" | "creg" => {
+                let name = self.ident()?;
+                self.expect("[")?;
+                let size = self.int()? as usize;
+                self.expect("]")?;
+                self.expect(";")?;
+                if keyword == "qreg" {
+                    StatementKind::QReg(name, size)
+                } else {
+                    StatementKind::CReg(name, size)
+                }
+            }
+            "gate" => StatementKind::Gate(self.gate(false)?),
+            "opaque" => StatementKind::Gate(self.gate(true)?),
+            "if" => {
+                self.expect("(")?;
+                let creg = self.ident()?;
+                self.expect("==")?;
+                let value = self.int()?;
+                self.expect(")")?;
+                let keyword = self.ident()?;
+                StatementKind::If(creg, value, self.operation(keyword)?)
+            }
+            "OPENQASM" => return self.error("unexpected version header"),
+            _ => StatementKind::Operation(self.operation(keyword)?),
+        };
+        Ok(Parsed::Statement(kind))
+    }
+}
+
+/// Parses the source of a program, expanding includes relative to the given
+/// directory. The name of the source is used for error reporting.
+pub fn parse(source: &str, name: &str, directory: &Path) -> Result<Vec<Statement>> {
+    let mut parser = Parser {
+        name,
+        tokens: tokenize(source, name)?,
+        position: 0,
+    };
+
+    // The version header is optional in included files.
+    if parser.peek() == Some(&Token::Ident("OPENQASM".to_string())) {
+        parser.position += 1;
+        match parser.next() {
+            Ok(Token::Real(version)) if version.floor() == 2.0 => {}
+            _ => return parser.error("only OpenQASM 2 is supported"),
+        }
+        parser.expect(";")?;
+    }
+
+    let mut statements = vec![];
+    while parser.peek().is_some() {
+        let location = format!("{}:{}", name, parser.line());
+        match parser.statement()? {
+            Parsed::Include(file) if file == "qelib1.inc" => statements.push(Statement {
+                location,
+                kind: StatementKind::IncludeQelib,
+            }),
+            Parsed::Include(file) => statements.extend(load(directory.join(file))?),
+            Parsed::Statement(kind) => statements.push(Statement { location, kind }),
+        }
+    }
+    Ok(statements)
+}
+
+/// Loads the program in the given file, expanding includes. The standard
+/// gate library `qelib1.inc` is built in.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Statement>> {
+    let path = path.as_ref();
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => return inv_arg(format!("failed to read {}: {}", path.display(), e)),
+    };
+    let directory = path.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+    parse(&source, &path.to_string_lossy(), &directory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(source: &str) -> Result<Vec<StatementKind>> {
+        Ok(parse(source, "test.qasm", Path::new("."))?
+            .into_iter()
+            .map(|statement| statement.kind)
+            .collect())
+    }
+
+    #[test]
+    fn statements() {
+        let program = parse_str(
+            r#"
+            OPENQASM 2.0;
+            include "qelib1.inc";
+            qreg q[2]; creg c[2];
+            gate g(theta) a, b { rz(theta / 2) a; CX a, b; barrier a, b; }
+            opaque o a;
+            U(pi, 0, -1.5e-1) q[0]; // comment
+            measure q -> c;
+            if (c == 3) reset q[1];
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            program,
+            vec![
+                StatementKind::IncludeQelib,
+                StatementKind::QReg("q".to_string(), 2),
+                StatementKind::CReg("c".to_string(), 2),
+                StatementKind::Gate(GateDefinition {
+                    name: "g".to_string(),
+                    params: vec!["theta".to_string()],
+                    qubits: vec!["a".to_string(), "b".to_string()],
+                    body: Some(vec![
+                        Operation::Gate {
+                            name: "rz".to_string(),
+                            params: vec![Expr::Binary(
+                                BinaryOp::Div,
+                                Box::new(Expr::Param("theta".to_string())),
+                                Box::new(Expr::Real(2.0))
+                            )],
+                            args: vec![Argument::Register("a".to_string())],
+                        },
+                        Operation::Gate {
+                            name: "CX".to_string(),
+                            params: vec![],
+                            args: vec![
+                                Argument::Register("a".to_string()),
+                                Argument::Register("b".to_string())
+                            ],
+                        },
+                        Operation::Barrier(vec![
+                            Argument::Register("a".to_string()),
+                            Argument::Register("b".to_string())
+                        ]),
+                    ]),
+                }),
+                StatementKind::Gate(GateDefinition {
+                    name: "o".to_string(),
+                    params: vec![],
+                    qubits: vec!["a".to_string()],
+                    body: None,
+                }),
+                StatementKind::Operation(Operation::Gate {
+                    name: "U".to_string(),
+                    params: vec![
+                        Expr::Pi,
+                        Expr::Real(0.0),
+                        Expr::Neg(Box::new(Expr::Real(0.15)))
+                    ],
+                    args: vec![Argument::Bit("q".to_string(), 0)],
+                }),
+                StatementKind::Operation(Operation::Measure(
+                    Argument::Register("q".to_string()),
+                    Argument::Register("c".to_string())
+                )),
+                StatementKind::If(
+                    "c".to_string(),
+                    3,
+                    Operation::Reset(Argument::Bit("q".to_string(), 1))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        let program = parse_str("u1(-2^2*3+sin(pi)) q;").unwrap();
+        let power = Expr::Binary(
+            BinaryOp::Pow,
+            Box::new(Expr::Real(2.0)),
+            Box::new(Expr::Real(2.0)),
+        );
+        let product = Expr::Binary(
+            BinaryOp::Mul,
+            Box::new(Expr::Neg(Box::new(power))),
+            Box::new(Expr::Real(3.0)),
+        );
+        assert_eq!(
+            program,
+            vec![StatementKind::Operation(Operation::Gate {
+                name: "u1".to_string(),
+                params: vec![Expr::Binary(
+                    BinaryOp::Add,
+                    Box::new(product),
+                    Box::new(Expr::Call(Function::Sin, Box::new(Expr::Pi)))
+                )],
+                args: vec![Argument::Register("q".to_string())],
+            })]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_str("OPENQASM 3.0;").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:1: only OpenQASM 2 is supported"
+        );
+        assert_eq!(
+            parse_str("qreg q[2];\nh q[0]").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:2: expected ';'"
+        );
+        assert_eq!(
+            parse_str("qreg q[2] $").unwrap_err().to_string(),
+            "Invalid argument: test.qasm:1: unexpected character '$'"
+        );
+        assert_eq!(
+            parse_str("gate g a { measure a -> c; }")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: test.qasm:1: gate bodies can only contain gates and barriers"
+        );
+    }
+}
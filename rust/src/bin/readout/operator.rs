@@ -0,0 +1,131 @@
+//! Operator that models asymmetric readout errors by randomly flipping
+//! measurement results as they are propagated upstream.
+//!
+//! Error rates are configured through the `readout` arb interface, either as
+//! initialization commands or as host arbs:
+//!
+//!  - `readout.error_rate`: sets the probability of reading 0 when the qubit
+//!    was measured to be 1 (`"p0_given_1"`) and the probability of reading 1
+//!    when the qubit was measured to be 0 (`"p1_given_0"`). Unspecified
+//!    probabilities default to 0. An optional `"qubits": [...]` entry
+//!    restricts the rates to the given qubits; without it, the rates replace
+//!    the rates for all qubits.
+//!  - `readout.clear`: removes all error rates.
+//!
+//! Every measurement result of a qubit with configured error rates is
+//! annotated with a `"readout_flip"` boolean in the JSON object of its
+//! `ArbData`, indicating whether the result was flipped by this operator.
+//!
+//! The conditions of conditional gates are resolved by this operator, using
+//! the flipped measurement results, such that only the gates that are
+//! executed are passed downstream.
+
+mod rates;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        types::{ArbCmd, ArbData, PluginMetadata, PluginType, QubitMeasurementValue},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use rates::ReadoutModel;
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// Handles the `readout` arb interface.
+fn readout_arb(model: &mut ReadoutModel, cmd: &ArbCmd) -> Result<ArbData> {
+    if cmd.interface_identifier() != "readout" {
+        return Ok(ArbData::default());
+    }
+    match cmd.operation_identifier() {
+        "error_rate" => {
+            model.configure(cmd.data())?;
+            Ok(ArbData::default())
+        }
+        "clear" => {
+            model.clear();
+            Ok(ArbData::default())
+        }
+        op => inv_arg(format!("unknown operation readout.{}", op)),
+    }
+}
+
+/// Records whether a measurement result was flipped in the JSON object of
+/// its `ArbData`.
+fn record_flip(data: &mut ArbData, flipped: bool) -> Result<()> {
+    let mut json: serde_json::Value = serde_json::from_str(&data.get_json()?)?;
+    if let Some(object) = json.as_object_mut() {
+        object.insert("readout_flip".to_string(), flipped.into());
+        data.set_json(json.to_string())
+    } else {
+        inv_arg("cannot record readout flip; measurement data is not a JSON object")
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Readout error operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let model = Arc::new(Mutex::new(ReadoutModel::default()));
+
+    let model_initialize = Arc::clone(&model);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running readout error operator initialization callback");
+        let mut model = model_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            readout_arb(&mut model, &arb_cmd)?;
+        }
+        Ok(())
+    });
+
+    let model_free = Arc::clone(&model);
+    definition.free = Box::new(move |state, qubits| {
+        let mut model = model_free.lock().unwrap();
+        for qubit in &qubits {
+            model.forget(*qubit);
+        }
+        state.free(qubits)
+    });
+
+    definition.gate = Box::new(|state, gate| {
+        if state.resolve_condition(&gate)? {
+            state.gate(gate.without_condition())?;
+            Ok(vec![])
+        } else {
+            trace!("Condition of gate is false; not executing it");
+            Ok(gate.undefined_measurements())
+        }
+    });
+
+    let model_modify_measurement = Arc::clone(&model);
+    definition.modify_measurement = Box::new(move |state, mut measurement| {
+        let model = model_modify_measurement.lock().unwrap();
+        if let Some(rates) = model.get(measurement.qubit) {
+            let flipped = rates.flips(measurement.value, state.random_f64());
+            if flipped {
+                trace!("Flipping measurement of qubit {}", measurement.qubit);
+                measurement.value = match measurement.value {
+                    QubitMeasurementValue::Zero => QubitMeasurementValue::One,
+                    QubitMeasurementValue::One => QubitMeasurementValue::Zero,
+                    QubitMeasurementValue::Undefined => QubitMeasurementValue::Undefined,
+                };
+            }
+            record_flip(&mut measurement.data, flipped)?;
+        }
+        Ok(vec![measurement])
+    });
+
+    let model_host_arb = Arc::clone(&model);
+    definition.host_arb =
+        Box::new(move |_state, cmd| readout_arb(&mut model_host_arb.lock().unwrap(), &cmd));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
@@ -0,0 +1,154 @@
+//! Asymmetric readout error rates and their configuration.
+
+use dqcsim::common::{
+    error::{inv_arg, Result},
+    types::{ArbData, QubitMeasurementValue, QubitRef},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The probabilities of misreading a measurement result.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReadoutRates {
+    /// The probability of reading 0 when the qubit was measured to be 1.
+    pub p0_given_1: f64,
+
+    /// The probability of reading 1 when the qubit was measured to be 0.
+    pub p1_given_0: f64,
+}
+
+impl ReadoutRates {
+    /// Constructs a set of readout error rates, verifying that both are
+    /// within [0, 1].
+    pub fn new(p0_given_1: f64, p1_given_0: f64) -> Result<ReadoutRates> {
+        for p in &[p0_given_1, p1_given_0] {
+            if !(0.0..=1.0).contains(p) {
+                return inv_arg(format!("error rate {} is not within [0, 1]", p));
+            }
+        }
+        Ok(ReadoutRates {
+            p0_given_1,
+            p1_given_0,
+        })
+    }
+
+    /// Returns whether a measurement with the given value should be flipped,
+    /// using a uniformly distributed random number in [0, 1). Undefined
+    /// measurements are never flipped.
+    pub fn flips(&self, value: QubitMeasurementValue, random: f64) -> bool {
+        match value {
+            QubitMeasurementValue::Zero => random < self.p1_given_0,
+            QubitMeasurementValue::One => random < self.p0_given_1,
+            QubitMeasurementValue::Undefined => false,
+        }
+    }
+}
+
+/// The JSON representation of the `readout.error_rate` arb.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateSpecification {
+    p0_given_1: Option<f64>,
+    p1_given_0: Option<f64>,
+    qubits: Option<Vec<u64>>,
+}
+
+/// Keeps track of the readout error rates of the qubits.
+#[derive(Debug, Default)]
+pub struct ReadoutModel {
+    /// Rates applying to all qubits without specific rates.
+    default: Option<ReadoutRates>,
+
+    /// Rates configured for specific qubits.
+    qubits: HashMap<QubitRef, ReadoutRates>,
+}
+
+impl ReadoutModel {
+    /// Configures error rates based on the JSON data of a
+    /// `readout.error_rate` arb.
+    pub fn configure(&mut self, data: &ArbData) -> Result<()> {
+        let spec: RateSpecification = serde_json::from_str(&data.get_json()?)?;
+        let rates = ReadoutRates::new(
+            spec.p0_given_1.unwrap_or(0.0),
+            spec.p1_given_0.unwrap_or(0.0),
+        )?;
+        match spec.qubits {
+            Some(qubits) => {
+                for qubit in qubits {
+                    let qubit = QubitRef::from_foreign(qubit)
+                        .map_or_else(|| inv_arg("qubit references cannot be zero"), Ok)?;
+                    self.qubits.insert(qubit, rates);
+                }
+            }
+            None => {
+                self.qubits.clear();
+                self.default.replace(rates);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all configured error rates.
+    pub fn clear(&mut self) {
+        self.default.take();
+        self.qubits.clear();
+    }
+
+    /// Forgets the rates configured for a freed qubit.
+    pub fn forget(&mut self, qubit: QubitRef) {
+        self.qubits.remove(&qubit);
+    }
+
+    /// Returns the error rates for the given qubit.
+    pub fn get(&self, qubit: QubitRef) -> Option<&ReadoutRates> {
+        self.qubits.get(&qubit).or(self.default.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates() {
+        let rates = ReadoutRates::new(0.2, 0.1).unwrap();
+        assert!(rates.flips(QubitMeasurementValue::Zero, 0.05));
+        assert!(!rates.flips(QubitMeasurementValue::Zero, 0.15));
+        assert!(rates.flips(QubitMeasurementValue::One, 0.15));
+        assert!(!rates.flips(QubitMeasurementValue::One, 0.25));
+        assert!(!rates.flips(QubitMeasurementValue::Undefined, 0.0));
+        assert_eq!(
+            ReadoutRates::new(1.1, 0.0).unwrap_err().to_string(),
+            "Invalid argument: error rate 1.1 is not within [0, 1]"
+        );
+    }
+
+    #[test]
+    fn model() {
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let q2 = QubitRef::from_foreign(2).unwrap();
+        let json = |s: &str| ArbData::from_json(s, vec![]).unwrap();
+        let mut model = ReadoutModel::default();
+        assert!(model.get(q1).is_none());
+
+        model
+            .configure(&json(r#"{"p1_given_0": 0.3, "qubits": [2]}"#))
+            .unwrap();
+        assert!(model.get(q1).is_none());
+        assert_eq!(model.get(q2).unwrap().p1_given_0, 0.3);
+
+        model.configure(&json(r#"{"p0_given_1": 0.1}"#)).unwrap();
+        assert_eq!(model.get(q1).unwrap().p0_given_1, 0.1);
+        assert_eq!(model.get(q2).unwrap().p1_given_0, 0.0);
+
+        model
+            .configure(&json(r#"{"p0_given_1": 0.5, "qubits": [2]}"#))
+            .unwrap();
+        model.forget(q2);
+        assert_eq!(model.get(q2).unwrap().p0_given_1, 0.1);
+        model.clear();
+        assert!(model.get(q2).is_none());
+
+        assert!(model.configure(&json(r#"{"p": 0.1}"#)).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Operator that records the gatestream passing through it to a trace file.
+//!
+//! The trace file is configured using a `record.file` initialization command
+//! with JSON data of the form `{"path": <path>}`. This command is required.
+//! The operator passes all requests through unchanged, while writing every
+//! pipelined gatestream request, every `ArbCmd` sent downstream, and every
+//! measurement result to the trace file. The file format is described by
+//! `dqcsim::common::protocol::TraceRecord`. Traces can be replayed using the
+//! trace replay frontend; giving the file the `.trace` extension allows it to
+//! be replayed using `dqcsim <file>.trace <backend>`.
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        protocol::{PipelinedGatestreamDown, TraceRecord, TraceWriter},
+        types::{ArbCmd, PluginMetadata, PluginType},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+};
+use serde::Deserialize;
+use std::{
+    env,
+    fs::File,
+    io::BufWriter,
+    sync::{Arc, Mutex},
+};
+
+/// The JSON representation of the `record.file` arb.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileSpecification {
+    path: String,
+}
+
+/// The trace writer of the operator, if the trace file has been opened.
+type Trace = Option<TraceWriter<BufWriter<File>>>;
+
+/// Handles the `record` initialization commands.
+fn configure(trace: &mut Trace, cmd: &ArbCmd) -> Result<()> {
+    if cmd.interface_identifier() != "record" {
+        return Ok(());
+    }
+    match cmd.operation_identifier() {
+        "file" => {
+            let spec: FileSpecification = serde_json::from_str(&cmd.data().get_json()?)?;
+            info!("Recording gatestream to {}", spec.path);
+            trace.replace(TraceWriter::new(BufWriter::new(File::create(spec.path)?))?);
+            Ok(())
+        }
+        op => inv_arg(format!("unknown operation record.{}", op)),
+    }
+}
+
+/// Writes a record to the trace file.
+fn record(trace: &Mutex<Trace>, record: TraceRecord) -> Result<()> {
+    trace.lock().unwrap().as_mut().unwrap().write(&record)
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Recording operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let trace: Arc<Mutex<Trace>> = Arc::new(Mutex::new(None));
+
+    let trace_initialize = Arc::clone(&trace);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running recording operator initialization callback");
+        let mut trace = trace_initialize.lock().unwrap();
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+            configure(&mut trace, &arb_cmd)?;
+        }
+        if trace.is_none() {
+            return inv_arg("the recording operator requires a record.file command");
+        }
+        Ok(())
+    });
+
+    let trace_allocate = Arc::clone(&trace);
+    definition.allocate = Box::new(move |state, qubits, arb_cmds| {
+        record(
+            &trace_allocate,
+            TraceRecord::Request(PipelinedGatestreamDown::Allocate(
+                qubits.len(),
+                arb_cmds.clone(),
+            )),
+        )?;
+        state.allocate(qubits.len(), arb_cmds).map(|_| ())
+    });
+
+    let trace_free = Arc::clone(&trace);
+    definition.free = Box::new(move |state, qubits| {
+        record(
+            &trace_free,
+            TraceRecord::Request(PipelinedGatestreamDown::Free(qubits.clone())),
+        )?;
+        state.free(qubits)
+    });
+
+    let trace_gate = Arc::clone(&trace);
+    definition.gate = Box::new(move |state, gate| {
+        record(
+            &trace_gate,
+            TraceRecord::Request(PipelinedGatestreamDown::Gate(gate.clone())),
+        )?;
+        state.gate(gate).map(|_| vec![])
+    });
+
+    let trace_modify_measurement = Arc::clone(&trace);
+    definition.modify_measurement = Box::new(move |_state, measurement| {
+        record(
+            &trace_modify_measurement,
+            TraceRecord::Measured(measurement.clone()),
+        )?;
+        Ok(vec![measurement])
+    });
+
+    let trace_advance = Arc::clone(&trace);
+    definition.advance = Box::new(move |state, cycles| {
+        record(
+            &trace_advance,
+            TraceRecord::Request(PipelinedGatestreamDown::Advance(cycles)),
+        )?;
+        state.advance(cycles).map(|_| ())
+    });
+
+    let trace_upstream_arb = Arc::clone(&trace);
+    definition.upstream_arb = Box::new(move |state, cmd| {
+        record(&trace_upstream_arb, TraceRecord::Arb(cmd.clone()))?;
+        state.arb(cmd)
+    });
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
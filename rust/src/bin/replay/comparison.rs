@@ -0,0 +1,149 @@
+//! Comparison of the measurement results of a replay against those recorded
+//! in the trace.
+
+use dqcsim::{
+    common::types::{QubitMeasurementResult, QubitRef},
+    warn,
+};
+use std::collections::{HashMap, VecDeque};
+
+/// Compares the measurement results of a replay against those recorded in
+/// the trace.
+///
+/// The results are matched per qubit, in the order in which the qubit was
+/// measured; the n-th replayed measurement of a qubit is compared against
+/// the n-th recorded measurement of that qubit. The results may be added in
+/// any order, so the replayed results can be collected lazily.
+#[derive(Debug, Default)]
+pub struct Comparison {
+    /// Recorded results that have not been matched yet.
+    recorded: HashMap<QubitRef, VecDeque<QubitMeasurementResult>>,
+
+    /// Replayed results that have not been matched yet.
+    replayed: HashMap<QubitRef, VecDeque<QubitMeasurementResult>>,
+
+    /// The number of measurements compared so far.
+    measurements: usize,
+
+    /// The number of diverging measurements so far.
+    divergences: usize,
+}
+
+impl Comparison {
+    /// Adds a measurement result recorded in the trace.
+    pub fn recorded(&mut self, result: QubitMeasurementResult) {
+        let qubit = result.qubit;
+        self.recorded.entry(qubit).or_default().push_back(result);
+        self.compare(qubit);
+    }
+
+    /// Adds a measurement result of the replay.
+    pub fn replayed(&mut self, result: QubitMeasurementResult) {
+        let qubit = result.qubit;
+        self.replayed.entry(qubit).or_default().push_back(result);
+        self.compare(qubit);
+    }
+
+    /// Compares the results for the given qubit that are available on both
+    /// sides.
+    fn compare(&mut self, qubit: QubitRef) {
+        let recorded_results = self.recorded.entry(qubit).or_default();
+        let replayed_results = self.replayed.entry(qubit).or_default();
+        while !recorded_results.is_empty() && !replayed_results.is_empty() {
+            let recorded = recorded_results.pop_front().unwrap();
+            let replayed = replayed_results.pop_front().unwrap();
+            self.measurements += 1;
+            if replayed != recorded {
+                warn!(
+                    "Measurement of qubit {} diverged: recorded {:?} with data {}, replayed {:?} with data {}",
+                    qubit, recorded.value, recorded.data, replayed.value, replayed.data
+                );
+                self.divergences += 1;
+            }
+        }
+    }
+
+    /// Reports the results that could not be matched as divergences, and
+    /// returns the number of measurements and the number of divergences.
+    pub fn finish(mut self) -> (usize, usize) {
+        let mut unmatched: Vec<_> = self
+            .recorded
+            .drain()
+            .flat_map(|(_, results)| results.into_iter().map(|result| (result, true)))
+            .chain(
+                self.replayed
+                    .drain()
+                    .flat_map(|(_, results)| results.into_iter().map(|result| (result, false))),
+            )
+            .collect();
+        unmatched.sort_by_key(|(result, _)| result.qubit.to_foreign().unwrap_or(0));
+        for (result, recorded) in unmatched {
+            if recorded {
+                warn!(
+                    "Measurement of qubit {} diverged: recorded {:?}, but qubit was not measured",
+                    result.qubit, result.value
+                );
+            } else {
+                warn!(
+                    "Measurement of qubit {} diverged: replayed {:?}, but no measurement was recorded",
+                    result.qubit, result.value
+                );
+            }
+            self.measurements += 1;
+            self.divergences += 1;
+        }
+        (self.measurements, self.divergences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::types::{ArbData, QubitMeasurementValue};
+
+    fn result(qubit: u64, value: QubitMeasurementValue) -> QubitMeasurementResult {
+        QubitMeasurementResult::new(
+            QubitRef::from_foreign(qubit).unwrap(),
+            value,
+            ArbData::default(),
+        )
+    }
+
+    #[test]
+    fn matching() {
+        let mut comparison = Comparison::default();
+        comparison.recorded(result(1, QubitMeasurementValue::Zero));
+        comparison.recorded(result(2, QubitMeasurementValue::One));
+        comparison.recorded(result(1, QubitMeasurementValue::One));
+        comparison.replayed(result(2, QubitMeasurementValue::One));
+        comparison.replayed(result(1, QubitMeasurementValue::Zero));
+        comparison.replayed(result(1, QubitMeasurementValue::One));
+        assert_eq!(comparison.finish(), (3, 0));
+    }
+
+    #[test]
+    fn diverging() {
+        let mut comparison = Comparison::default();
+        comparison.replayed(result(1, QubitMeasurementValue::Zero));
+        comparison.recorded(result(1, QubitMeasurementValue::One));
+        assert_eq!(comparison.finish(), (1, 1));
+    }
+
+    #[test]
+    fn missing() {
+        let mut comparison = Comparison::default();
+        comparison.recorded(result(1, QubitMeasurementValue::Zero));
+        comparison.recorded(result(1, QubitMeasurementValue::One));
+        comparison.replayed(result(1, QubitMeasurementValue::Zero));
+        assert_eq!(comparison.finish(), (2, 1));
+    }
+
+    #[test]
+    fn extra() {
+        let mut comparison = Comparison::default();
+        comparison.recorded(result(1, QubitMeasurementValue::Zero));
+        comparison.replayed(result(1, QubitMeasurementValue::Zero));
+        comparison.replayed(result(2, QubitMeasurementValue::One));
+        assert_eq!(comparison.finish(), (2, 1));
+    }
+}
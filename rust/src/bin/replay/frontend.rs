@@ -0,0 +1,159 @@
+//! Frontend that replays a gatestream trace recorded by the recording
+//! operator.
+//!
+//! The path to the trace file is passed as the script argument of the
+//! plugin, such that a trace file with the `.trace` extension can be
+//! replayed using `dqcsim <file>.trace <backend>`. The frontend sends the
+//! recorded allocate, free, gate, advance, and arb requests downstream in
+//! order. Because qubit references are assigned sequentially, the qubits
+//! allocated during the replay receive the same references as in the
+//! recorded run.
+//!
+//! The measurement results returned by the downstream plugin are compared
+//! against the recorded ones, including measurements that are missing from
+//! either the replay or the trace. Each divergence is logged, and the run
+//! fails if any measurement diverged. Note that the results of
+//! nondeterministic measurements only match when the simulation is run with
+//! the same seed.
+//!
+//! Collecting a measurement result requires waiting for the downstream
+//! plugins to catch up, so the results are collected as late as possible:
+//! before a qubit is measured again or freed, before an arb is sent, and at
+//! the end of the trace.
+
+mod comparison;
+
+use comparison::Comparison;
+use dqcsim::{
+    common::{
+        error::{err, inv_arg, Result},
+        protocol::{PipelinedGatestreamDown, TraceReader, TraceRecord},
+        types::{ArbData, PluginMetadata, PluginType, QubitRef},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace, warn,
+};
+use std::{
+    env,
+    fs::File,
+    io::BufReader,
+    sync::{Arc, Mutex},
+};
+
+/// Collects the results of the measurements performed during the replay
+/// that have not been collected yet.
+fn collect(
+    state: &mut PluginState,
+    uncollected: &mut Vec<QubitRef>,
+    comparison: &mut Comparison,
+) -> Result<()> {
+    for qubit in uncollected.drain(..) {
+        comparison.replayed(state.get_measurement(qubit)?);
+    }
+    Ok(())
+}
+
+/// Replays a gatestream trace, returning the number of measurements and the
+/// number of diverging measurements.
+fn replay(state: &mut PluginState, reader: TraceReader<BufReader<File>>) -> Result<(usize, usize)> {
+    // Qubits measured during the replay whose results have not been
+    // collected yet. `get_measurement()` only returns the latest result of a
+    // qubit, so these must be collected before the qubit is measured again.
+    let mut uncollected: Vec<QubitRef> = vec![];
+    let mut comparison = Comparison::default();
+
+    for record in reader {
+        match record? {
+            TraceRecord::Request(PipelinedGatestreamDown::Allocate(num_qubits, arb_cmds)) => {
+                let qubits = state.allocate(num_qubits, arb_cmds)?;
+                trace!("Allocated qubits {:?}", qubits);
+            }
+            TraceRecord::Request(PipelinedGatestreamDown::Free(qubits)) => {
+                if qubits.iter().any(|qubit| uncollected.contains(qubit)) {
+                    collect(state, &mut uncollected, &mut comparison)?;
+                }
+                state.free(qubits)?;
+            }
+            TraceRecord::Request(PipelinedGatestreamDown::Gate(gate)) => {
+                let measures = gate.get_measures().to_vec();
+                if measures.iter().any(|qubit| uncollected.contains(qubit)) {
+                    collect(state, &mut uncollected, &mut comparison)?;
+                }
+                state.gate(gate)?;
+                uncollected.extend(measures);
+            }
+            TraceRecord::Request(PipelinedGatestreamDown::Advance(cycles)) => {
+                state.advance(cycles)?;
+            }
+            TraceRecord::Arb(cmd) => {
+                // Arbs wait for the downstream plugins anyway.
+                collect(state, &mut uncollected, &mut comparison)?;
+
+                // The trace does not record whether the arb succeeded, so
+                // failures are not considered to be divergences.
+                if let Err(e) = state.arb(cmd) {
+                    warn!("Replayed arb failed: {}", e);
+                }
+            }
+            TraceRecord::Measured(recorded) => {
+                comparison.recorded(recorded);
+            }
+        }
+    }
+    collect(state, &mut uncollected, &mut comparison)?;
+    Ok(comparison.finish())
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("Trace replay frontend", "TU Delft QCE", "0.1.0"),
+    );
+
+    // The trace file is passed in the same way as a script file, preceding
+    // the simulator address.
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let simulator = args.pop().unwrap();
+    let path = args.pop();
+
+    let reader = Arc::new(Mutex::new(None));
+
+    let reader_initialize = Arc::clone(&reader);
+    definition.initialize = Box::new(move |_state, arb_cmds| {
+        info!("Running trace replay frontend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        let path = match &path {
+            Some(path) => path,
+            None => return inv_arg("the trace replay frontend requires a trace file"),
+        };
+        info!("Replaying gatestream trace {}", path);
+        reader_initialize
+            .lock()
+            .unwrap()
+            .replace(TraceReader::new(BufReader::new(File::open(path)?))?);
+        Ok(())
+    });
+
+    let reader_run = Arc::clone(&reader);
+    definition.run = Box::new(move |state, _args| {
+        info!("Running trace replay frontend run callback");
+        let reader = match reader_run.lock().unwrap().take() {
+            Some(reader) => reader,
+            None => return inv_arg("the trace has already been replayed"),
+        };
+        let (measurements, divergences) = replay(state, reader)?;
+        if divergences > 0 {
+            return err(format!(
+                "{} of {} measurement results diverged from the trace",
+                divergences, measurements
+            ));
+        }
+        info!("All {} measurement results matched the trace", measurements);
+        ArbData::from_json(format!("{{\"measurements\": {}}}", measurements), vec![])
+    });
+
+    PluginState::run(&definition, simulator).unwrap();
+}
@@ -0,0 +1,153 @@
+//! Operator that gathers statistics about the gatestream passing through it.
+//!
+//! The operator passes all requests through unchanged, except that it
+//! resolves the conditions of conditional gates itself, such that only the
+//! gates that are executed are counted. Note that this requires waiting for
+//! the results of outstanding measurements. It counts the gates per gate
+//! type, per qubit, and per cycle, and keeps track of the number of two-qubit
+//! and multi-qubit gates and the circuit depth. Unitary gates are
+//! classified by detecting their `UnitaryGateType` after moving controls
+//! encoded in the matrix to the control qubits; each control qubit adds a
+//! `C` prefix to the type, as in `CX` or `CCX`. Unitaries that do not match
+//! a known type are classified as `U(<num_targets>)`. Measurement and prep
+//! gates are classified as `measure` and `prep`, and custom gates by their
+//! name.
+//!
+//! The statistics can be queried through the `stats` host arb interface:
+//!
+//!  - `stats.report`: returns the statistics as JSON data, with the fields of
+//!    the `Report` structure.
+//!  - `stats.reset`: clears the statistics.
+//!
+//! The statistics are also logged when the operator is dropped.
+
+mod statistics;
+
+use dqcsim::{
+    common::{
+        converter::{Converter, ConverterMap},
+        error::{inv_arg, Result},
+        gates::UnitaryGateType,
+        types::{ArbCmd, ArbData, Gate, GateType, PluginMetadata, PluginType, QubitRef},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+};
+use statistics::Statistics;
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// Maximum RMS deviation between gate matrices when detecting gate types and
+/// control qubits.
+const EPSILON: f64 = 1.0e-6;
+
+/// Converter map used to detect the types of unitary gates.
+type TypeMap = ConverterMap<'static, UnitaryGateType, Gate, (Vec<QubitRef>, ArbData)>;
+
+/// Constructs the converter map that detects the gate types, in order of
+/// precedence.
+fn type_map() -> TypeMap {
+    use UnitaryGateType::*;
+    let mut map = TypeMap::new(Some(Box::new(|gate: &Gate| gate.without_qubit_refs())));
+    for typ in [
+        I, X, Y, Z, H, S, SDAG, T, TDAG, RX90, RXM90, RX180, RY90, RYM90, RY180, RZ90, RZM90,
+        RZ180, SWAP, SQSWAP, RX, RY, RZ, Phase,
+    ]
+    .iter()
+    {
+        map.push(*typ, typ.into_gate_converter(None, EPSILON, true));
+    }
+    map
+}
+
+thread_local! {
+    /// The converter map used to detect gate types. It is not `Send`, so it
+    /// cannot be moved into the callback closures.
+    static TYPE_MAP: TypeMap = type_map();
+}
+
+/// Returns the type of a gate, as used in the statistics.
+fn classify(gate: &Gate) -> Result<String> {
+    Ok(match gate.get_type() {
+        GateType::Unitary => {
+            let gate = gate.with_gate_controls(EPSILON, gate.get_controls().is_empty());
+            let typ = TYPE_MAP
+                .with(|map| map.detect(&gate))?
+                .map(|(typ, _)| typ)
+                .unwrap_or_else(|| UnitaryGateType::U(gate.get_targets().len()));
+            format!("{}{:?}", "C".repeat(gate.get_controls().len()), typ)
+        }
+        GateType::Measurement => "measure".to_string(),
+        GateType::PauliMeasurement(bases) => format!(
+            "measure_{}",
+            bases.iter().map(|b| format!("{:?}", b)).collect::<String>()
+        ),
+        GateType::Prep => "prep".to_string(),
+        GateType::Barrier => "barrier".to_string(),
+        GateType::Custom(name) => name.clone(),
+    })
+}
+
+/// Handles the `stats` arb interface.
+fn stats_arb(stats: &mut Statistics, cmd: &ArbCmd) -> Result<ArbData> {
+    if cmd.interface_identifier() != "stats" {
+        return Ok(ArbData::default());
+    }
+    match cmd.operation_identifier() {
+        "report" => ArbData::from_json(serde_json::to_string(stats.report())?, vec![]),
+        "reset" => {
+            stats.reset();
+            Ok(ArbData::default())
+        }
+        op => inv_arg(format!("unknown operation stats.{}", op)),
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Operator,
+        PluginMetadata::new("Gate statistics operator", "TU Delft QCE", "0.1.0"),
+    );
+
+    let stats = Arc::new(Mutex::new(Statistics::default()));
+
+    definition.initialize = Box::new(|_state, arb_cmds| {
+        info!("Running gate statistics operator initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        Ok(())
+    });
+
+    let stats_drop = Arc::clone(&stats);
+    definition.drop = Box::new(move |_state| {
+        info!("Running gate statistics operator drop callback");
+        let report = serde_json::to_string(stats_drop.lock().unwrap().report())?;
+        info!("Gate statistics: {}", report);
+        Ok(())
+    });
+
+    let stats_gate = Arc::clone(&stats);
+    definition.gate = Box::new(move |state, gate| {
+        if !state.resolve_condition(&gate)? {
+            return Ok(gate.undefined_measurements());
+        }
+        let gate = gate.without_condition();
+        stats_gate.lock().unwrap().record(classify(&gate)?, &gate);
+        state.gate(gate).map(|_| vec![])
+    });
+
+    let stats_advance = Arc::clone(&stats);
+    definition.advance = Box::new(move |state, cycles| {
+        stats_advance.lock().unwrap().advance(cycles);
+        state.advance(cycles).map(|_| ())
+    });
+
+    let stats_host_arb = Arc::clone(&stats);
+    definition.host_arb =
+        Box::new(move |_state, cmd| stats_arb(&mut stats_host_arb.lock().unwrap(), &cmd));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
@@ -0,0 +1,148 @@
+use dqcsim::common::types::{Gate, QubitRef};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// A snapshot of the gate statistics, as returned by the `stats.report`
+/// host arb.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Report {
+    /// The total number of gates.
+    pub gates: usize,
+    /// The number of unitary gates acting on exactly two qubits, counting
+    /// both target and control qubits.
+    pub two_qubit_gates: usize,
+    /// The number of unitary gates acting on more than two qubits.
+    pub multi_qubit_gates: usize,
+    /// The circuit depth, i.e. the number of layers when each gate is
+    /// scheduled as soon as all its qubits are available.
+    pub depth: usize,
+    /// The number of gates per gate type.
+    pub types: BTreeMap<String, usize>,
+    /// The number of gates acting on each qubit.
+    pub qubits: BTreeMap<u64, usize>,
+    /// The number of gates per cycle, for cycles containing gates.
+    pub cycles: BTreeMap<u64, usize>,
+}
+
+/// Gate statistics of a gatestream.
+#[derive(Debug, Default)]
+pub struct Statistics {
+    /// The statistics gathered so far.
+    report: Report,
+    /// The current cycle.
+    cycle: u64,
+    /// The layer of the most recent gate acting on each qubit.
+    layers: HashMap<QubitRef, usize>,
+}
+
+impl Statistics {
+    /// Records a gate of the given type.
+    pub fn record(&mut self, gate_type: impl Into<String>, gate: &Gate) {
+        let qubits: Vec<QubitRef> = gate
+            .get_targets()
+            .iter()
+            .chain(gate.get_controls())
+            .chain(gate.get_measures())
+            .cloned()
+            .collect();
+        let layer = 1 + qubits
+            .iter()
+            .filter_map(|qubit| self.layers.get(qubit))
+            .max()
+            .unwrap_or(&0);
+
+        let report = &mut self.report;
+        report.gates += 1;
+        match gate.get_targets().len() + gate.get_controls().len() {
+            0 | 1 => {}
+            2 => report.two_qubit_gates += 1,
+            _ => report.multi_qubit_gates += 1,
+        }
+        *report.types.entry(gate_type.into()).or_default() += 1;
+        *report.cycles.entry(self.cycle).or_default() += 1;
+        for qubit in qubits {
+            self.layers.insert(qubit, layer);
+            *report
+                .qubits
+                .entry(qubit.to_foreign().unwrap())
+                .or_default() += 1;
+        }
+        report.depth = report.depth.max(layer);
+    }
+
+    /// Advances the current cycle.
+    pub fn advance(&mut self, cycles: u64) {
+        self.cycle += cycles;
+    }
+
+    /// Returns the statistics gathered so far.
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    /// Clears the statistics gathered so far. The current cycle is kept.
+    pub fn reset(&mut self) {
+        self.report = Report::default();
+        self.layers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::{
+        gates::UnboundUnitaryGate,
+        types::{Basis, Matrix},
+    };
+
+    #[test]
+    fn statistics() {
+        let q: Vec<QubitRef> = (1..=3)
+            .map(|i| QubitRef::from_foreign(i).unwrap())
+            .collect();
+        let unitary = |targets: Vec<QubitRef>, controls: Vec<QubitRef>, gate| {
+            Gate::new_unitary(targets, controls, Matrix::from(gate)).unwrap()
+        };
+        let mut stats = Statistics::default();
+        stats.record("H", &unitary(vec![q[0]], vec![], UnboundUnitaryGate::H));
+        stats.record("H", &unitary(vec![q[2]], vec![], UnboundUnitaryGate::H));
+        stats.advance(2);
+        stats.record(
+            "CX",
+            &unitary(vec![q[1]], vec![q[0]], UnboundUnitaryGate::X),
+        );
+        stats.record(
+            "CCX",
+            &unitary(vec![q[2]], vec![q[0], q[1]], UnboundUnitaryGate::X),
+        );
+        stats.record(
+            "measure",
+            &Gate::new_measurement(vec![q[0]], Matrix::from(Basis::Z)).unwrap(),
+        );
+
+        let report = stats.report();
+        assert_eq!(report.gates, 5);
+        assert_eq!(report.two_qubit_gates, 1);
+        assert_eq!(report.multi_qubit_gates, 1);
+        assert_eq!(report.depth, 4);
+        assert_eq!(
+            report.types,
+            vec![("CCX", 1), ("CX", 1), ("H", 2), ("measure", 1)]
+                .into_iter()
+                .map(|(name, count)| (name.to_string(), count))
+                .collect()
+        );
+        assert_eq!(
+            report.qubits,
+            vec![(1, 4), (2, 2), (3, 2)].into_iter().collect()
+        );
+        assert_eq!(report.cycles, vec![(0, 2), (2, 3)].into_iter().collect());
+        assert_eq!(
+            serde_json::to_string(report).unwrap(),
+            r#"{"gates":5,"two_qubit_gates":1,"multi_qubit_gates":1,"depth":4,"types":{"CCX":1,"CX":1,"H":2,"measure":1},"qubits":{"1":4,"2":2,"3":2},"cycles":{"0":2,"2":3}}"#
+        );
+
+        stats.reset();
+        assert_eq!(stats.report(), &Report::default());
+    }
+}
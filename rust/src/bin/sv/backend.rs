@@ -0,0 +1,152 @@
+//! State-vector simulator backend. Keeps track of the full quantum state of
+//! the allocated qubits as a dense vector of 2^n complex amplitudes, so it is
+//! exact but limited to a modest number of qubits.
+//!
+//! The backend supports the `sv` arb interface from the host or upstream:
+//!
+//!  - `sv.state`: returns the current state as a JSON object with a `qubits`
+//!    list, ordered from least to most significant bit, and an `amplitudes`
+//!    list of `[re, im]` pairs.
+
+mod statevector;
+
+use dqcsim::{
+    common::{
+        error::{inv_arg, Result},
+        types::{ArbCmd, ArbData, GateType, PluginMetadata, PluginType, QubitMeasurementResult},
+    },
+    debug, info,
+    plugin::{definition::PluginDefinition, state::PluginState},
+    trace,
+};
+use statevector::StateVector;
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+/// Handles the `sv` arb interface for both upstream and host arbs.
+fn sv_arb(sv: &StateVector, cmd: ArbCmd) -> Result<ArbData> {
+    if cmd.interface_identifier() != "sv" {
+        return Ok(ArbData::default());
+    }
+    match cmd.operation_identifier() {
+        "state" => {
+            let json = serde_json::json!({
+                "qubits": sv.qubits().iter().map(|q| q.to_foreign()).collect::<Result<Vec<u64>>>()?,
+                "amplitudes": sv.amplitudes().iter().map(|a| [a.re, a.im]).collect::<Vec<_>>(),
+            });
+            ArbData::from_json(json.to_string(), vec![])
+        }
+        op => inv_arg(format!("unknown operation sv.{}", op)),
+    }
+}
+
+fn main() {
+    let mut definition = PluginDefinition::new(
+        PluginType::Backend,
+        PluginMetadata::new("State-vector backend", "TU Delft QCE", "0.1.0"),
+    );
+
+    let sv = Arc::new(Mutex::new(StateVector::new()));
+
+    definition.initialize = Box::new(|_state, arb_cmds| {
+        info!("Running state-vector backend initialization callback");
+        for arb_cmd in arb_cmds {
+            debug!("{}", arb_cmd);
+        }
+        Ok(())
+    });
+
+    let sv_allocate = Arc::clone(&sv);
+    definition.allocate = Box::new(move |_state, qubits, _arb_cmds| {
+        let mut sv = sv_allocate.lock().unwrap();
+        sv.allocate(qubits)?;
+        trace!("{} qubit(s) live after allocation", sv.num_qubits());
+        Ok(())
+    });
+
+    let sv_free = Arc::clone(&sv);
+    definition.free = Box::new(move |state, qubits| {
+        let mut sv = sv_free.lock().unwrap();
+        sv.free(qubits, || state.random_f64())?;
+        trace!("{} qubit(s) live after deallocation", sv.num_qubits());
+        Ok(())
+    });
+
+    let sv_gate = Arc::clone(&sv);
+    definition.gate = Box::new(move |state, gate| {
+        let mut sv = sv_gate.lock().unwrap();
+        match gate.get_type() {
+            GateType::Unitary => {
+                sv.apply_unitary(
+                    gate.get_targets(),
+                    gate.get_controls(),
+                    gate.get_matrix().unwrap(),
+                )?;
+                Ok(vec![])
+            }
+            GateType::Measurement => {
+                let basis = gate.get_matrix().unwrap();
+                gate.get_measures()
+                    .iter()
+                    .map(|qubit| {
+                        let value = sv.measure(*qubit, basis, state.random_f64())?;
+                        debug!("Measured qubit {}: {}", qubit, value as u8);
+                        Ok(QubitMeasurementResult::new(
+                            *qubit,
+                            value,
+                            ArbData::default(),
+                        ))
+                    })
+                    .collect()
+            }
+            GateType::Prep => {
+                let basis = gate.get_matrix().unwrap();
+                for qubit in gate.get_targets() {
+                    sv.prep(*qubit, basis, state.random_f64())?;
+                }
+                Ok(vec![])
+            }
+            GateType::PauliMeasurement(_) => {
+                // Pauli-product measurements are executed as the equivalent
+                // sequence of unitaries and a single Z-basis measurement.
+                let mut measurements = vec![];
+                for gate in gate.decompose_pauli_measurement()? {
+                    if gate.get_type() == &GateType::Unitary {
+                        sv.apply_unitary(
+                            gate.get_targets(),
+                            gate.get_controls(),
+                            gate.get_matrix().unwrap(),
+                        )?;
+                    } else {
+                        let qubit = gate.get_measures()[0];
+                        let value =
+                            sv.measure(qubit, gate.get_matrix().unwrap(), state.random_f64())?;
+                        debug!("Measured Pauli product on qubit {}: {}", qubit, value as u8);
+                        measurements.push(QubitMeasurementResult::new(
+                            qubit,
+                            value,
+                            ArbData::default(),
+                        ));
+                    }
+                }
+                Ok(measurements)
+            }
+            GateType::Barrier => Ok(vec![]),
+            GateType::Custom(name) => inv_arg(format!(
+                "the state-vector backend does not support custom gate '{}'",
+                name
+            )),
+        }
+    });
+
+    let sv_upstream_arb = Arc::clone(&sv);
+    definition.upstream_arb =
+        Box::new(move |_state, cmd| sv_arb(&sv_upstream_arb.lock().unwrap(), cmd));
+
+    let sv_host_arb = Arc::clone(&sv);
+    definition.host_arb = Box::new(move |_state, cmd| sv_arb(&sv_host_arb.lock().unwrap(), cmd));
+
+    PluginState::run(&definition, env::args().nth(1).unwrap()).unwrap();
+}
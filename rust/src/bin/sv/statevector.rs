@@ -0,0 +1,303 @@
+//! Dense state-vector representation of the simulated qubit register.
+
+use dqcsim::common::{
+    error::{inv_arg, inv_op, Result},
+    gates::UnboundUnitaryGate,
+    types::{Matrix, QubitRef},
+};
+use num_complex::Complex64;
+
+/// The maximum number of qubits that can be live at the same time. Every
+/// additional qubit doubles the memory footprint of the state vector, so this
+/// mostly serves to give a friendly error instead of an allocation failure.
+pub const MAX_QUBITS: usize = 30;
+
+/// Dense state-vector simulator state.
+///
+/// The qubits are stored in allocation order, such that the index of a qubit
+/// in `qubits` is the bit position of that qubit in the indices of the
+/// amplitude vector.
+#[derive(Debug)]
+pub struct StateVector {
+    /// The upstream qubit references of the live qubits, in bit order.
+    qubits: Vec<QubitRef>,
+
+    /// The complex amplitudes of the 2^n basis states.
+    amplitudes: Vec<Complex64>,
+}
+
+impl Default for StateVector {
+    fn default() -> StateVector {
+        StateVector::new()
+    }
+}
+
+impl StateVector {
+    /// Constructs a state vector without any qubits.
+    pub fn new() -> StateVector {
+        StateVector {
+            qubits: vec![],
+            amplitudes: vec![Complex64::new(1.0, 0.0)],
+        }
+    }
+
+    /// Returns the number of live qubits.
+    pub fn num_qubits(&self) -> usize {
+        self.qubits.len()
+    }
+
+    /// Returns the qubits in bit order.
+    pub fn qubits(&self) -> &[QubitRef] {
+        &self.qubits
+    }
+
+    /// Returns the amplitude vector.
+    pub fn amplitudes(&self) -> &[Complex64] {
+        &self.amplitudes
+    }
+
+    /// Returns the bit index of the given qubit.
+    fn index(&self, qubit: QubitRef) -> Result<usize> {
+        self.qubits
+            .iter()
+            .position(|q| *q == qubit)
+            .map_or_else(|| inv_arg(format!("qubit {} is not allocated", qubit)), Ok)
+    }
+
+    /// Adds the given qubits to the state vector, initialized to |0>.
+    pub fn allocate(&mut self, qubits: impl IntoIterator<Item = QubitRef>) -> Result<()> {
+        for qubit in qubits {
+            if self.qubits.contains(&qubit) {
+                return inv_arg(format!("qubit {} is already allocated", qubit));
+            }
+            if self.qubits.len() >= MAX_QUBITS {
+                return inv_op(format!(
+                    "cannot allocate qubit {}: the state-vector backend supports at most {} qubits",
+                    qubit, MAX_QUBITS
+                ));
+            }
+            // The new qubit becomes the most significant bit, so the existing
+            // amplitudes remain valid for the |0> half of the new vector.
+            let len = self.amplitudes.len();
+            self.amplitudes.resize(len * 2, Complex64::new(0.0, 0.0));
+            self.qubits.push(qubit);
+        }
+        Ok(())
+    }
+
+    /// Removes the given qubits from the state vector.
+    ///
+    /// Each qubit is measured in the Z basis first to disentangle it from the
+    /// rest of the system. `random` is called once for each qubit and must
+    /// return a uniformly distributed number in [0, 1).
+    pub fn free(
+        &mut self,
+        qubits: impl IntoIterator<Item = QubitRef>,
+        mut random: impl FnMut() -> f64,
+    ) -> Result<()> {
+        for qubit in qubits {
+            let index = self.index(qubit)?;
+            let value = self.measure_z(index, random());
+            let mask = 1 << index;
+            let low = mask - 1;
+            let amplitudes = (0..self.amplitudes.len() / 2)
+                .map(|i| {
+                    let i = ((i & !low) << 1) | (i & low);
+                    self.amplitudes[if value { i | mask } else { i }]
+                })
+                .collect();
+            self.amplitudes = amplitudes;
+            self.qubits.remove(index);
+        }
+        Ok(())
+    }
+
+    /// Applies a unitary matrix to the given target qubits, conditioned on
+    /// the given control qubits all being |1>.
+    ///
+    /// The first target qubit corresponds to the most significant bit of the
+    /// matrix row/column indices.
+    pub fn apply_unitary(
+        &mut self,
+        targets: &[QubitRef],
+        controls: &[QubitRef],
+        matrix: &Matrix,
+    ) -> Result<()> {
+        if matrix.num_qubits() != Some(targets.len()) {
+            return inv_arg(format!(
+                "matrix of dimension {} cannot be applied to {} qubit(s)",
+                matrix.dimension(),
+                targets.len()
+            ));
+        }
+        let targets = targets
+            .iter()
+            .map(|q| self.index(*q))
+            .collect::<Result<Vec<usize>>>()?;
+        let control_mask = controls
+            .iter()
+            .map(|q| self.index(*q).map(|i| 1 << i))
+            .collect::<Result<Vec<usize>>>()?
+            .into_iter()
+            .fold(0, |acc, bit| acc | bit);
+        let target_mask = targets.iter().fold(0, |acc, i| acc | (1 << i));
+
+        // Precompute the amplitude index offsets for each matrix index.
+        let dimension = matrix.dimension();
+        let offsets: Vec<usize> = (0..dimension)
+            .map(|j| {
+                targets
+                    .iter()
+                    .enumerate()
+                    .filter(|(t, _)| j & (1 << (targets.len() - t - 1)) != 0)
+                    .fold(0, |acc, (_, i)| acc | (1 << i))
+            })
+            .collect();
+
+        let mut input = vec![Complex64::new(0.0, 0.0); dimension];
+        for base in 0..self.amplitudes.len() {
+            if base & target_mask != 0 || base & control_mask != control_mask {
+                continue;
+            }
+            for (value, offset) in input.iter_mut().zip(offsets.iter()) {
+                *value = self.amplitudes[base | offset];
+            }
+            for (row, offset) in offsets.iter().enumerate() {
+                self.amplitudes[base | offset] = input
+                    .iter()
+                    .enumerate()
+                    .map(|(col, value)| matrix[(row, col)] * value)
+                    .sum();
+            }
+        }
+        Ok(())
+    }
+
+    /// Measures the qubit at the given bit index in the Z basis, collapsing
+    /// the state accordingly. `random` must be uniformly distributed in
+    /// [0, 1).
+    fn measure_z(&mut self, index: usize, random: f64) -> bool {
+        let mask = 1 << index;
+        let p_one: f64 = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i & mask != 0)
+            .map(|(_, a)| a.norm_sqr())
+            .sum();
+        let value = random < p_one;
+        let norm = if value { p_one } else { 1.0 - p_one }.sqrt();
+        for (i, a) in self.amplitudes.iter_mut().enumerate() {
+            if (i & mask != 0) == value {
+                *a /= norm;
+            } else {
+                *a = Complex64::new(0.0, 0.0);
+            }
+        }
+        value
+    }
+
+    /// Measures a qubit in the basis described by the given 2x2 matrix, as
+    /// defined by the semantics of `GateType::Measurement`.
+    pub fn measure(&mut self, qubit: QubitRef, basis: &Matrix, random: f64) -> Result<bool> {
+        let index = self.index(qubit)?;
+        self.apply_unitary(&[qubit], &[], &basis.adjoint())?;
+        let value = self.measure_z(index, random);
+        self.apply_unitary(&[qubit], &[], basis)?;
+        Ok(value)
+    }
+
+    /// Resets a qubit to |0> and then applies the given 2x2 matrix to it, as
+    /// defined by the semantics of `GateType::Prep`.
+    pub fn prep(&mut self, qubit: QubitRef, basis: &Matrix, random: f64) -> Result<()> {
+        let index = self.index(qubit)?;
+        if self.measure_z(index, random) {
+            self.apply_unitary(&[qubit], &[], &UnboundUnitaryGate::X.into())?;
+        }
+        self.apply_unitary(&[qubit], &[], basis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dqcsim::common::types::Basis;
+
+    fn qref(q: u64) -> QubitRef {
+        QubitRef::from_foreign(q).unwrap()
+    }
+
+    fn approx(a: Complex64, b: f64) -> bool {
+        (a - Complex64::new(b, 0.0)).norm() < 1e-9
+    }
+
+    #[test]
+    fn allocate_free() {
+        let mut sv = StateVector::new();
+        sv.allocate(vec![qref(1), qref(2)]).unwrap();
+        assert_eq!(sv.num_qubits(), 2);
+        assert_eq!(sv.amplitudes().len(), 4);
+        assert!(sv.allocate(vec![qref(2)]).is_err());
+        sv.free(vec![qref(1)], || 0.5).unwrap();
+        assert_eq!(sv.num_qubits(), 1);
+        assert_eq!(sv.amplitudes().len(), 2);
+        assert!(sv.free(vec![qref(1)], || 0.5).is_err());
+    }
+
+    #[test]
+    fn bell_state() {
+        let mut sv = StateVector::new();
+        sv.allocate(vec![qref(1), qref(2)]).unwrap();
+        sv.apply_unitary(&[qref(1)], &[], &UnboundUnitaryGate::H.into())
+            .unwrap();
+        sv.apply_unitary(&[qref(2)], &[qref(1)], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        assert!(approx(sv.amplitudes()[0], s));
+        assert!(approx(sv.amplitudes()[1], 0.0));
+        assert!(approx(sv.amplitudes()[2], 0.0));
+        assert!(approx(sv.amplitudes()[3], s));
+
+        let z: Matrix = Basis::Z.into();
+        assert!(!sv.measure(qref(1), &z, 0.9).unwrap());
+        assert!(!sv.measure(qref(2), &z, 0.1).unwrap());
+        assert!(approx(sv.amplitudes()[0], 1.0));
+    }
+
+    #[test]
+    fn matrix_qubit_order() {
+        // The first target is the most significant bit of the matrix index,
+        // so a CNOT matrix on [q1, q2] uses q1 as control.
+        let mut sv = StateVector::new();
+        sv.allocate(vec![qref(1), qref(2)]).unwrap();
+        sv.apply_unitary(&[qref(1)], &[], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        let cnot = Matrix::from(UnboundUnitaryGate::X).add_controls(1);
+        sv.apply_unitary(&[qref(1), qref(2)], &[], &cnot).unwrap();
+        assert!(approx(sv.amplitudes()[3], 1.0));
+    }
+
+    #[test]
+    fn measure_x_basis() {
+        let mut sv = StateVector::new();
+        sv.allocate(vec![qref(1)]).unwrap();
+        let x: Matrix = Basis::X.into();
+        sv.prep(qref(1), &x, 0.5).unwrap();
+        for _ in 0..4 {
+            assert!(!sv.measure(qref(1), &x, 0.999).unwrap());
+        }
+        let z: Matrix = Basis::Z.into();
+        assert!(sv.measure(qref(1), &z, 0.4).unwrap());
+    }
+
+    #[test]
+    fn prep_resets() {
+        let mut sv = StateVector::new();
+        sv.allocate(vec![qref(1)]).unwrap();
+        sv.apply_unitary(&[qref(1)], &[], &UnboundUnitaryGate::X.into())
+            .unwrap();
+        sv.prep(qref(1), &Basis::Z.into(), 0.5).unwrap();
+        assert!(approx(sv.amplitudes()[0], 1.0));
+        assert!(approx(sv.amplitudes()[1], 0.0));
+    }
+}
@@ -265,6 +265,82 @@ impl Into<Result<PluginType>> for dqcs_plugin_type_t {
     }
 }
 
+/// Enumeration of the optional protocol features that a plugin can declare
+/// support for.
+///
+/// Frontends declare the kinds of gates they send, operators and backends
+/// declare the kinds of gates they accept. DQCsim refuses to start a
+/// simulation if a plugin does not support a capability declared by its
+/// upstream neighbour, unless either of them does not declare any
+/// capabilities at all.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[allow(non_camel_case_types)]
+pub enum dqcs_plugin_capability_t {
+    /// Invalid capability. Used to indicate failure of an API that returns a
+    /// capability.
+    DQCS_PCAP_INVALID = -1,
+
+    /// Unitary gates.
+    DQCS_PCAP_UNITARY = 0,
+
+    /// Measurement gates.
+    DQCS_PCAP_MEASUREMENT = 1,
+
+    /// Prep gates.
+    DQCS_PCAP_PREP = 2,
+
+    /// Pauli-product measurement gates.
+    DQCS_PCAP_PAULI_MEASUREMENT = 3,
+
+    /// Barriers.
+    DQCS_PCAP_BARRIER = 4,
+
+    /// Custom gates.
+    DQCS_PCAP_CUSTOM = 5,
+
+    /// Conditional gates. Backends support these implicitly, since DQCsim
+    /// resolves their conditions before passing them on.
+    DQCS_PCAP_CONDITIONAL = 6,
+}
+
+impl From<PluginCapability> for dqcs_plugin_capability_t {
+    fn from(x: PluginCapability) -> Self {
+        match x {
+            PluginCapability::UnitaryGates => dqcs_plugin_capability_t::DQCS_PCAP_UNITARY,
+            PluginCapability::MeasurementGates => dqcs_plugin_capability_t::DQCS_PCAP_MEASUREMENT,
+            PluginCapability::PrepGates => dqcs_plugin_capability_t::DQCS_PCAP_PREP,
+            PluginCapability::PauliMeasurementGates => {
+                dqcs_plugin_capability_t::DQCS_PCAP_PAULI_MEASUREMENT
+            }
+            PluginCapability::Barriers => dqcs_plugin_capability_t::DQCS_PCAP_BARRIER,
+            PluginCapability::CustomGates => dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM,
+            PluginCapability::ConditionalGates => dqcs_plugin_capability_t::DQCS_PCAP_CONDITIONAL,
+        }
+    }
+}
+
+impl From<dqcs_plugin_capability_t> for Result<PluginCapability> {
+    fn from(x: dqcs_plugin_capability_t) -> Self {
+        match x {
+            dqcs_plugin_capability_t::DQCS_PCAP_UNITARY => Ok(PluginCapability::UnitaryGates),
+            dqcs_plugin_capability_t::DQCS_PCAP_MEASUREMENT => {
+                Ok(PluginCapability::MeasurementGates)
+            }
+            dqcs_plugin_capability_t::DQCS_PCAP_PREP => Ok(PluginCapability::PrepGates),
+            dqcs_plugin_capability_t::DQCS_PCAP_PAULI_MEASUREMENT => {
+                Ok(PluginCapability::PauliMeasurementGates)
+            }
+            dqcs_plugin_capability_t::DQCS_PCAP_BARRIER => Ok(PluginCapability::Barriers),
+            dqcs_plugin_capability_t::DQCS_PCAP_CUSTOM => Ok(PluginCapability::CustomGates),
+            dqcs_plugin_capability_t::DQCS_PCAP_CONDITIONAL => {
+                Ok(PluginCapability::ConditionalGates)
+            }
+            _ => inv_arg("invalid plugin capability"),
+        }
+    }
+}
+
 /// Enumeration of loglevels and logging modes.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -866,6 +942,94 @@ pub enum dqcs_predefined_gate_t {
     /// \f]
     DQCS_GATE_SQRT_SWAP = 201,
 
+    /// The iSWAP gate matrix.
+    ///
+    /// \f[
+    /// \textit{iSWAP} = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & 0 & i & 0 \\
+    /// 0 & i & 0 & 0 \\
+    /// 0 & 0 & 0 & 1
+    /// \end{bmatrix}
+    /// \f]
+    DQCS_GATE_ISWAP = 202,
+
+    /// The square-root of an iSWAP gate matrix.
+    ///
+    /// \f[
+    /// \sqrt{\textit{iSWAP}} = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & \frac{1}{\sqrt{2}} & \frac{i}{\sqrt{2}} & 0 \\
+    /// 0 & \frac{i}{\sqrt{2}} & \frac{1}{\sqrt{2}} & 0 \\
+    /// 0 & 0 & 0 & 1
+    /// \end{bmatrix}
+    /// \f]
+    DQCS_GATE_SQRT_ISWAP = 203,
+
+    /// The controlled X (CNOT) gate matrix.
+    ///
+    /// \f[
+    /// \textit{CNOT} = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & 1 & 0 & 0 \\
+    /// 0 & 0 & 0 & 1 \\
+    /// 0 & 0 & 1 & 0
+    /// \end{bmatrix}
+    /// \f]
+    ///
+    /// The first qubit is the control qubit. When used to construct a gate,
+    /// the control qubit is placed in the control qubit list of the gate, and
+    /// the matrix is reduced to the X matrix. When used to detect gates, the
+    /// control qubit may be specified either way.
+    DQCS_GATE_CNOT = 210,
+
+    /// The controlled Z gate matrix.
+    ///
+    /// \f[
+    /// \textit{CZ} = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & 1 & 0 & 0 \\
+    /// 0 & 0 & 1 & 0 \\
+    /// 0 & 0 & 0 & -1
+    /// \end{bmatrix}
+    /// \f]
+    ///
+    /// The control qubit is handled the same way as for `DQCS_GATE_CNOT`.
+    DQCS_GATE_CZ = 211,
+
+    /// The matrix for a fermionic simulation (fSim) gate.
+    ///
+    /// \f[
+    /// \textit{fSim}(\theta, \phi) = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & \cos{\theta} & -i\sin{\theta} & 0 \\
+    /// 0 & -i\sin{\theta} & \cos{\theta} & 0 \\
+    /// 0 & 0 & 0 & e^{-i\phi}
+    /// \end{bmatrix}
+    /// \f]
+    ///
+    /// θ and φ are specified or returned through the first two binary string
+    /// arguments of the parameterization ArbData object. They are represented
+    /// as little-endian double floating point values, specified in radians.
+    DQCS_GATE_FSIM = 250,
+
+    /// The matrix for a controlled phase gate with angle π/2^k.
+    ///
+    /// \f[
+    /// \textit{CR}(k) = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 \\
+    /// 0 & 1 & 0 & 0 \\
+    /// 0 & 0 & 1 & 0 \\
+    /// 0 & 0 & 0 & e^{i\pi / 2^k}
+    /// \end{bmatrix}
+    /// \f]
+    ///
+    /// k is specified or returned through the first binary string argument
+    /// of the parameterization ArbData object. It is represented as a
+    /// little-endian unsigned 64-bit integer. The control qubit is handled the
+    /// same way as for `DQCS_GATE_CNOT`.
+    DQCS_GATE_CR_K = 251,
+
     /// Any two-qubit unitary gate, parameterized as a full unitary matrix.
     ///
     /// The full matrix is specified or returned through the first binary string
@@ -874,6 +1038,25 @@ pub enum dqcs_predefined_gate_t {
     /// real/imag pairs, with the pairs in row-major order.
     DQCS_GATE_U2 = 290,
 
+    /// The Toffoli (doubly-controlled X) gate matrix.
+    ///
+    /// \f[
+    /// \textit{Toffoli} = \begin{bmatrix}
+    /// 1 & 0 & 0 & 0 & 0 & 0 & 0 & 0 \\
+    /// 0 & 1 & 0 & 0 & 0 & 0 & 0 & 0 \\
+    /// 0 & 0 & 1 & 0 & 0 & 0 & 0 & 0 \\
+    /// 0 & 0 & 0 & 1 & 0 & 0 & 0 & 0 \\
+    /// 0 & 0 & 0 & 0 & 1 & 0 & 0 & 0 \\
+    /// 0 & 0 & 0 & 0 & 0 & 1 & 0 & 0 \\
+    /// 0 & 0 & 0 & 0 & 0 & 0 & 0 & 1 \\
+    /// 0 & 0 & 0 & 0 & 0 & 0 & 1 & 0
+    /// \end{bmatrix}
+    /// \f]
+    ///
+    /// The first two qubits are the control qubits. They are handled the same
+    /// way as for `DQCS_GATE_CNOT`.
+    DQCS_GATE_TOFFOLI = 300,
+
     /// Any three-qubit unitary gate, parameterized as a full unitary matrix.
     ///
     /// The full matrix is specified or returned through the first binary string
@@ -914,7 +1097,14 @@ impl TryFrom<dqcs_predefined_gate_t> for UnitaryGateType {
             dqcs_predefined_gate_t::DQCS_GATE_R => Ok(UnitaryGateType::R),
             dqcs_predefined_gate_t::DQCS_GATE_SWAP => Ok(UnitaryGateType::SWAP),
             dqcs_predefined_gate_t::DQCS_GATE_SQRT_SWAP => Ok(UnitaryGateType::SQSWAP),
+            dqcs_predefined_gate_t::DQCS_GATE_ISWAP => Ok(UnitaryGateType::ISWAP),
+            dqcs_predefined_gate_t::DQCS_GATE_SQRT_ISWAP => Ok(UnitaryGateType::SQISWAP),
+            dqcs_predefined_gate_t::DQCS_GATE_CNOT => Ok(UnitaryGateType::CNOT),
+            dqcs_predefined_gate_t::DQCS_GATE_CZ => Ok(UnitaryGateType::CZ),
+            dqcs_predefined_gate_t::DQCS_GATE_FSIM => Ok(UnitaryGateType::FSim),
+            dqcs_predefined_gate_t::DQCS_GATE_CR_K => Ok(UnitaryGateType::CRk),
             dqcs_predefined_gate_t::DQCS_GATE_U2 => Ok(UnitaryGateType::U(2)),
+            dqcs_predefined_gate_t::DQCS_GATE_TOFFOLI => Ok(UnitaryGateType::TOFFOLI),
             dqcs_predefined_gate_t::DQCS_GATE_U3 => Ok(UnitaryGateType::U(3)),
             dqcs_predefined_gate_t::DQCS_GATE_INVALID => inv_arg("invalid gate"),
         }
@@ -972,6 +1162,16 @@ impl TryFrom<dqcs_basis_t> for Basis {
     }
 }
 
+impl From<Basis> for dqcs_basis_t {
+    fn from(basis: Basis) -> dqcs_basis_t {
+        match basis {
+            Basis::X => dqcs_basis_t::DQCS_BASIS_X,
+            Basis::Y => dqcs_basis_t::DQCS_BASIS_Y,
+            Basis::Z => dqcs_basis_t::DQCS_BASIS_Z,
+        }
+    }
+}
+
 /// Types of DQCsim gates.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -1040,6 +1240,38 @@ pub enum dqcs_gate_type_t {
     ///  - exactly one measurement result is reported upstream for exactly the
     ///    qubits in the measures set.
     DQCS_GATE_TYPE_CUSTOM,
+
+    /// Pauli-product measurement gates have one or more target qubits, each
+    /// associated with a Pauli basis (X, Y, or Z). The first target is also
+    /// the measured qubit. The matrix is the tensor product of the Pauli
+    /// matrices.
+    ///
+    /// The semantics are:
+    ///
+    ///  - the joint observable formed by the tensor product of the Paulis is
+    ///    measured;
+    ///  - a single measurement result is propagated upstream for the first
+    ///    target qubit, where zero represents the +1 (even parity) eigenvalue
+    ///    and one represents the -1 (odd parity) eigenvalue.
+    ///
+    /// The data field may add pragma-like hints to the gate, for instance to
+    /// represent the line number in the source file that generated the gate,
+    /// error modelling information, and so on. This data may be silently
+    /// ignored.
+    DQCS_GATE_TYPE_PAULI_MEASUREMENT,
+
+    /// Barrier gates have zero or more target qubits and no matrix. They do
+    /// not operate on the quantum state, but forbid gates from being moved
+    /// across them by operators that reorder or schedule gates.
+    ///
+    /// The semantics are:
+    ///
+    ///  - no gate operating on any of the target qubits may be reordered
+    ///    across the barrier;
+    ///  - if there are no target qubits, the barrier applies to all qubits.
+    ///
+    /// Backends treat barriers as no-ops.
+    DQCS_GATE_TYPE_BARRIER,
 }
 
 impl From<&GateType> for dqcs_gate_type_t {
@@ -1049,6 +1281,8 @@ impl From<&GateType> for dqcs_gate_type_t {
             GateType::Measurement => dqcs_gate_type_t::DQCS_GATE_TYPE_MEASUREMENT,
             GateType::Prep => dqcs_gate_type_t::DQCS_GATE_TYPE_PREP,
             GateType::Custom(_) => dqcs_gate_type_t::DQCS_GATE_TYPE_CUSTOM,
+            GateType::PauliMeasurement(_) => dqcs_gate_type_t::DQCS_GATE_TYPE_PAULI_MEASUREMENT,
+            GateType::Barrier => dqcs_gate_type_t::DQCS_GATE_TYPE_BARRIER,
         }
     }
 }
@@ -44,7 +44,9 @@ fn new_predef_helper(
 /// `targets` must be a handle to a non-empty qubit set, containing at least
 /// as many qubits as needed for the specified gate type. If more qubits are
 /// specified, the rightmost qubits become the targets, and the remaining
-/// qubits become control qubits to make a controlled gate.
+/// qubits become control qubits to make a controlled gate. Gate types with
+/// intrinsic control qubits, such as `DQCS_GATE_CNOT`, also place those in
+/// the control qubit set.
 ///
 /// `param_data` takes an optional `ArbData` object used to parameterize the
 /// gate if necessary. If not specified, an empty object is used. Some of the
@@ -282,6 +284,56 @@ pub extern "C" fn dqcs_gate_new_measurement(
     })
 }
 
+/// Constructs a new Pauli-product measurement gate.
+///
+/// `qubits` must be a handle to a non-empty qubit set. `bases` must point to
+/// an array of Pauli bases with as many entries as there are qubits in the
+/// set, specifying for each qubit which Pauli operator (X, Y, or Z) is part of
+/// the measured observable. The semantics are as follows:
+///
+///  - measure the observable formed by the tensor product of the Pauli
+///    operators
+///  - report a single measurement result for the first qubit in the set, where
+///    zero represents the +1 (even parity) eigenvalue and one represents the
+///    -1 (odd parity) eigenvalue
+///
+/// This function returns the handle to the gate, or 0 to indicate failure.
+/// The `qubits` qubit set is consumed/deleted by this function if and only if
+/// it succeeds.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_new_pauli_measurement(
+    qubits: dqcs_handle_t,
+    bases: *const dqcs_basis_t,
+) -> dqcs_handle_t {
+    api_return(0, || {
+        // Interpret qubit set.
+        resolve!(qubits as pending QubitReferenceSet);
+        let qubit_vec: Vec<QubitRef> = {
+            let x: &QubitReferenceSet = qubits.as_ref().unwrap();
+            x.iter().cloned().collect()
+        };
+
+        // Interpret bases.
+        if bases.is_null() {
+            inv_arg("bases cannot be null")?;
+        }
+        let mut basis_vec = Vec::with_capacity(qubit_vec.len());
+        for i in 0..qubit_vec.len() {
+            basis_vec.push(Basis::try_from(unsafe { *bases.add(i) })?);
+        }
+
+        // Construct the gate.
+        let gate = insert(Gate::new_pauli_measurement(
+            qubit_vec.into_iter().zip(basis_vec),
+        )?);
+
+        // Everything went OK. Now make sure that the qubit set handle is
+        // deleted.
+        delete!(resolved qubits);
+        Ok(gate)
+    })
+}
+
 /// Constructs a new prep gate.
 ///
 /// `targets` must be a handle to a qubit set. `matrix` is an optional matrix
@@ -332,6 +384,45 @@ pub extern "C" fn dqcs_gate_new_prep(
     })
 }
 
+/// Constructs a new barrier gate.
+///
+/// `qubits` optionally specifies the set of qubits that the barrier applies
+/// to. You may pass 0 or an empty qubit set to construct a barrier for all
+/// qubits.
+///
+/// Barriers do not operate on the quantum state. Instead, they forbid
+/// operators that reorder or schedule gates from moving gates that operate on
+/// any of the barrier's qubits across it. Backends treat them as no-ops.
+///
+/// This function returns the handle to the gate, or 0 to indicate failure.
+/// The `qubits` qubit set (if specified) is consumed/deleted by this function
+/// if and only if it succeeds.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_new_barrier(qubits: dqcs_handle_t) -> dqcs_handle_t {
+    api_return(0, || {
+        // Interpret qubit set.
+        resolve!(optional qubits as pending QubitReferenceSet);
+        let qubit_vec: Vec<QubitRef> = {
+            if let Some(qubits) = qubits.as_ref() {
+                let x: &QubitReferenceSet = qubits.as_ref().unwrap();
+                x.iter().cloned().collect()
+            } else {
+                vec![]
+            }
+        };
+
+        // Construct the gate.
+        let gate = insert(Gate::new_barrier(qubit_vec)?);
+
+        // Everything went OK. Now make sure that the qubit set handle is
+        // deleted.
+        if let Some(mut qubits) = qubits {
+            delete!(resolved qubits);
+        }
+        Ok(gate)
+    })
+}
+
 /// Constructs a new custom gate.
 ///
 /// The functionality of custom gates is not specified by DQCsim. Instead, this
@@ -577,6 +668,84 @@ pub extern "C" fn dqcs_gate_matrix(gate: dqcs_handle_t) -> dqcs_handle_t {
     })
 }
 
+/// Returns the Pauli basis associated with the qubit at the given index of a
+/// Pauli-product measurement gate.
+///
+/// The index refers to the order of the qubits in the `targets` set of the
+/// gate. Returns `DQCS_BASIS_INVALID` if the gate handle is invalid, the gate
+/// is not a Pauli-product measurement gate, or the index is out of range.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_pauli_basis(gate: dqcs_handle_t, index: size_t) -> dqcs_basis_t {
+    api_return(dqcs_basis_t::DQCS_BASIS_INVALID, || {
+        resolve!(gate as &Gate);
+        match gate.get_type() {
+            GateType::PauliMeasurement(bases) => bases
+                .get(index)
+                .cloned()
+                .map(|basis| basis.into())
+                .ok_or_else(oe_inv_arg("index out of range")),
+            _ => inv_arg("not a Pauli-product measurement gate"),
+        }
+    })
+}
+
+/// Returns whether the specified gate is annotated with a duration.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_has_duration(gate: dqcs_handle_t) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        resolve!(gate as &Gate);
+        Ok(gate.get_duration().is_some())
+    })
+}
+
+/// Returns the duration of the specified gate in cycles.
+///
+/// This function fails if the gate is not annotated with a duration. Query
+/// `dqcs_gate_has_duration()` to disambiguate between a gate without a
+/// duration and a different error.
+///
+/// This function uses -1 to signal an error.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_duration(gate: dqcs_handle_t) -> dqcs_cycle_t {
+    api_return(-1, || {
+        resolve!(gate as &Gate);
+        Ok(gate
+            .get_duration()
+            .ok_or_else(oe_inv_arg("gate does not have a duration"))? as dqcs_cycle_t)
+    })
+}
+
+/// Annotates the specified gate with the given duration in cycles, replacing
+/// any existing duration.
+///
+/// Durations are timing annotations intended for operators that schedule
+/// gates. They do not advance the simulation cycle counter; use
+/// `dqcs_plugin_advance()` for that. The duration cannot be negative.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_set_duration(
+    gate: dqcs_handle_t,
+    duration: dqcs_cycle_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        if duration < 0 {
+            return inv_arg("duration cannot be negative");
+        }
+        resolve!(gate as &mut Gate);
+        *gate = gate.with_duration(duration as u64);
+        Ok(())
+    })
+}
+
+/// Removes the duration annotation from the specified gate, if any.
+#[no_mangle]
+pub extern "C" fn dqcs_gate_clear_duration(gate: dqcs_handle_t) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(gate as &mut Gate);
+        *gate = gate.without_duration();
+        Ok(())
+    })
+}
+
 /// Utility function that detects control qubits in the `targets` list of the
 /// gate by means of the gate matrix, and reduces them into `controls` qubits.
 ///>
@@ -118,6 +118,10 @@ pub extern "C" fn dqcs_gm_new(
 ///> `num_controls` specifies the number of control qubits associated with this
 ///> gate type. If negative, the gate can have any number of control qubits.
 ///> If zero or positive, the number of control qubits must be as specified.
+///> For gates with intrinsic control qubits, such as `DQCS_GATE_CNOT`, this
+///> specifies the number of control qubits in addition to the intrinsic ones,
+///> and the gate is detected regardless of whether its control qubits are
+///> specified explicitly or encoded in its matrix.
 ///> `epsilon` specifies the maximum element-wise root-mean-square error
 ///> between the incoming matrix and the to be detected matrix that results in a
 ///> positive match.
@@ -451,6 +455,48 @@ pub extern "C" fn dqcs_gm_add_measure(
     })
 }
 
+/// Adds a Pauli-product measurement gate mapping to the given gate map.
+///>
+///> `gm` must be a handle to a gate map object (`dqcs_gm_new()`).
+///> `key_free` is an optional callback function used to free `key_data` when
+///> the gate map is destroyed, or when this function fails.
+///> `key_data` is the user-specified value used to identify this mapping.
+///> `num_qubits` specifies the number of qubits in the Pauli string, and
+///> `bases` must point to an array of that many Pauli bases, representing
+///> the Pauli string to be detected. Only Pauli-product measurement gates
+///> with exactly this Pauli string are detected.
+///>
+///> The parameterization `ArbData` object returned by detection and consumed
+///> by construction is mapped one-to-one to the user data of the gate in the
+///> DQCsim-protocol.
+#[no_mangle]
+pub extern "C" fn dqcs_gm_add_pauli_measure(
+    gm: dqcs_handle_t,
+    key_free: Option<extern "C" fn(user_data: *mut c_void)>,
+    key_data: *mut c_void,
+    num_qubits: size_t,
+    bases: *const dqcs_basis_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        let key = UserKeyData::new(key_free, key_data);
+        resolve!(gm as &mut GateMap);
+        let key = gm.make_key(key);
+        if num_qubits == 0 {
+            inv_arg("at least one qubit is required")?;
+        }
+        if bases.is_null() {
+            inv_arg("bases cannot be null")?;
+        }
+        let mut pauli = Vec::with_capacity(num_qubits);
+        for i in 0..num_qubits {
+            pauli.push(Basis::try_from(unsafe { *bases.add(i) })?);
+        }
+        gm.map
+            .push(key, Box::new(MeasurementGateConverter::new_pauli(pauli)));
+        Ok(())
+    })
+}
+
 /// Adds a prep gate mapping to the given gate map.
 ///>
 ///> `gm` must be a handle to a gate map object (`dqcs_gm_new()`).
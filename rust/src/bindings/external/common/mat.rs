@@ -1,5 +1,8 @@
 use super::*;
-use crate::common::gates::UnitaryGateType;
+use crate::common::{
+    decomposition::{EulerAngles, TwoQubitDecomposition},
+    gates::{UnboundUnitaryGate, UnitaryGateType},
+};
 use std::convert::{TryFrom, TryInto};
 use std::mem::size_of;
 use std::ptr::null_mut;
@@ -366,3 +369,240 @@ pub extern "C" fn dqcs_mat_strip_control(
         }
     })
 }
+
+/// Multiplies two matrices.
+///>
+///> `a` and `b` are borrowed matrix handles. The matrices must have the same
+///> dimension. This function returns a new matrix handle with the product
+///> `A * B`, or 0 if it fails. When both matrices represent gates, the
+///> product represents applying gate `B` first and then gate `A`.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_multiply(a: dqcs_handle_t, b: dqcs_handle_t) -> dqcs_handle_t {
+    api_return(0, || {
+        resolve!(a as &Matrix);
+        resolve!(b as &Matrix);
+        Ok(insert(a.multiply(b)?))
+    })
+}
+
+/// Computes the tensor (Kronecker) product of two matrices.
+///>
+///> `a` and `b` are borrowed matrix handles. This function returns a new
+///> matrix handle with the product `A ⊗ B`, or 0 if it fails. The qubits of
+///> matrix `A` come before the qubits of matrix `B` in the resulting matrix;
+///> that is, when the result is used as a gate matrix, the first qubits are
+///> operated on by `A`, and the remaining qubits by `B`.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_kron(a: dqcs_handle_t, b: dqcs_handle_t) -> dqcs_handle_t {
+    api_return(0, || {
+        resolve!(a as &Matrix);
+        resolve!(b as &Matrix);
+        Ok(insert(a.kron(b)))
+    })
+}
+
+/// Computes the adjoint (conjugate transpose) of a matrix.
+///>
+///> `mat` is a borrowed matrix handle. This function returns a new matrix
+///> handle with the adjoint, or 0 if it fails. For unitary matrices, the
+///> adjoint is the inverse.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_adjoint(mat: dqcs_handle_t) -> dqcs_handle_t {
+    api_return(0, || {
+        resolve!(mat as &Matrix);
+        Ok(insert(mat.adjoint()))
+    })
+}
+
+/// Computes the trace of a matrix.
+///>
+///> `mat` is a borrowed matrix handle. The real and imaginary components of
+///> the trace are written to `real` and `imag`, which must not be null.
+///> These are not mutated if the function fails.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_trace(
+    mat: dqcs_handle_t,
+    real: *mut c_double,
+    imag: *mut c_double,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(mat as &Matrix);
+        if real.is_null() || imag.is_null() {
+            inv_arg("real and imag cannot be null")
+        } else {
+            let trace = mat.trace();
+            unsafe {
+                *real = trace.re;
+                *imag = trace.im;
+            }
+            Ok(())
+        }
+    })
+}
+
+/// Raises a matrix to a non-negative integer power.
+///>
+///> `mat` is a borrowed matrix handle. This function returns a new matrix
+///> handle with the matrix multiplied with itself `exponent` times, or 0 if
+///> it fails. The zeroth power is the identity matrix.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_pow(mat: dqcs_handle_t, exponent: size_t) -> dqcs_handle_t {
+    api_return(0, || {
+        resolve!(mat as &Matrix);
+        match u32::try_from(exponent) {
+            Ok(exponent) => Ok(insert(mat.pow(exponent))),
+            Err(_) => inv_arg(format!("exponent {} is too large", exponent)),
+        }
+    })
+}
+
+/// Decomposes a single-qubit unitary into ZYZ Euler angles.
+///>
+///> `mat` is a borrowed handle to a 2x2 unitary matrix. The angles are
+///> computed such that `U = e^(i*phase) RZ(beta) RY(gamma) RZ(delta)`, and
+///> are written to the respective output arguments. `phase` may be null if
+///> the global phase is not needed; the other outputs must not be null.
+///>
+///> The function fails if the matrix is not unitary within `epsilon`, or if
+///> the decomposition does not reproduce the matrix within `epsilon`. Both
+///> use the same metric as `dqcs_mat_approx_eq()`. The outputs are not
+///> mutated if the function fails.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_decompose_zyz(
+    mat: dqcs_handle_t,
+    epsilon: c_double,
+    phase: *mut c_double,
+    beta: *mut c_double,
+    gamma: *mut c_double,
+    delta: *mut c_double,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(mat as &Matrix);
+        if beta.is_null() || gamma.is_null() || delta.is_null() {
+            return inv_arg("beta, gamma, and delta cannot be null");
+        }
+        let angles = EulerAngles::zyz(mat, epsilon)?;
+        unsafe {
+            if !phase.is_null() {
+                *phase = angles.phase;
+            }
+            *beta = angles.beta;
+            *gamma = angles.gamma;
+            *delta = angles.delta;
+        }
+        Ok(())
+    })
+}
+
+/// Decomposes a single-qubit unitary into the parameters of an R gate.
+///>
+///> `mat` is a borrowed handle to a 2x2 unitary matrix. The parameters are
+///> computed such that the matrix equals `e^(i*phase)` times the matrix of
+///> the `DQCS_GATE_R` gate with parameters `theta`, `phi`, and `lambda`, and
+///> are written to the respective output arguments. `phase` may be null if
+///> the global phase is not needed; the other outputs must not be null.
+///>
+///> The function fails under the same conditions as
+///> `dqcs_mat_decompose_zyz()`. The outputs are not mutated if the function
+///> fails.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_decompose_r(
+    mat: dqcs_handle_t,
+    epsilon: c_double,
+    phase: *mut c_double,
+    theta: *mut c_double,
+    phi: *mut c_double,
+    lambda: *mut c_double,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        resolve!(mat as &Matrix);
+        if theta.is_null() || phi.is_null() || lambda.is_null() {
+            return inv_arg("theta, phi, and lambda cannot be null");
+        }
+        let angles = EulerAngles::zyz(mat, epsilon)?;
+        let (gate, global_phase) = angles.to_r();
+        match gate {
+            UnboundUnitaryGate::R(t, p, l) => unsafe {
+                if !phase.is_null() {
+                    *phase = global_phase;
+                }
+                *theta = t;
+                *phi = p;
+                *lambda = l;
+            },
+            _ => unreachable!(),
+        }
+        Ok(())
+    })
+}
+
+/// Decomposes a two-qubit unitary into single-qubit gates and CNOTs.
+///>
+///> `mat` is a borrowed handle to a 4x4 unitary matrix. `qa` and `qb` are
+///> the qubits that the matrix operates on, where `qa` corresponds to the
+///> most significant bit of the matrix indices, as for gate matrices. The
+///> matrix is decomposed using the KAK decomposition into single-qubit R
+///> gates and at most three CNOTs, which are represented as X gates with a
+///> control qubit.
+///>
+///> This function returns a new array of new gate handles, in the order in
+///> which the gates are to be applied, terminated by a 0 entry. The
+///> decomposed unitary equals `e^(i*phase)` times the unitary of the gates;
+///> this global phase is written to `phase` if it is not null. The returned
+///> array **must be freed using `free()` when you are done with it to avoid
+///> memory leaks**, and the gate handles it contains must be deleted
+///> individually. This function returns null if it fails, in which case
+///> `phase` is not mutated.
+///>
+///> The function fails if the matrix is not unitary within `epsilon`, or if
+///> the decomposition does not reproduce the matrix within `epsilon`. Both
+///> use the same metric as `dqcs_mat_approx_eq()`.
+#[no_mangle]
+pub extern "C" fn dqcs_mat_decompose_kak(
+    mat: dqcs_handle_t,
+    epsilon: c_double,
+    qa: dqcs_qubit_t,
+    qb: dqcs_qubit_t,
+    phase: *mut c_double,
+) -> *mut dqcs_handle_t {
+    api_return(null_mut(), || {
+        resolve!(mat as &Matrix);
+        let qubits = [
+            QubitRef::from_foreign(qa)
+                .ok_or_else(oe_inv_arg("0 is not a valid qubit reference"))?,
+            QubitRef::from_foreign(qb)
+                .ok_or_else(oe_inv_arg("0 is not a valid qubit reference"))?,
+        ];
+        if qa == qb {
+            inv_arg(format!("cannot use qubit {} twice", qa))?;
+        }
+        let decomposition = TwoQubitDecomposition::kak(mat, epsilon)?;
+        let gates = decomposition
+            .gates
+            .iter()
+            .map(|gate| {
+                Gate::new_unitary(
+                    vec![qubits[gate.target]],
+                    gate.control.map(|control| qubits[control]),
+                    Matrix::from(gate.gate),
+                )
+            })
+            .collect::<Result<Vec<Gate>>>()?;
+        let handles_ffi =
+            unsafe { calloc(gates.len() + 1, size_of::<dqcs_handle_t>()) as *mut dqcs_handle_t };
+        if handles_ffi.is_null() {
+            return err("failed to allocate gate handles");
+        }
+        for (index, gate) in gates.into_iter().enumerate() {
+            unsafe {
+                *handles_ffi.add(index) = insert(gate);
+            }
+        }
+        if !phase.is_null() {
+            unsafe {
+                *phase = decomposition.phase;
+            }
+        }
+        Ok(handles_ffi)
+    })
+}
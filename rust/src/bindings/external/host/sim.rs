@@ -276,6 +276,44 @@ pub extern "C" fn dqcs_sim_get_version_idx(sim: dqcs_handle_t, index: ssize_t) -
     })
 }
 
+/// Queries whether a plugin, referenced by instance name, declared the given
+/// capability.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_has_capability(
+    sim: dqcs_handle_t,
+    name: *const c_char,
+    capability: dqcs_plugin_capability_t,
+) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        let capability: Result<PluginCapability> = capability.into();
+        let capability = capability?;
+        resolve!(sim as &Simulator);
+        Ok(sim
+            .simulation
+            .get_capabilities(receive_str(name)?)?
+            .contains(&capability))
+    })
+}
+
+/// Queries whether a plugin, referenced by index, declared the given
+/// capability.
+#[no_mangle]
+pub extern "C" fn dqcs_sim_has_capability_idx(
+    sim: dqcs_handle_t,
+    index: ssize_t,
+    capability: dqcs_plugin_capability_t,
+) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        let capability: Result<PluginCapability> = capability.into();
+        let capability = capability?;
+        resolve!(sim as &Simulator);
+        Ok(sim
+            .simulation
+            .get_capabilities_idx(index)?
+            .contains(&capability))
+    })
+}
+
 /// Writes a reproduction file for the simulation so far.
 #[no_mangle]
 pub extern "C" fn dqcs_sim_write_reproduction_file(
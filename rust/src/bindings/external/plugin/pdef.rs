@@ -87,6 +87,47 @@ pub extern "C" fn dqcs_pdef_version(pdef: dqcs_handle_t) -> *mut c_char {
     })
 }
 
+/// Declares that the plugin defined by the given plugin definition object
+/// supports the given capability.
+///
+/// Frontends declare the kinds of gates they send, operators and backends
+/// declare the kinds of gates they accept. No capabilities are declared by
+/// default, in which case DQCsim does not check whether the plugin is
+/// compatible with its neighbours. Declaring a capability that was already
+/// declared is no-op.
+#[no_mangle]
+pub extern "C" fn dqcs_pdef_add_capability(
+    pdef: dqcs_handle_t,
+    capability: dqcs_plugin_capability_t,
+) -> dqcs_return_t {
+    api_return_none(|| {
+        let capability: Result<PluginCapability> = capability.into();
+        let capability = capability?;
+        resolve!(pdef as &mut PluginDefinition);
+        if !pdef.get_capabilities().contains(&capability) {
+            let mut capabilities = pdef.get_capabilities().to_vec();
+            capabilities.push(capability);
+            pdef.set_capabilities(capabilities);
+        }
+        Ok(())
+    })
+}
+
+/// Returns whether the given plugin definition object declares the given
+/// capability.
+#[no_mangle]
+pub extern "C" fn dqcs_pdef_has_capability(
+    pdef: dqcs_handle_t,
+    capability: dqcs_plugin_capability_t,
+) -> dqcs_bool_return_t {
+    api_return_bool(|| {
+        let capability: Result<PluginCapability> = capability.into();
+        let capability = capability?;
+        resolve!(pdef as &PluginDefinition);
+        Ok(pdef.get_capabilities().contains(&capability))
+    })
+}
+
 /// Sets the user logic initialization callback.
 ///
 /// This is always called before any of the other callbacks are run. The
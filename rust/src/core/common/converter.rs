@@ -9,7 +9,7 @@
 use crate::common::{
     error::{inv_arg, oe_err, oe_inv_arg, Result},
     gates::UnboundUnitaryGate,
-    types::{ArbData, Gate, GateType, Matrix, QubitRef},
+    types::{ArbData, Basis, Gate, GateType, Matrix, QubitRef},
 };
 use integer_sqrt::IntegerSquareRoot;
 use num_complex::Complex64;
@@ -68,6 +68,29 @@ impl FromArb for f64 {
     }
 }
 
+impl FromArb for (f64, f64) {
+    fn from_arb(src: &mut ArbData) -> Result<Self> {
+        let args = src.get_args_mut();
+        if args.len() < 2 {
+            inv_arg("expected two double arguments in ArbData")?;
+        }
+        let a = f64::from_le_bytes(
+            args[0][..]
+                .try_into()
+                .ok()
+                .ok_or_else(oe_inv_arg("expected double first argument in ArbData"))?,
+        );
+        let b = f64::from_le_bytes(
+            args[1][..]
+                .try_into()
+                .ok()
+                .ok_or_else(oe_inv_arg("expected double second argument in ArbData"))?,
+        );
+        args.drain(..2);
+        Ok((a, b))
+    }
+}
+
 impl FromArb for (f64, f64, f64) {
     fn from_arb(src: &mut ArbData) -> Result<Self> {
         let args = src.get_args_mut();
@@ -149,6 +172,13 @@ impl ToArb for f64 {
     }
 }
 
+impl ToArb for (f64, f64) {
+    fn to_arb(self, dest: &mut ArbData) {
+        self.1.to_arb(dest);
+        self.0.to_arb(dest);
+    }
+}
+
 impl ToArb for (f64, f64, f64) {
     fn to_arb(self, dest: &mut ArbData) {
         self.2.to_arb(dest);
@@ -552,6 +582,86 @@ impl MatrixConverter for RMatrixConverter {
     }
 }
 
+/// Matrix converter object for the fermionic simulation (fSim) gate.
+#[derive(Default)]
+pub struct FSimMatrixConverter {}
+
+impl MatrixConverter for FSimMatrixConverter {
+    type Parameters = (f64, f64);
+
+    fn detect_matrix(
+        &self,
+        matrix: &Matrix,
+        epsilon: f64,
+        ignore_global_phase: bool,
+    ) -> Result<Option<Self::Parameters>> {
+        if matrix.dimension() != 4 {
+            return Ok(None);
+        }
+        let phase = if ignore_global_phase {
+            try_normalize(matrix[(0, 0)]).conj()
+        } else {
+            Complex64::new(1.0, 0.0)
+        };
+        let c = matrix[(1, 1)] * phase;
+        let s = matrix[(1, 2)] * phase;
+        let theta = (-s.im).atan2(c.re);
+        let phi = -(matrix[(3, 3)] * phase).arg();
+        let expected: Matrix = self.construct_matrix(&(theta, phi))?;
+        if matrix.approx_eq(&expected, epsilon, ignore_global_phase) {
+            Ok(Some((theta, phi)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn construct_matrix(&self, params: &Self::Parameters) -> Result<Matrix> {
+        Ok(UnboundUnitaryGate::FSim(params.0, params.1).into())
+    }
+}
+
+/// Matrix converter object for the controlled phase gate using θ = π/2^k.
+///
+/// Note that this converter operates on the full two-qubit matrix.
+#[derive(Default)]
+pub struct CRkMatrixConverter {}
+
+impl MatrixConverter for CRkMatrixConverter {
+    type Parameters = u64;
+
+    fn detect_matrix(
+        &self,
+        matrix: &Matrix,
+        epsilon: f64,
+        ignore_global_phase: bool,
+    ) -> Result<Option<Self::Parameters>> {
+        if matrix.dimension() != 4 {
+            return Ok(None);
+        }
+        let theta = detect_angle(
+            matrix[(0, 0)].re,
+            matrix[(0, 0)].im,
+            matrix[(3, 3)].re,
+            matrix[(3, 3)].im,
+        );
+        let k = if theta <= 0.0 {
+            0u64
+        } else {
+            (-(theta / PI).log(2.0).round()) as u64
+        };
+        let expected: Matrix = self.construct_matrix(&k)?;
+        if matrix.approx_eq(&expected, epsilon, ignore_global_phase) {
+            Ok(Some(k))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn construct_matrix(&self, k: &Self::Parameters) -> Result<Matrix> {
+        Ok(UnboundUnitaryGate::CRk(*k).into())
+    }
+}
+
 /// Matrix converter object for any matrix of a certain size - simply has the
 /// matrix itself as its parameter type.
 pub struct UMatrixConverter {
@@ -773,7 +883,174 @@ where
     }
 }
 
+/// Returns the bottom-right submatrix of the given matrix for the given number
+/// of qubits. For a controlled matrix, this is the matrix that is applied to
+/// the target qubits when all the control qubits are set.
+fn controlled_submatrix(matrix: &Matrix, num_qubits: usize) -> Matrix {
+    let dimension = 1 << num_qubits;
+    let offset = matrix.dimension() - dimension;
+    let mut entries = Vec::with_capacity(dimension * dimension);
+    for row in offset..matrix.dimension() {
+        for col in offset..matrix.dimension() {
+            entries.push(matrix[(row, col)]);
+        }
+    }
+    Matrix::new(entries).unwrap()
+}
+
+/// Converter implementation for unitary gates with intrinsic control qubits,
+/// such as CNOT or Toffoli, based on a converter for their full matrix.
+///
+/// Unlike `UnitaryGateConverter`, this converter detects gates regardless of
+/// whether their control qubits are specified through the control qubit list
+/// of the gate or are encoded in its matrix. In either case, the control
+/// qubits come first in the qubit argument vector. Upon construction, the
+/// intrinsic control qubits are always placed in the control qubit list.
+pub struct ControlledGateConverter<T>
+where
+    T: MatrixConverter,
+{
+    /// The converter dealing with detection and construction of the full
+    /// matrix, including the intrinsic control qubits.
+    converter: T,
+    /// The number of intrinsic control qubits of the matrix.
+    num_intrinsic_controls: usize,
+    /// How many control qubits are expected in addition to the intrinsic
+    /// ones. If None, no constraint is placed on this.
+    num_controls: Option<usize>,
+    /// The max RMS deviation between the given matrix and the input matrix
+    /// during detection.
+    epsilon: f64,
+    /// Whether global phase should be ignored if the gate has no additional
+    /// control qubits.
+    ignore_global_phase: bool,
+}
+
+impl<T> ControlledGateConverter<T>
+where
+    T: MatrixConverter,
+{
+    pub fn new(
+        converter: T,
+        num_intrinsic_controls: usize,
+        num_controls: Option<usize>,
+        epsilon: f64,
+        ignore_global_phase: bool,
+    ) -> Self {
+        Self {
+            converter,
+            num_intrinsic_controls,
+            num_controls,
+            epsilon,
+            ignore_global_phase,
+        }
+    }
+}
+
+impl<T> Converter for ControlledGateConverter<T>
+where
+    T: MatrixConverter,
+    T::Parameters: FromArb + ToArb,
+{
+    type Input = Gate;
+    type Output = (Vec<QubitRef>, ArbData);
+
+    fn detect(&self, gate: &Gate) -> Result<Option<Self::Output>> {
+        if gate.get_type() != &GateType::Unitary {
+            // Not a unitary so no match.
+            return Ok(None);
+        }
+
+        // Move the control qubits into the matrix, such that it doesn't
+        // matter how they were specified. This doesn't change the order of
+        // the qubits.
+        let gate = gate.with_matrix_controls();
+        let matrix = gate.get_matrix().unwrap();
+        let num_qubits = gate.get_targets().len();
+
+        // Try the allowed numbers of additional control qubits.
+        let candidates = if let Some(expected) = self.num_controls {
+            expected..expected + 1
+        } else {
+            0..num_qubits
+        };
+        for num_controls in candidates {
+            if num_controls >= num_qubits {
+                break;
+            }
+            let submatrix = controlled_submatrix(matrix, num_qubits - num_controls);
+            if num_controls > 0
+                && !submatrix
+                    .add_controls(num_controls)
+                    .approx_eq(matrix, self.epsilon, false)
+            {
+                continue;
+            }
+            if let Some(params) = self.converter.detect_matrix(
+                &submatrix,
+                self.epsilon,
+                self.ignore_global_phase && num_controls == 0,
+            )? {
+                // Matrix match; construct data.
+                let mut data = gate.data.clone();
+                params.to_arb(&mut data);
+                return Ok(Some((gate.get_targets().to_vec(), data)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn construct(&self, output: &Self::Output) -> Result<Gate> {
+        let (qubits, data) = output;
+
+        // Construct the data.
+        let mut data = data.clone();
+        let params = T::Parameters::from_arb(&mut data)?;
+
+        // Construct the matrix.
+        let matrix = self.converter.construct_matrix(&params)?;
+
+        // Parse qubit argument vector.
+        let num_matrix_qubits = matrix.num_qubits().unwrap();
+        let num_targets = num_matrix_qubits - self.num_intrinsic_controls;
+        let num_controls = qubits
+            .len()
+            .checked_sub(num_matrix_qubits)
+            .ok_or_else(oe_inv_arg(format!(
+                "need at least {} qubits",
+                num_matrix_qubits
+            )))?;
+        if let Some(expected) = self.num_controls {
+            if num_controls != expected {
+                inv_arg(format!(
+                    "expected {} control and {} target qubits",
+                    expected + self.num_intrinsic_controls,
+                    num_targets
+                ))?;
+            }
+        }
+        let split = qubits.len() - num_targets;
+        let controls = &qubits[..split];
+        let targets = &qubits[split..];
+
+        // Construct the gate.
+        let mut gate = Gate::new_unitary(
+            targets.iter().cloned(),
+            controls.iter().cloned(),
+            controlled_submatrix(&matrix, num_targets),
+        )?;
+        gate.data.copy_from(&data);
+
+        Ok(gate)
+    }
+}
+
 /// Converter implementation for measurement gates.
+///
+/// This converter either detects regular measurement gates with the given
+/// basis, or Pauli-product measurement gates with the given Pauli string.
+/// In the latter case, the qubits in the output are the target qubits of the
+/// gate, in the order of the Pauli string.
 pub struct MeasurementGateConverter {
     /// The number of expected measurement qubits, or None if not constrained.
     num_measures: Option<usize>,
@@ -781,6 +1058,8 @@ pub struct MeasurementGateConverter {
     basis: Matrix,
     /// The maximum RMS deviation in the basis when detecting.
     epsilon: f64,
+    /// The Pauli string, if this converter is for Pauli-product measurements.
+    pauli: Option<Vec<Basis>>,
 }
 
 impl MeasurementGateConverter {
@@ -789,6 +1068,19 @@ impl MeasurementGateConverter {
             num_measures,
             basis,
             epsilon,
+            pauli: None,
+        }
+    }
+
+    /// Constructs a converter for Pauli-product measurements of the given
+    /// Pauli string, represented by the bases of the Pauli operators.
+    pub fn new_pauli(pauli: impl IntoIterator<Item = Basis>) -> Self {
+        let pauli: Vec<Basis> = pauli.into_iter().collect();
+        Self {
+            num_measures: Some(pauli.len()),
+            basis: Matrix::new_identity(2),
+            epsilon: 0.0,
+            pauli: Some(pauli),
         }
     }
 }
@@ -798,6 +1090,15 @@ impl Converter for MeasurementGateConverter {
     type Output = (Vec<QubitRef>, ArbData);
 
     fn detect(&self, gate: &Gate) -> Result<Option<Self::Output>> {
+        if let Some(pauli) = &self.pauli {
+            return Ok(match gate.get_type() {
+                GateType::PauliMeasurement(bases) if bases == pauli => {
+                    Some((gate.get_targets().to_vec(), gate.data.clone()))
+                }
+                // Not a Pauli-product measurement, or a different string.
+                _ => None,
+            });
+        }
         if gate.get_type() != &GateType::Measurement {
             // Not a measurement gate.
             Ok(None)
@@ -831,7 +1132,11 @@ impl Converter for MeasurementGateConverter {
         }
 
         // Construct the gate.
-        let mut gate = Gate::new_measurement(qubits.clone(), self.basis.clone())?;
+        let mut gate = if let Some(pauli) = &self.pauli {
+            Gate::new_pauli_measurement(qubits.iter().cloned().zip(pauli.iter().cloned()))?
+        } else {
+            Gate::new_measurement(qubits.clone(), self.basis.clone())?
+        };
         gate.data.copy_from(&data);
 
         Ok(gate)
@@ -1481,6 +1786,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fsim_matrix_converter() {
+        let fsim = FSimMatrixConverter::default();
+        assert!(fsim
+            .detect_matrix(&Matrix::new_identity(2), 0., false)
+            .unwrap()
+            .is_none());
+        assert!(fsim
+            .detect_matrix(&Matrix::from(UnboundUnitaryGate::SWAP), 0.001, false)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            fsim.detect_matrix(&Matrix::new_identity(4), 0., false)
+                .unwrap(),
+            Some((0., 0.))
+        );
+        assert_eq!(
+            fsim.detect_matrix(
+                &Matrix::from(UnboundUnitaryGate::FSim(1.234f64, 0.5)),
+                0.001,
+                false
+            )
+            .unwrap(),
+            Some((1.234f64, 0.5))
+        );
+        let iswap = fsim
+            .detect_matrix(&Matrix::from(UnboundUnitaryGate::ISWAP), 0.001, false)
+            .unwrap()
+            .unwrap();
+        assert!(approx_eq!(f64, iswap.0, -PI / 2., ulps = 2));
+        assert!(approx_eq!(f64, iswap.1, 0., ulps = 2));
+        let phased = Matrix::new(
+            Matrix::from(UnboundUnitaryGate::FSim(0.3, -0.2))
+                .into_iter()
+                .map(|x| x * Complex64::new(0., 1.)),
+        )
+        .unwrap();
+        assert!(fsim.detect_matrix(&phased, 0.001, false).unwrap().is_none());
+        let params = fsim.detect_matrix(&phased, 0.001, true).unwrap().unwrap();
+        assert!(approx_eq!(f64, params.0, 0.3, epsilon = 1e-12));
+        assert!(approx_eq!(f64, params.1, -0.2, epsilon = 1e-12));
+        assert_eq!(
+            fsim.construct_matrix(&(1.234f64, 0.5)).unwrap(),
+            Matrix::from(UnboundUnitaryGate::FSim(1.234f64, 0.5))
+        );
+    }
+
+    #[test]
+    fn crk_matrix_converter() {
+        let crk = CRkMatrixConverter::default();
+        assert!(crk
+            .detect_matrix(&Matrix::from(UnboundUnitaryGate::PhaseK(1)), 0., false)
+            .unwrap()
+            .is_none());
+        assert!(crk
+            .detect_matrix(&Matrix::from(UnboundUnitaryGate::CNOT), 0.001, false)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            crk.detect_matrix(&Matrix::from(UnboundUnitaryGate::CZ), 0.001, false)
+                .unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            crk.detect_matrix(&Matrix::from(UnboundUnitaryGate::CRk(3)), 0.001, false)
+                .unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            crk.construct_matrix(&2).unwrap(),
+            Matrix::from(UnboundUnitaryGate::PhaseK(2)).add_controls(1)
+        );
+    }
+
     #[test]
     fn u_matrix_converter() {
         let u = UMatrixConverter::new(Some(3));
@@ -1737,6 +2116,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn controlled_gate_converter() {
+        let a = QubitRef::from_foreign(1).unwrap();
+        let b = QubitRef::from_foreign(2).unwrap();
+        let c = QubitRef::from_foreign(3).unwrap();
+        let cnot = UnitaryGateType::CNOT.into_gate_converter(Some(0), 0.001, false);
+        let xcnot = UnitaryGateType::CNOT.into_gate_converter(None, 0.001, false);
+        let crk = UnitaryGateType::CRk.into_gate_converter(Some(0), 0.001, false);
+        let toffoli = UnitaryGateType::TOFFOLI.into_gate_converter(Some(0), 0.001, false);
+
+        // Controls specified through the control qubit list.
+        let cnot_gate = Gate::from(BoundUnitaryGate::CNOT(a, b));
+        assert_eq!(cnot_gate.get_controls(), &[a]);
+        assert_eq!(cnot_gate.get_targets(), &[b]);
+        assert_eq!(
+            cnot.detect(&cnot_gate).unwrap(),
+            Some((vec![a, b], ArbData::default()))
+        );
+
+        // Controls encoded in the matrix.
+        assert_eq!(
+            cnot.detect(&cnot_gate.with_matrix_controls()).unwrap(),
+            Some((vec![a, b], ArbData::default()))
+        );
+
+        // Not a CNOT.
+        let x_gate = Gate::from(BoundUnitaryGate::X(b));
+        assert!(cnot.detect(&x_gate).unwrap().is_none());
+        assert!(xcnot.detect(&x_gate).unwrap().is_none());
+        assert!(cnot
+            .detect(&Gate::from(BoundUnitaryGate::CZ(a, b)))
+            .unwrap()
+            .is_none());
+
+        // Additional control qubits.
+        let toffoli_gate = Gate::from(BoundUnitaryGate::TOFFOLI(a, c, b));
+        assert!(cnot.detect(&toffoli_gate).unwrap().is_none());
+        assert_eq!(
+            xcnot.detect(&toffoli_gate).unwrap(),
+            Some((vec![a, c, b], ArbData::default()))
+        );
+        assert_eq!(
+            toffoli.detect(&toffoli_gate).unwrap(),
+            Some((vec![a, c, b], ArbData::default()))
+        );
+        assert_eq!(
+            toffoli
+                .detect(&toffoli_gate.with_matrix_controls())
+                .unwrap(),
+            Some((vec![a, c, b], ArbData::default()))
+        );
+
+        // Parameterized.
+        let mut arb = ArbData::default();
+        2u64.to_arb(&mut arb);
+        let crk_gate =
+            Gate::new_unitary(vec![a, b], vec![], Matrix::from(UnboundUnitaryGate::CRk(2)))
+                .unwrap();
+        assert_eq!(
+            crk.detect(&crk_gate).unwrap(),
+            Some((vec![a, b], arb.clone()))
+        );
+        assert_eq!(
+            crk.construct(&(vec![a, b], arb)).unwrap(),
+            Gate::from(BoundUnitaryGate::CRk(2, a, b))
+        );
+
+        // Construction.
+        assert_eq!(
+            cnot.construct(&(vec![a, b], ArbData::default())).unwrap(),
+            cnot_gate
+        );
+        assert_eq!(
+            xcnot
+                .construct(&(vec![a, c, b], ArbData::default()))
+                .unwrap(),
+            toffoli_gate
+        );
+        assert_eq!(
+            cnot.construct(&(vec![a, c, b], ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected 1 control and 1 target qubits"
+        );
+        assert_eq!(
+            cnot.construct(&(vec![a], ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: need at least 2 qubits"
+        );
+    }
+
     #[test]
     fn measurement_gate_converter() {
         let mn = MeasurementGateConverter::new(None, Matrix::new_identity(2), 0.001);
@@ -1836,6 +2307,37 @@ mod tests {
         assert!(m1.construct(&two).is_err());
     }
 
+    #[test]
+    fn pauli_measurement_gate_converter() {
+        let xz = MeasurementGateConverter::new_pauli(vec![Basis::X, Basis::Z]);
+        let mn = MeasurementGateConverter::new(None, Matrix::new_identity(2), 0.001);
+        let q1 = QubitRef::from_foreign(1).unwrap();
+        let q2 = QubitRef::from_foreign(2).unwrap();
+
+        let xz_gate = Gate::new_pauli_measurement(vec![(q2, Basis::X), (q1, Basis::Z)]).unwrap();
+        let zx_gate = Gate::new_pauli_measurement(vec![(q1, Basis::Z), (q2, Basis::X)]).unwrap();
+        let measure_gate = Gate::new_measurement(vec![q1, q2], Matrix::new_identity(2)).unwrap();
+
+        assert_eq!(
+            xz.detect(&xz_gate).unwrap(),
+            Some((vec![q2, q1], ArbData::default()))
+        );
+        assert!(xz.detect(&zx_gate).unwrap().is_none());
+        assert!(xz.detect(&measure_gate).unwrap().is_none());
+        assert!(mn.detect(&xz_gate).unwrap().is_none());
+
+        assert_eq!(
+            xz.construct(&(vec![q2, q1], ArbData::default())).unwrap(),
+            xz_gate
+        );
+        assert_eq!(
+            xz.construct(&(vec![q1], ArbData::default()))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected 2 measurement qubits"
+        );
+    }
+
     #[test]
     fn custom_gate_converter() {
         let gate = Gate::new_measurement(
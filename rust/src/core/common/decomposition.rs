@@ -0,0 +1,597 @@
+//! Decompositions of single- and two-qubit unitaries.
+//!
+//! The types defined here are provided to facilitate plugin developers that
+//! need to express arbitrary unitaries using a limited set of gates.
+//!
+//! - [`EulerAngles`]: the ZYZ Euler angle decomposition of a single-qubit
+//!   unitary, which can also be expressed as a single
+//!   [`UnboundUnitaryGate::R`] gate.
+//!
+//! - [`TwoQubitDecomposition`]: the KAK (canonical) decomposition of a
+//!   two-qubit unitary into single-qubit gates and at most three CNOTs, using
+//!   the circuit from "Optimal quantum circuits for general two-qubit gates",
+//!   F. Vatan and C. Williams, Phys. Rev. A 69, 032315 (2004).
+//!
+//! Both decompositions are exact, i.e. they include the global phase of the
+//! decomposed unitary. The input matrix is verified to be unitary and the
+//! decomposition is verified to reproduce it, both within the given
+//! `epsilon`, using the same root-mean-square metric as
+//! [`Matrix::approx_eq`].
+//!
+//! [`EulerAngles`]: ./struct.EulerAngles.html
+//! [`TwoQubitDecomposition`]: ./struct.TwoQubitDecomposition.html
+//! [`UnboundUnitaryGate::R`]: ../gates/enum.UnboundUnitaryGate.html#variant.R
+//! [`Matrix::approx_eq`]: ../types/struct.Matrix.html#method.approx_eq
+
+use crate::common::{
+    error::{err, inv_arg, Result},
+    gates::UnboundUnitaryGate,
+    types::Matrix,
+};
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI};
+
+/// Tolerance used for internal consistency checks.
+const TOLERANCE: f64 = 1.0e-9;
+
+/// Verifies that the given matrix is a unitary with the given number of
+/// qubits.
+fn check_unitary(matrix: &Matrix, num_qubits: usize, epsilon: f64) -> Result<()> {
+    if matrix.num_qubits() != Some(num_qubits) {
+        inv_arg(format!(
+            "expected a {0}x{0} matrix, but got a {1}x{1} matrix",
+            1 << num_qubits,
+            matrix.dimension()
+        ))
+    } else if !matrix.approx_unitary(epsilon) {
+        inv_arg("matrix is not unitary")
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns a matrix multiplied by a scalar.
+fn scale(matrix: &Matrix, factor: Complex64) -> Matrix {
+    Matrix::new(matrix.clone().into_iter().map(|x| x * factor)).unwrap()
+}
+
+/// Returns the transpose of a matrix.
+fn transpose(matrix: &Matrix) -> Matrix {
+    let n = matrix.dimension();
+    Matrix::new((0..n * n).map(|i| matrix[(i % n, i / n)])).unwrap()
+}
+
+/// Returns the determinant of a matrix using Gaussian elimination.
+fn det(matrix: &Matrix) -> Complex64 {
+    let n = matrix.dimension();
+    let mut m: Vec<Vec<Complex64>> = (0..n)
+        .map(|r| (0..n).map(|c| matrix[(r, c)]).collect())
+        .collect();
+    let mut det = c!(1.);
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&x, &y| m[x][col].norm().partial_cmp(&m[y][col].norm()).unwrap())
+            .unwrap();
+        if m[pivot][col].norm() == 0.0 {
+            return c!(0.);
+        }
+        if pivot != col {
+            m.swap(pivot, col);
+            det = -det;
+        }
+        det *= m[col][col];
+        let (upper, lower) = m.split_at_mut(col + 1);
+        let pivot = &upper[col];
+        for row in lower {
+            let factor = row[col] / pivot[col];
+            for (x, p) in row.iter_mut().zip(pivot.iter()).skip(col) {
+                *x -= factor * p;
+            }
+        }
+    }
+    det
+}
+
+/// The ZYZ Euler angle decomposition of a single-qubit unitary U, such that
+/// U = e^(iα) RZ(β) RY(γ) RZ(δ).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EulerAngles {
+    /// The global phase α.
+    pub phase: f64,
+    /// The angle β of the last Z rotation.
+    pub beta: f64,
+    /// The angle γ of the Y rotation.
+    pub gamma: f64,
+    /// The angle δ of the first Z rotation.
+    pub delta: f64,
+}
+
+impl EulerAngles {
+    /// Decomposes a single-qubit unitary into ZYZ Euler angles.
+    ///
+    /// Returns an error if the matrix is not a 2x2 unitary matrix within
+    /// `epsilon`.
+    pub fn zyz(matrix: &Matrix, epsilon: f64) -> Result<EulerAngles> {
+        check_unitary(matrix, 1, epsilon)?;
+        let phase = det(matrix).arg() / 2.0;
+        let dephase = Complex64::from_polar(1.0, -phase);
+        let v00 = matrix[(0, 0)] * dephase;
+        let v10 = matrix[(1, 0)] * dephase;
+        let v11 = matrix[(1, 1)] * dephase;
+        let sum = 2.0 * v11.arg();
+        let difference = 2.0 * v10.arg();
+        let angles = EulerAngles {
+            phase,
+            beta: (sum + difference) / 2.0,
+            gamma: 2.0 * v10.norm().atan2(v00.norm()),
+            delta: (sum - difference) / 2.0,
+        };
+        if !angles.matrix().approx_eq(matrix, epsilon, false) {
+            return err("ZYZ decomposition failed");
+        }
+        Ok(angles)
+    }
+
+    /// Returns the rotations RZ(δ), RY(γ), and RZ(β), in the order in which
+    /// they are to be applied. Together with the global phase, these make up
+    /// the decomposed unitary.
+    pub fn to_gates(&self) -> Vec<UnboundUnitaryGate<'static>> {
+        vec![
+            UnboundUnitaryGate::RZ(self.delta),
+            UnboundUnitaryGate::RY(self.gamma),
+            UnboundUnitaryGate::RZ(self.beta),
+        ]
+    }
+
+    /// Returns the decomposed unitary as an `R(θ, φ, λ)` gate and the global
+    /// phase that is to be applied in addition to it.
+    pub fn to_r(&self) -> (UnboundUnitaryGate<'static>, f64) {
+        (
+            UnboundUnitaryGate::R(self.gamma, self.beta, self.delta),
+            self.phase - (self.beta + self.delta) / 2.0,
+        )
+    }
+
+    /// Returns the matrix of the decomposed unitary.
+    pub fn matrix(&self) -> Matrix {
+        let matrix = self
+            .to_gates()
+            .into_iter()
+            .fold(Matrix::new_identity(2), |matrix, gate| {
+                Matrix::from(gate).multiply(&matrix).unwrap()
+            });
+        scale(&matrix, Complex64::from_polar(1.0, self.phase))
+    }
+}
+
+/// A gate in a decomposition of a multi-qubit unitary.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DecomposedGate {
+    /// The single-qubit gate.
+    pub gate: UnboundUnitaryGate<'static>,
+    /// The index of the target qubit within the decomposed unitary, where
+    /// index 0 corresponds to the most significant bit of the matrix indices.
+    pub target: usize,
+    /// The index of the control qubit, if any.
+    pub control: Option<usize>,
+}
+
+impl DecomposedGate {
+    /// Returns the matrix of this gate when it acts on a unitary with the
+    /// given number of qubits.
+    pub fn matrix(&self, num_qubits: usize) -> Matrix {
+        let dimension = 1 << num_qubits;
+        let bit = |index: usize| 1 << (num_qubits - 1 - index);
+        let target = bit(self.target);
+        let control = self.control.map(bit).unwrap_or(0);
+        let gate = Matrix::from(self.gate);
+        Matrix::new((0..dimension * dimension).map(|i| {
+            let (row, col) = (i / dimension, i % dimension);
+            if row & !target != col & !target {
+                c!(0.)
+            } else if col & control != control {
+                c!((row == col) as u8 as f64)
+            } else {
+                gate[((row & target != 0) as usize, (col & target != 0) as usize)]
+            }
+        }))
+        .unwrap()
+    }
+}
+
+/// The KAK decomposition of a two-qubit unitary into single-qubit gates and
+/// at most three CNOTs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TwoQubitDecomposition {
+    /// The gates, in the order in which they are to be applied. Single-qubit
+    /// gates have no control qubit, CNOTs are represented as X gates with a
+    /// control qubit.
+    pub gates: Vec<DecomposedGate>,
+    /// The global phase φ, such that the decomposed unitary equals e^(iφ)
+    /// times the matrix of the gates.
+    pub phase: f64,
+}
+
+/// Returns the magic basis transformation, in which local gates are real
+/// orthogonal matrices and the canonical gates are diagonal.
+fn magic_basis() -> Matrix {
+    let s = c!(FRAC_1_SQRT_2);
+    let i = Complex64::new(0., FRAC_1_SQRT_2);
+    let o = c!(0.);
+    Matrix::new(vec![s, o, o, i, o, i, s, o, o, i, -s, o, s, o, o, -i]).unwrap()
+}
+
+/// Returns the eigenvectors of a real symmetric 4x4 matrix as the columns of
+/// an orthogonal matrix, using the Jacobi eigenvalue algorithm.
+fn jacobi(mut a: [[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut v = [[0.0; 4]; 4];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    for _ in 0..100 {
+        let off: f64 = (0..4)
+            .flat_map(|p| (0..4).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[p][q] * a[p][q])
+            .sum();
+        if off < 1.0e-30 {
+            break;
+        }
+        for p in 0..4 {
+            for q in p + 1..4 {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (pk, qk) = (a[p], a[q]);
+                for (k, (pk, qk)) in pk.iter().zip(qk.iter()).enumerate() {
+                    a[p][k] = c * pk - s * qk;
+                    a[q][k] = s * pk + c * qk;
+                }
+                for row in v.iter_mut() {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+    v
+}
+
+/// Diagonalizes a symmetric unitary 4x4 matrix M using a real orthogonal
+/// matrix P with determinant 1, such that P^T M P is diagonal.
+fn diagonalize(m: &Matrix) -> Result<Matrix> {
+    // The real and imaginary parts of M are commuting real symmetric
+    // matrices, so they can be diagonalized simultaneously by diagonalizing
+    // a linear combination of them. Some combinations may have degenerate
+    // eigenvalues that the other part does not, so we try several.
+    for angle in &[0.4, 1.3, 2.1, 2.9, 0.05] {
+        let (cos, sin) = (f64::cos(*angle), f64::sin(*angle));
+        let mut a = [[0.0; 4]; 4];
+        for (r, row) in a.iter_mut().enumerate() {
+            for (c, x) in row.iter_mut().enumerate() {
+                *x = cos * m[(r, c)].re + sin * m[(r, c)].im;
+            }
+        }
+        let v = jacobi(a);
+        let mut p = Matrix::new(v.iter().flat_map(|row| row.iter().map(|x| c!(*x))))?;
+        if det(&p).re < 0.0 {
+            for r in 0..4 {
+                p[(r, 0)] = -p[(r, 0)];
+            }
+        }
+        let d = transpose(&p).multiply(m)?.multiply(&p)?;
+        let off_diagonal: f64 = (0..16)
+            .filter(|i| i / 4 != i % 4)
+            .map(|i| d[i].norm_sqr())
+            .sum();
+        if off_diagonal < TOLERANCE {
+            return Ok(p);
+        }
+    }
+    err("failed to diagonalize matrix during KAK decomposition")
+}
+
+/// Factors a 4x4 matrix that is the tensor product of two single-qubit
+/// unitaries into said unitaries.
+fn factor(k: &Matrix) -> Result<(Matrix, Matrix)> {
+    let largest = (0..16)
+        .max_by(|&x, &y| k[x].norm().partial_cmp(&k[y].norm()).unwrap())
+        .unwrap();
+    let (r, c) = (largest / 4, largest % 4);
+    let a =
+        Matrix::new((0..4).map(|i| k[(2 * (i / 2) + r % 2, 2 * (i % 2) + c % 2)] / k[largest]))?;
+    let b = Matrix::new((0..4).map(|i| k[(2 * (r / 2) + i / 2, 2 * (c / 2) + i % 2)]))?;
+    let norm = det(&a).norm().sqrt();
+    Ok((scale(&a, c!(1.0 / norm)), scale(&b, c!(norm))))
+}
+
+impl TwoQubitDecomposition {
+    /// Decomposes a two-qubit unitary into single-qubit `R` gates and three
+    /// CNOTs, such that the canonical part of the decomposition consists of
+    /// Z and Y rotations.
+    ///
+    /// Returns an error if the matrix is not a 4x4 unitary matrix within
+    /// `epsilon`.
+    pub fn kak(matrix: &Matrix, epsilon: f64) -> Result<TwoQubitDecomposition> {
+        check_unitary(matrix, 2, epsilon)?;
+
+        // Normalize U to SU(4) and transform it to the magic basis.
+        let b = magic_basis();
+        let b_dag = b.adjoint();
+        let special = scale(matrix, Complex64::from_polar(1.0, -det(matrix).arg() / 4.0));
+        let ub = b_dag.multiply(&special)?.multiply(&b)?;
+
+        // Ub = K1 A K2, with K1 and K2 real orthogonal and A diagonal.
+        let ub_squared = transpose(&ub).multiply(&ub)?;
+        let p = diagonalize(&ub_squared)?;
+        let d = transpose(&p).multiply(&ub_squared)?.multiply(&p)?;
+        let mut theta = [0.0; 4];
+        for (j, theta) in theta.iter_mut().enumerate() {
+            *theta = d[(j, j)].arg() / 2.0;
+        }
+        let mut k1 = ub.multiply(&p)?;
+        for (j, theta) in theta.iter().enumerate() {
+            for r in 0..4 {
+                k1[(r, j)] *= Complex64::from_polar(1.0, -theta);
+            }
+        }
+        if det(&k1).re < 0.0 {
+            theta[0] += PI;
+            for r in 0..4 {
+                k1[(r, 0)] = -k1[(r, 0)];
+            }
+        }
+
+        // Transform back to the computational basis, where K1 and K2 are
+        // tensor products of single-qubit gates and A is the canonical gate
+        // exp(i(x XX + y YY + z ZZ)).
+        let (a1, b1) = factor(&b.multiply(&k1)?.multiply(&b_dag)?)?;
+        let (a2, b2) = factor(&b.multiply(&transpose(&p))?.multiply(&b_dag)?)?;
+        let x = (theta[0] + theta[1] - theta[2] - theta[3]) / 4.0;
+        let y = (-theta[0] + theta[1] - theta[2] + theta[3]) / 4.0;
+        let z = (theta[0] - theta[1] - theta[2] + theta[3]) / 4.0;
+
+        let single = |target, matrix: &Matrix| -> Result<DecomposedGate> {
+            // The factors are only unitary up to the accuracy of the
+            // decomposition, which is verified as a whole below.
+            let (gate, _) = EulerAngles::zyz(matrix, epsilon.max(1.0e-6))?.to_r();
+            Ok(DecomposedGate {
+                gate,
+                target,
+                control: None,
+            })
+        };
+        let mut gates = vec![single(0, &a2)?, single(1, &b2)?];
+        gates.extend(canonical(x, y, z));
+        gates.push(single(0, &a1)?);
+        gates.push(single(1, &b1)?);
+
+        // Determine the global phase, and verify the decomposition.
+        let mut decomposition = TwoQubitDecomposition { gates, phase: 0.0 };
+        let w = decomposition.matrix();
+        let overlap = (0..16).map(|i| matrix[i] * w[i].conj()).sum::<Complex64>();
+        decomposition.phase = overlap.arg();
+        if !decomposition.matrix().approx_eq(matrix, epsilon, false) {
+            return err("KAK decomposition failed");
+        }
+        Ok(decomposition)
+    }
+
+    /// Returns the number of CNOTs in the decomposition.
+    pub fn num_cnots(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|gate| gate.control.is_some())
+            .count()
+    }
+
+    /// Returns the matrix of the decomposed unitary, including the global
+    /// phase.
+    pub fn matrix(&self) -> Matrix {
+        let matrix = self
+            .gates
+            .iter()
+            .fold(Matrix::new_identity(4), |matrix, gate| {
+                gate.matrix(2).multiply(&matrix).unwrap()
+            });
+        scale(&matrix, Complex64::from_polar(1.0, self.phase))
+    }
+}
+
+/// Returns the gates implementing the canonical gate
+/// exp(i(a XX + b YY + c ZZ)) up to global phase using three CNOTs.
+fn canonical(a: f64, b: f64, c: f64) -> Vec<DecomposedGate> {
+    let single = |gate, target| DecomposedGate {
+        gate,
+        target,
+        control: None,
+    };
+    let cnot = |control, target| DecomposedGate {
+        gate: UnboundUnitaryGate::X,
+        target,
+        control: Some(control),
+    };
+    vec![
+        single(UnboundUnitaryGate::RZ(FRAC_PI_2), 0),
+        cnot(0, 1),
+        single(UnboundUnitaryGate::RZ(FRAC_PI_2 - 2.0 * c), 1),
+        single(UnboundUnitaryGate::RY(FRAC_PI_2 - 2.0 * a), 0),
+        cnot(1, 0),
+        single(UnboundUnitaryGate::RY(2.0 * b - FRAC_PI_2), 0),
+        cnot(0, 1),
+        single(UnboundUnitaryGate::RZ(-FRAC_PI_2), 1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tolerance used for the decompositions.
+    const EPSILON: f64 = 1.0e-6;
+
+    /// Returns a pseudo-random single-qubit unitary.
+    fn random_single(seed: f64) -> Matrix {
+        EulerAngles {
+            phase: seed * 1.3,
+            beta: seed * 2.7 + 0.4,
+            gamma: seed * 0.9 + 1.1,
+            delta: seed * 5.1,
+        }
+        .matrix()
+    }
+
+    /// Returns a pseudo-random two-qubit unitary.
+    fn random_two(seed: f64) -> Matrix {
+        let cnot = Matrix::from(UnboundUnitaryGate::X).add_controls(1);
+        let swap = Matrix::from(UnboundUnitaryGate::SWAP);
+        let reversed_cnot = swap.multiply(&cnot).unwrap().multiply(&swap).unwrap();
+        (0..4).fold(Matrix::new_identity(4), |matrix, i| {
+            let s = seed + i as f64;
+            let local = random_single(s).kron(&random_single(s * 1.7));
+            let cnot = if i % 2 == 0 { &cnot } else { &reversed_cnot };
+            cnot.multiply(&local).unwrap().multiply(&matrix).unwrap()
+        })
+    }
+
+    #[test]
+    fn zyz() {
+        let mut unitaries = vec![
+            UnboundUnitaryGate::I.into(),
+            UnboundUnitaryGate::X.into(),
+            UnboundUnitaryGate::Y.into(),
+            UnboundUnitaryGate::Z.into(),
+            UnboundUnitaryGate::H.into(),
+            UnboundUnitaryGate::T.into(),
+        ];
+        for seed in &[0.0, 0.3, 1.0, 2.5, 4.0] {
+            unitaries.push(random_single(*seed));
+        }
+        for u in unitaries {
+            let angles = EulerAngles::zyz(&u, EPSILON).unwrap();
+            assert!(angles.matrix().approx_eq(&u, EPSILON, false));
+
+            let (r, phase) = angles.to_r();
+            let r = scale(&Matrix::from(r), Complex64::from_polar(1.0, phase));
+            assert!(r.approx_eq(&u, EPSILON, false));
+        }
+
+        assert_eq!(
+            EulerAngles::zyz(&Matrix::new_identity(4), EPSILON)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected a 2x2 matrix, but got a 4x4 matrix"
+        );
+        assert_eq!(
+            EulerAngles::zyz(&matrix!(1., 1.; 0., 1.;), EPSILON)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: matrix is not unitary"
+        );
+    }
+
+    #[test]
+    fn canonical_gate() {
+        let pauli = |gate: UnboundUnitaryGate| Matrix::from(gate).kron(&gate.into());
+        let xx = pauli(UnboundUnitaryGate::X);
+        let yy = pauli(UnboundUnitaryGate::Y);
+        let zz = pauli(UnboundUnitaryGate::Z);
+        for (a, b, c) in &[(0.1, 0.2, 0.3), (0.7, -0.4, 0.25), (0.0, 0.0, 0.0)] {
+            // XX, YY, and ZZ commute and square to identity, so the
+            // exponential factorizes into cos + i sin terms.
+            let exp = |m: &Matrix, t: f64| {
+                Matrix::new((0..16).map(|i| {
+                    c!(t.cos()) * Matrix::new_identity(4)[i] + Complex64::new(0., t.sin()) * m[i]
+                }))
+                .unwrap()
+            };
+            let expected = exp(&xx, *a)
+                .multiply(&exp(&yy, *b))
+                .unwrap()
+                .multiply(&exp(&zz, *c))
+                .unwrap();
+            let actual = TwoQubitDecomposition {
+                gates: canonical(*a, *b, *c),
+                phase: 0.0,
+            }
+            .matrix();
+            assert!(actual.approx_eq(&expected, EPSILON, true));
+        }
+    }
+
+    #[test]
+    fn kak() {
+        let mut unitaries = vec![
+            UnboundUnitaryGate::SWAP.into(),
+            UnboundUnitaryGate::SQSWAP.into(),
+            Matrix::from(UnboundUnitaryGate::X).add_controls(1),
+            Matrix::from(UnboundUnitaryGate::Z).add_controls(1),
+            Matrix::new_identity(4),
+            random_single(0.2).kron(&random_single(1.4)),
+        ];
+        for seed in &[0.0, 0.5, 1.9, 3.3] {
+            unitaries.push(random_two(*seed));
+        }
+        for u in unitaries {
+            let decomposition = TwoQubitDecomposition::kak(&u, EPSILON).unwrap();
+            assert!(decomposition.num_cnots() <= 3);
+            assert!(decomposition.matrix().approx_eq(&u, EPSILON, false));
+            for gate in &decomposition.gates {
+                match gate.control {
+                    Some(control) => {
+                        assert_eq!(gate.gate, UnboundUnitaryGate::X);
+                        assert_ne!(control, gate.target);
+                    }
+                    None => assert!(gate.target < 2),
+                }
+            }
+        }
+
+        assert_eq!(
+            TwoQubitDecomposition::kak(&UnboundUnitaryGate::X.into(), EPSILON)
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: expected a 4x4 matrix, but got a 2x2 matrix"
+        );
+    }
+
+    #[test]
+    fn decomposed_gate() {
+        let cnot = DecomposedGate {
+            gate: UnboundUnitaryGate::X,
+            target: 0,
+            control: Some(1),
+        };
+        assert!(cnot.matrix(2).approx_eq(
+            &matrix!(
+                1., 0., 0., 0.;
+                0., 0., 0., 1.;
+                0., 0., 1., 0.;
+                0., 1., 0., 0.;
+            ),
+            EPSILON,
+            false
+        ));
+        let h = DecomposedGate {
+            gate: UnboundUnitaryGate::H,
+            target: 1,
+            control: None,
+        };
+        assert!(h.matrix(3).approx_eq(
+            &Matrix::new_identity(2)
+                .kron(&UnboundUnitaryGate::H.into())
+                .kron(&Matrix::new_identity(2)),
+            EPSILON,
+            false
+        ));
+    }
+}
@@ -210,6 +210,15 @@ impl From<ipc_channel::ipc::IpcError> for Error {
     }
 }
 
+impl From<serde_cbor::Error> for Error {
+    fn from(error: serde_cbor::Error) -> Error {
+        let msg = error.to_string();
+        Error {
+            ctx: Context::new(ErrorKind::IPCError(msg)),
+        }
+    }
+}
+
 impl From<strum::ParseError> for Error {
     fn from(error: strum::ParseError) -> Error {
         let msg = error.to_string();
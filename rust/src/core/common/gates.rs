@@ -39,9 +39,10 @@
 
 use crate::common::{
     converter::{
-        Converter, FixedMatrixConverter, MatrixConverterArb, PhaseKMatrixConverter,
-        PhaseMatrixConverter, RMatrixConverter, RxMatrixConverter, RyMatrixConverter,
-        RzMatrixConverter, UMatrixConverter, UnitaryConverter, UnitaryGateConverter,
+        CRkMatrixConverter, ControlledGateConverter, Converter, FSimMatrixConverter,
+        FixedMatrixConverter, MatrixConverterArb, PhaseKMatrixConverter, PhaseMatrixConverter,
+        RMatrixConverter, RxMatrixConverter, RyMatrixConverter, RzMatrixConverter,
+        UMatrixConverter, UnitaryConverter, UnitaryGateConverter,
     },
     types::{ArbData, Gate, Matrix, QubitRef},
 };
@@ -113,6 +114,21 @@ pub enum UnitaryGateType {
     SWAP,
     /// Square root of Swap.
     SQSWAP,
+    /// iSwap.
+    ISWAP,
+    /// Square root of iSwap.
+    SQISWAP,
+    /// Fermionic simulation gate with a swap angle (θ) and a controlled phase
+    /// angle (φ).
+    FSim,
+    /// Controlled Pauli-X.
+    CNOT,
+    /// Controlled Pauli-Z.
+    CZ,
+    /// Controlled PhaseK, i.e. a controlled phase gate with θ = π/2^k.
+    CRk,
+    /// Doubly-controlled Pauli-X.
+    TOFFOLI,
     /// Abstract unitary gate with number of target qubits specified.
     U(usize),
 }
@@ -178,6 +194,21 @@ pub enum UnboundUnitaryGate<'matrix> {
     SWAP,
     /// Square root of Swap.
     SQSWAP,
+    /// iSwap.
+    ISWAP,
+    /// Square root of iSwap.
+    SQISWAP,
+    /// Fermionic simulation gate with specified swap angle (θ) and controlled
+    /// phase angle (φ).
+    FSim(f64, f64),
+    /// Controlled Pauli-X.
+    CNOT,
+    /// Controlled Pauli-Z.
+    CZ,
+    /// Controlled PhaseK, i.e. a controlled phase gate with θ = π/2^k.
+    CRk(u64),
+    /// Doubly-controlled Pauli-X.
+    TOFFOLI,
     /// Abstract unitary gate with a reference to specified unitary matrix.
     U(&'matrix Matrix),
 }
@@ -245,6 +276,22 @@ pub enum BoundUnitaryGate<'matrix, 'qref> {
     SWAP(QubitRef, QubitRef),
     /// Square root of Swap with specified qubit targets.
     SQSWAP(QubitRef, QubitRef),
+    /// iSwap with specified qubit targets.
+    ISWAP(QubitRef, QubitRef),
+    /// Square root of iSwap with specified qubit targets.
+    SQISWAP(QubitRef, QubitRef),
+    /// Fermionic simulation gate with specified swap angle (θ), controlled
+    /// phase angle (φ), and qubit targets.
+    FSim(f64, f64, QubitRef, QubitRef),
+    /// Controlled Pauli-X with specified control and target qubit.
+    CNOT(QubitRef, QubitRef),
+    /// Controlled Pauli-Z with specified control and target qubit.
+    CZ(QubitRef, QubitRef),
+    /// Controlled PhaseK with specified k, control qubit, and target qubit.
+    CRk(u64, QubitRef, QubitRef),
+    /// Doubly-controlled Pauli-X with specified control qubits and target
+    /// qubit.
+    TOFFOLI(QubitRef, QubitRef, QubitRef),
     /// Abstract unitary gate with a reference to specified unitary matrix and
     /// qubit targets.
     U(&'matrix Matrix, &'qref [QubitRef]),
@@ -279,6 +326,13 @@ impl<'matrix> From<BoundUnitaryGate<'matrix, '_>> for UnboundUnitaryGate<'matrix
             BoundUnitaryGate::R(theta, phi, lambda, _) => UnboundUnitaryGate::R(theta, phi, lambda),
             BoundUnitaryGate::SWAP(_, _) => UnboundUnitaryGate::SWAP,
             BoundUnitaryGate::SQSWAP(_, _) => UnboundUnitaryGate::SQSWAP,
+            BoundUnitaryGate::ISWAP(_, _) => UnboundUnitaryGate::ISWAP,
+            BoundUnitaryGate::SQISWAP(_, _) => UnboundUnitaryGate::SQISWAP,
+            BoundUnitaryGate::FSim(theta, phi, _, _) => UnboundUnitaryGate::FSim(theta, phi),
+            BoundUnitaryGate::CNOT(_, _) => UnboundUnitaryGate::CNOT,
+            BoundUnitaryGate::CZ(_, _) => UnboundUnitaryGate::CZ,
+            BoundUnitaryGate::CRk(k, _, _) => UnboundUnitaryGate::CRk(k),
+            BoundUnitaryGate::TOFFOLI(_, _, _) => UnboundUnitaryGate::TOFFOLI,
             BoundUnitaryGate::U(matrix, _) => UnboundUnitaryGate::U(matrix),
         }
     }
@@ -324,9 +378,27 @@ impl From<BoundUnitaryGate<'_, '_>> for Gate {
             | BoundUnitaryGate::Phase(_, q)
             | BoundUnitaryGate::PhaseK(_, q)
             | BoundUnitaryGate::R(_, _, _, q) => Gate::new_unitary(vec![q], vec![], matrix),
-            BoundUnitaryGate::SWAP(q1, q2) | BoundUnitaryGate::SQSWAP(q1, q2) => {
+            BoundUnitaryGate::SWAP(q1, q2)
+            | BoundUnitaryGate::SQSWAP(q1, q2)
+            | BoundUnitaryGate::ISWAP(q1, q2)
+            | BoundUnitaryGate::SQISWAP(q1, q2)
+            | BoundUnitaryGate::FSim(_, _, q1, q2) => {
                 Gate::new_unitary(vec![q1, q2], vec![], matrix)
             }
+            BoundUnitaryGate::CNOT(c, t) => {
+                Gate::new_unitary(vec![t], vec![c], Matrix::from(UnboundUnitaryGate::X))
+            }
+            BoundUnitaryGate::CZ(c, t) => {
+                Gate::new_unitary(vec![t], vec![c], Matrix::from(UnboundUnitaryGate::Z))
+            }
+            BoundUnitaryGate::CRk(k, c, t) => Gate::new_unitary(
+                vec![t],
+                vec![c],
+                Matrix::from(UnboundUnitaryGate::PhaseK(k)),
+            ),
+            BoundUnitaryGate::TOFFOLI(c1, c2, t) => {
+                Gate::new_unitary(vec![t], vec![c1, c2], Matrix::from(UnboundUnitaryGate::X))
+            }
             BoundUnitaryGate::U(matrix, q) => Gate::new_unitary(q.to_vec(), vec![], matrix.clone()),
         }
         .unwrap()
@@ -362,6 +434,13 @@ impl From<UnboundUnitaryGate<'_>> for UnitaryGateType {
             UnboundUnitaryGate::R(_, _, _) => UnitaryGateType::R,
             UnboundUnitaryGate::SWAP => UnitaryGateType::SWAP,
             UnboundUnitaryGate::SQSWAP => UnitaryGateType::SQSWAP,
+            UnboundUnitaryGate::ISWAP => UnitaryGateType::ISWAP,
+            UnboundUnitaryGate::SQISWAP => UnitaryGateType::SQISWAP,
+            UnboundUnitaryGate::FSim(_, _) => UnitaryGateType::FSim,
+            UnboundUnitaryGate::CNOT => UnitaryGateType::CNOT,
+            UnboundUnitaryGate::CZ => UnitaryGateType::CZ,
+            UnboundUnitaryGate::CRk(_) => UnitaryGateType::CRk,
+            UnboundUnitaryGate::TOFFOLI => UnitaryGateType::TOFFOLI,
             UnboundUnitaryGate::U(matrix) => UnitaryGateType::U(matrix.num_qubits().unwrap_or(0)),
         }
     }
@@ -474,6 +553,26 @@ impl From<UnboundUnitaryGate<'_>> for Matrix {
                 0., 0.,           0.,         1.
             ),
 
+            UnboundUnitaryGate::ISWAP => matrix!(
+                1.,  0.,       0.,      0.;
+                0.,  0.,      (0., 1.), 0.;
+                0., (0., 1.),  0.,      0.;
+                0.,  0.,       0.,      1.
+            ),
+
+            UnboundUnitaryGate::SQISWAP => matrix!(
+                1.,  0.,                  0.,                 0.;
+                0.,  FRAC_1_SQRT_2,      (0., FRAC_1_SQRT_2), 0.;
+                0., (0., FRAC_1_SQRT_2),  FRAC_1_SQRT_2,      0.;
+                0.,  0.,                  0.,                 1.
+            ),
+
+            UnboundUnitaryGate::CNOT => Matrix::from(UnboundUnitaryGate::X).add_controls(1),
+
+            UnboundUnitaryGate::CZ => Matrix::from(UnboundUnitaryGate::Z).add_controls(1),
+
+            UnboundUnitaryGate::TOFFOLI => Matrix::from(UnboundUnitaryGate::X).add_controls(2),
+
             UnboundUnitaryGate::RX(theta) => {
                 let a = c!((0.5 * theta).cos());
                 let b = c!(0., -1.) * (0.5 * theta).sin();
@@ -516,6 +615,36 @@ impl From<UnboundUnitaryGate<'_>> for Matrix {
                 .unwrap()
             }
 
+            UnboundUnitaryGate::FSim(theta, phi) => {
+                let a = c!(theta.cos());
+                let b = c!(0., -theta.sin());
+                let z = c!(0.);
+                vec![
+                    c!(1.),
+                    z,
+                    z,
+                    z,
+                    z,
+                    a,
+                    b,
+                    z,
+                    z,
+                    b,
+                    a,
+                    z,
+                    z,
+                    z,
+                    z,
+                    c!(0., -phi).exp(),
+                ]
+                .try_into()
+                .unwrap()
+            }
+
+            UnboundUnitaryGate::CRk(k) => {
+                Matrix::from(UnboundUnitaryGate::PhaseK(k)).add_controls(1)
+            }
+
             UnboundUnitaryGate::U(matrix) => matrix.clone(),
         }
     }
@@ -530,7 +659,9 @@ impl TryFrom<UnitaryGateType> for UnboundUnitaryGate<'_> {
             | UnitaryGateType::RZ
             | UnitaryGateType::Phase
             | UnitaryGateType::PhaseK
-            | UnitaryGateType::R => Err("gate is parameterized"),
+            | UnitaryGateType::R
+            | UnitaryGateType::FSim
+            | UnitaryGateType::CRk => Err("gate is parameterized"),
             UnitaryGateType::U(_) => Err("gate is parameterized"),
             UnitaryGateType::I => Ok(UnboundUnitaryGate::I),
             UnitaryGateType::X => Ok(UnboundUnitaryGate::X),
@@ -552,6 +683,11 @@ impl TryFrom<UnitaryGateType> for UnboundUnitaryGate<'_> {
             UnitaryGateType::RZ180 => Ok(UnboundUnitaryGate::RZ180),
             UnitaryGateType::SWAP => Ok(UnboundUnitaryGate::SWAP),
             UnitaryGateType::SQSWAP => Ok(UnboundUnitaryGate::SQSWAP),
+            UnitaryGateType::ISWAP => Ok(UnboundUnitaryGate::ISWAP),
+            UnitaryGateType::SQISWAP => Ok(UnboundUnitaryGate::SQISWAP),
+            UnitaryGateType::CNOT => Ok(UnboundUnitaryGate::CNOT),
+            UnitaryGateType::CZ => Ok(UnboundUnitaryGate::CZ),
+            UnitaryGateType::TOFFOLI => Ok(UnboundUnitaryGate::TOFFOLI),
         }
     }
 }
@@ -572,6 +708,8 @@ impl From<UnitaryGateType> for Box<dyn MatrixConverterArb> {
             UnitaryGateType::Phase => Box::new(PhaseMatrixConverter::default()),
             UnitaryGateType::PhaseK => Box::new(PhaseKMatrixConverter::default()),
             UnitaryGateType::R => Box::new(RMatrixConverter::default()),
+            UnitaryGateType::FSim => Box::new(FSimMatrixConverter::default()),
+            UnitaryGateType::CRk => Box::new(CRkMatrixConverter::default()),
             UnitaryGateType::U(num_qubits) => Box::new(UMatrixConverter::new(Some(num_qubits))),
             _ => Box::new(FixedMatrixConverter::from(
                 Matrix::try_from(gate_type).unwrap(),
@@ -624,6 +762,33 @@ impl UnitaryGateType {
                 epsilon,
                 ignore_global_phase,
             ))),
+            UnitaryGateType::FSim => Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
+                FSimMatrixConverter::default(),
+                num_controls,
+                epsilon,
+                ignore_global_phase,
+            ))),
+            UnitaryGateType::CNOT | UnitaryGateType::CZ => Box::new(ControlledGateConverter::new(
+                FixedMatrixConverter::from(Matrix::try_from(self).unwrap()),
+                1,
+                num_controls,
+                epsilon,
+                ignore_global_phase,
+            )),
+            UnitaryGateType::CRk => Box::new(ControlledGateConverter::new(
+                CRkMatrixConverter::default(),
+                1,
+                num_controls,
+                epsilon,
+                ignore_global_phase,
+            )),
+            UnitaryGateType::TOFFOLI => Box::new(ControlledGateConverter::new(
+                FixedMatrixConverter::from(Matrix::try_from(self).unwrap()),
+                2,
+                num_controls,
+                epsilon,
+                ignore_global_phase,
+            )),
             UnitaryGateType::U(num_qubits) => {
                 Box::new(UnitaryGateConverter::from(UnitaryConverter::new(
                     UMatrixConverter::new(Some(num_qubits)),
@@ -690,6 +855,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn two_qubit_gates() {
+        assert!(check(
+            UnboundUnitaryGate::ISWAP,
+            UnboundUnitaryGate::FSim(-PI / 2., 0.)
+        ));
+        assert!(check(
+            UnboundUnitaryGate::SQISWAP,
+            UnboundUnitaryGate::FSim(-PI / 4., 0.)
+        ));
+        assert!(check(UnboundUnitaryGate::CZ, UnboundUnitaryGate::CRk(0)));
+        let sqiswap = Matrix::from(UnboundUnitaryGate::SQISWAP);
+        assert!(sqiswap.multiply(&sqiswap).unwrap().approx_eq(
+            &UnboundUnitaryGate::ISWAP.into(),
+            1e-15,
+            false
+        ));
+    }
+
     #[test]
     fn gates_coversions() {
         for gate in vec![
@@ -719,6 +903,13 @@ mod tests {
             (UnboundUnitaryGate::R(1., 1., 1.), UnitaryGateType::R),
             (UnboundUnitaryGate::SWAP, UnitaryGateType::SWAP),
             (UnboundUnitaryGate::SQSWAP, UnitaryGateType::SQSWAP),
+            (UnboundUnitaryGate::ISWAP, UnitaryGateType::ISWAP),
+            (UnboundUnitaryGate::SQISWAP, UnitaryGateType::SQISWAP),
+            (UnboundUnitaryGate::FSim(1., 1.), UnitaryGateType::FSim),
+            (UnboundUnitaryGate::CNOT, UnitaryGateType::CNOT),
+            (UnboundUnitaryGate::CZ, UnitaryGateType::CZ),
+            (UnboundUnitaryGate::CRk(1), UnitaryGateType::CRk),
+            (UnboundUnitaryGate::TOFFOLI, UnitaryGateType::TOFFOLI),
             (
                 UnboundUnitaryGate::U(&Matrix::new(vec![c!(1.), c!(1.), c!(1.), c!(1.)]).unwrap()),
                 UnitaryGateType::U(1),
@@ -732,6 +923,7 @@ mod tests {
 
         let a = QubitRef::from_foreign(1).unwrap();
         let b = QubitRef::from_foreign(2).unwrap();
+        let c = QubitRef::from_foreign(3).unwrap();
         for gate in vec![
             (
                 BoundUnitaryGate::I(a),
@@ -863,6 +1055,41 @@ mod tests {
                 UnboundUnitaryGate::SQSWAP,
                 UnitaryGateType::SQSWAP,
             ),
+            (
+                BoundUnitaryGate::ISWAP(a, b),
+                UnboundUnitaryGate::ISWAP,
+                UnitaryGateType::ISWAP,
+            ),
+            (
+                BoundUnitaryGate::SQISWAP(a, b),
+                UnboundUnitaryGate::SQISWAP,
+                UnitaryGateType::SQISWAP,
+            ),
+            (
+                BoundUnitaryGate::FSim(1., 2., a, b),
+                UnboundUnitaryGate::FSim(1., 2.),
+                UnitaryGateType::FSim,
+            ),
+            (
+                BoundUnitaryGate::CNOT(a, b),
+                UnboundUnitaryGate::CNOT,
+                UnitaryGateType::CNOT,
+            ),
+            (
+                BoundUnitaryGate::CZ(a, b),
+                UnboundUnitaryGate::CZ,
+                UnitaryGateType::CZ,
+            ),
+            (
+                BoundUnitaryGate::CRk(1, a, b),
+                UnboundUnitaryGate::CRk(1),
+                UnitaryGateType::CRk,
+            ),
+            (
+                BoundUnitaryGate::TOFFOLI(a, b, c),
+                UnboundUnitaryGate::TOFFOLI,
+                UnitaryGateType::TOFFOLI,
+            ),
             (
                 BoundUnitaryGate::U(
                     &Matrix::new(vec![c!(1.), c!(1.), c!(1.), c!(1.)]).unwrap(),
@@ -918,8 +1145,14 @@ mod tests {
                 Gate::from(bound_gate)
             );
         }
-        for bound_gate in
-            vec![BoundUnitaryGate::SWAP(a, b), BoundUnitaryGate::SQSWAP(a, b)].into_iter()
+        for bound_gate in vec![
+            BoundUnitaryGate::SWAP(a, b),
+            BoundUnitaryGate::SQSWAP(a, b),
+            BoundUnitaryGate::ISWAP(a, b),
+            BoundUnitaryGate::SQISWAP(a, b),
+            BoundUnitaryGate::FSim(1., 2., a, b),
+        ]
+        .into_iter()
         {
             assert_eq!(
                 Gate::new_unitary(
@@ -932,6 +1165,27 @@ mod tests {
             );
         }
 
+        for (bound_gate, qubits) in vec![
+            (BoundUnitaryGate::CNOT(a, b), vec![a, b]),
+            (BoundUnitaryGate::CZ(a, b), vec![a, b]),
+            (BoundUnitaryGate::CRk(2, a, b), vec![a, b]),
+            (BoundUnitaryGate::TOFFOLI(a, b, c), vec![a, b, c]),
+        ]
+        .into_iter()
+        {
+            let gate = Gate::from(bound_gate);
+            assert_eq!(gate.get_targets(), &qubits[qubits.len() - 1..]);
+            assert_eq!(
+                gate.with_matrix_controls(),
+                Gate::new_unitary(
+                    qubits,
+                    vec![],
+                    Matrix::from(UnboundUnitaryGate::from(bound_gate))
+                )
+                .unwrap()
+            );
+        }
+
         assert_eq!(
             Gate::from(BoundUnitaryGate::U(&Matrix::new_identity(2), &[a])),
             Gate::new_unitary(vec![a], vec![], Matrix::new_identity(2)).unwrap()
@@ -4,8 +4,11 @@
 pub mod util;
 pub mod channel;
 pub mod converter;
+pub mod decomposition;
 pub mod error;
 pub mod gates;
 pub mod log;
 pub mod protocol;
+pub mod shared_memory;
+pub mod transport;
 pub mod types;
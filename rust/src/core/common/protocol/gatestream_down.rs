@@ -18,6 +18,13 @@ pub enum GatestreamDown {
     /// should not do anything with the sequence number.
     Pipelined(SequenceNumber, PipelinedGatestreamDown),
 
+    /// A batch of pipelined gatestream requests.
+    ///
+    /// This is equivalent to sending a `Pipelined` message for each entry in
+    /// order, but only incurs the IPC overhead once. The sequence numbers
+    /// must be monotonously increasing within the batch as well.
+    PipelinedBatch(Vec<(SequenceNumber, PipelinedGatestreamDown)>),
+
     /// Requests execution of the given `ArbCmd` by the plugin.
     ///
     /// The valid responses to this message are:
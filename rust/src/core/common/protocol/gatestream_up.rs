@@ -31,4 +31,11 @@ pub enum GatestreamUp {
 
     /// Indicates that a `GatestreamDown::ArbRequest` failed.
     ArbFailure(String),
+
+    /// A batch of responses to pipelined requests.
+    ///
+    /// This is equivalent to sending the contained messages one by one in
+    /// order, but only incurs the IPC overhead once. Only `CompletedUpTo`,
+    /// `Failure`, `Measured`, and `Advanced` messages may be batched.
+    Batch(Vec<GatestreamUp>),
 }
@@ -0,0 +1,41 @@
+use crate::common::types::PluginCapability;
+use serde::{Deserialize, Serialize};
+
+/// The version of the simulator/plugin and gatestream protocols. This must be
+/// incremented whenever a change is made to them that breaks compatibility
+/// between plugins and simulators built against different versions of DQCsim.
+pub const PROTOCOL_VERSION: u32 = 4;
+
+/// Handshake message, exchanged between the simulator and a plugin before
+/// anything else.
+///
+/// The handshake is the first variant of both `SimulatorToPlugin` and
+/// `PluginToSimulator`. Its layout must never change, such that simulators
+/// and plugins built against different versions of DQCsim can always decode
+/// it, and can thus report a protocol version mismatch instead of failing to
+/// decode the messages that follow it.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// The protocol version used by the sender.
+    pub protocol_version: u32,
+
+    /// The capabilities of the sender, encoded using
+    /// `PluginCapability::to_mask()`. This is always zero for the simulator.
+    pub capabilities: u64,
+}
+
+impl Handshake {
+    /// Constructs a handshake for the current protocol version with the given
+    /// capabilities.
+    pub fn new<'a>(capabilities: impl IntoIterator<Item = &'a PluginCapability>) -> Handshake {
+        Handshake {
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: PluginCapability::to_mask(capabilities),
+        }
+    }
+
+    /// Returns the capabilities of the sender.
+    pub fn get_capabilities(&self) -> Vec<PluginCapability> {
+        PluginCapability::from_mask(self.capabilities)
+    }
+}
@@ -1,5 +1,9 @@
 //! Defines the protocols for all forms of communication.
 
+// Handshake exchanged by the simulator and plugins before anything else.
+mod handshake;
+pub use handshake::{Handshake, PROTOCOL_VERSION};
+
 // Requests from simulator to plugin.
 mod simulator_to_plugin;
 pub use simulator_to_plugin::{
@@ -22,3 +26,7 @@ pub use gatestream_down::{GatestreamDown, PipelinedGatestreamDown};
 // Gatestream response messages.
 mod gatestream_up;
 pub use gatestream_up::GatestreamUp;
+
+// Gatestream trace files.
+mod trace;
+pub use trace::{TraceHeader, TraceReader, TraceRecord, TraceWriter, TRACE_VERSION};
@@ -1,9 +1,18 @@
-use crate::common::types::{ArbData, PluginMetadata};
+use crate::common::{
+    protocol::Handshake,
+    types::{ArbData, PluginMetadata},
+};
 use serde::{Deserialize, Serialize};
 
 /// Plugin to simulator responses.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum PluginToSimulator {
+    /// Response to `SimulatorToPlugin::Handshake`, containing the protocol
+    /// version and capabilities of the plugin. This variant must remain the
+    /// first, such that simulators built against other versions of DQCsim can
+    /// decode it.
+    Handshake(Handshake),
+
     /// Success response to requests that don't return data..
     Success,
 
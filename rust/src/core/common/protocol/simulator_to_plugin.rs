@@ -1,6 +1,7 @@
 use crate::{
     common::{
         log::LogRecord,
+        protocol::Handshake,
         types::{ArbCmd, ArbData, PluginType},
     },
     host::configuration::PluginLogConfiguration,
@@ -11,9 +12,25 @@ use serde::{Deserialize, Serialize};
 /// Simulator/host to plugin requests.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum SimulatorToPlugin {
+    /// Handshake containing the protocol version of the simulator.
+    ///
+    /// This is always the first message sent by DQCsim. This variant must
+    /// remain the first, such that plugins built against other versions of
+    /// DQCsim can decode it; see `Handshake`. In response, the plugin must
+    /// send its own handshake, containing its protocol version and
+    /// capabilities. The simulator does not send anything else before it
+    /// receives this response. If the protocol versions differ, the plugin
+    /// must not decode any further messages, and the simulator rejects the
+    /// plugin.
+    ///
+    /// The valid responses to this message are:
+    ///
+    ///  - success: `PluginToSimulator::Handshake`
+    Handshake(Handshake),
+
     /// Request to initialize the plugin.
     ///
-    /// This is always the first message sent by DQCsim. In response, the
+    /// This is always the second message sent by DQCsim. In response, the
     /// plugin must:
     ///
     ///  - initialize its logging facilities (note that the tee files provided
@@ -35,7 +52,7 @@ pub enum SimulatorToPlugin {
 
     /// Request to complete the connection with the upstream plugin.
     ///
-    /// This is always the second message sent by DQCsim for operators and
+    /// This is always the third message sent by DQCsim for operators and
     /// backends. It is called after the upstream plugin has been successfully
     /// initialized. In response, the plugin must wait for the upstream plugin
     /// to connect and finish setting up the connection.
@@ -48,7 +65,7 @@ pub enum SimulatorToPlugin {
 
     /// Request to run user initialization code.
     ///
-    /// This is always the second (frontend) or third (operator, backend)
+    /// This is always the third (frontend) or fourth (operator, backend)
     /// message sent by DQCsim.
     ///
     /// The valid responses to this message are:
@@ -95,6 +112,12 @@ pub enum SimulatorToPlugin {
     ArbRequest(ArbCmd),
 }
 
+impl From<Handshake> for SimulatorToPlugin {
+    fn from(handshake: Handshake) -> SimulatorToPlugin {
+        SimulatorToPlugin::Handshake(handshake)
+    }
+}
+
 impl Into<SimulatorToPlugin> for ArbCmd {
     fn into(self) -> SimulatorToPlugin {
         SimulatorToPlugin::ArbRequest(self)
@@ -0,0 +1,163 @@
+use crate::common::{
+    error::{inv_arg, Result},
+    protocol::PipelinedGatestreamDown,
+    types::{ArbCmd, QubitMeasurementResult},
+};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Lines, Write};
+
+/// The version of the gatestream trace file format written by
+/// `TraceWriter`.
+pub const TRACE_VERSION: u32 = 1;
+
+/// The first line of a gatestream trace file.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    /// The version of the file format.
+    pub version: u32,
+}
+
+/// A record in a gatestream trace file.
+///
+/// A gatestream trace file is a JSON-lines file. The first line contains a
+/// `TraceHeader`, and each subsequent line contains a `TraceRecord`, in the
+/// order in which the messages passed through the gatestream.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum TraceRecord {
+    /// A pipelined request sent downstream.
+    Request(PipelinedGatestreamDown),
+
+    /// An `ArbCmd` sent downstream.
+    Arb(ArbCmd),
+
+    /// A measurement result received from downstream.
+    Measured(QubitMeasurementResult),
+}
+
+/// Writes gatestream trace files.
+#[derive(Debug)]
+pub struct TraceWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Constructs a trace writer, writing the header of the trace file.
+    pub fn new(mut writer: W) -> Result<TraceWriter<W>> {
+        serde_json::to_writer(
+            &mut writer,
+            &TraceHeader {
+                version: TRACE_VERSION,
+            },
+        )?;
+        writeln!(writer)?;
+        Ok(TraceWriter { writer })
+    }
+
+    /// Writes a record to the trace file. The underlying writer is flushed
+    /// after each record, such that the trace is complete up to the last
+    /// message even if the simulation is aborted.
+    pub fn write(&mut self, record: &TraceRecord) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads gatestream trace files, iterating over their records.
+#[derive(Debug)]
+pub struct TraceReader<R: BufRead> {
+    lines: Lines<R>,
+}
+
+impl<R: BufRead> TraceReader<R> {
+    /// Constructs a trace reader, verifying the header of the trace file.
+    pub fn new(reader: R) -> Result<TraceReader<R>> {
+        let mut lines = reader.lines();
+        let header: TraceHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)?,
+            None => return inv_arg("trace file is empty"),
+        };
+        if header.version != TRACE_VERSION {
+            return inv_arg(format!(
+                "unsupported trace file version {}; expected version {}",
+                header.version, TRACE_VERSION
+            ));
+        }
+        Ok(TraceReader { lines })
+    }
+}
+
+impl<R: BufRead> Iterator for TraceReader<R> {
+    type Item = Result<TraceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines
+            .next()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::{ArbData, Gate, Matrix, QubitMeasurementValue, QubitRef};
+    use num_complex::Complex64;
+
+    #[test]
+    fn round_trip() {
+        let qubit = QubitRef::from_foreign(1).unwrap();
+        let x = Matrix::new(vec![
+            Complex64::new(0f64, 0f64),
+            Complex64::new(1f64, 0f64),
+            Complex64::new(1f64, 0f64),
+            Complex64::new(0f64, 0f64),
+        ])
+        .unwrap();
+        let records = vec![
+            TraceRecord::Request(PipelinedGatestreamDown::Allocate(
+                1,
+                vec![ArbCmd::new("a", "b", ArbData::from_args(vec![vec![1, 2]]))],
+            )),
+            TraceRecord::Request(PipelinedGatestreamDown::Gate(
+                Gate::new_unitary(vec![qubit], vec![], x).unwrap(),
+            )),
+            TraceRecord::Request(PipelinedGatestreamDown::Gate(
+                Gate::new_barrier(vec![qubit]).unwrap().with_duration(2),
+            )),
+            TraceRecord::Arb(ArbCmd::new("c", "d", ArbData::default())),
+            TraceRecord::Measured(QubitMeasurementResult::new(
+                qubit,
+                QubitMeasurementValue::One,
+                ArbData::default(),
+            )),
+            TraceRecord::Request(PipelinedGatestreamDown::Advance(3)),
+            TraceRecord::Request(PipelinedGatestreamDown::Free(vec![qubit])),
+        ];
+
+        let mut writer = TraceWriter::new(vec![]).unwrap();
+        for record in records.iter() {
+            writer.write(record).unwrap();
+        }
+        let buffer = writer.writer;
+        assert!(buffer.starts_with(b"{\"version\":1}\n"));
+
+        let reader = TraceReader::new(&buffer[..]).unwrap();
+        let read: Vec<_> = reader.map(|record| record.unwrap()).collect();
+        assert_eq!(read, records);
+    }
+
+    #[test]
+    fn version() {
+        assert_eq!(
+            TraceReader::new(&b"{\"version\":2}\n"[..])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: unsupported trace file version 2; expected version 1"
+        );
+        assert_eq!(
+            TraceReader::new(&b""[..]).unwrap_err().to_string(),
+            "Invalid argument: trace file is empty"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Shared-memory transfer of large binary payloads.
+//!
+//! Large gate matrices and `ArbData` arguments would otherwise be serialized
+//! and copied through the `ipc_channel` sockets on every hop of the pipeline.
+//! Instead, payloads of at least [`SHARED_MEMORY_THRESHOLD`] bytes that are
+//! sent through an `ipc_channel` sender within [`with_shared_memory()`] are
+//! moved into an `IpcSharedMemory` region, such that only a handle to the
+//! region is sent along with the message.
+//!
+//! Shared memory regions can only be serialized by `ipc_channel` itself, so
+//! payloads are serialized inline in all other cases, for instance when they
+//! are sent over a TCP connection. Human-readable formats, such as those used
+//! for reproduction files, always use the plain representation of the data.
+//!
+//! The functions in this module can be used with serde's `with` attribute on
+//! `Vec<u8>` fields.
+//!
+//! [`SHARED_MEMORY_THRESHOLD`]: ./constant.SHARED_MEMORY_THRESHOLD.html
+//! [`with_shared_memory()`]: ./fn.with_shared_memory.html
+
+use ipc_channel::ipc::IpcSharedMemory;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+
+/// The minimum size in bytes of the payloads that are transferred through
+/// shared memory.
+pub const SHARED_MEMORY_THRESHOLD: usize = 64 * 1024;
+
+thread_local! {
+    /// Whether payloads may currently be serialized into shared memory.
+    static SHARED_MEMORY_ENABLED: Cell<bool> = Cell::new(false);
+}
+
+/// Representation of a payload in non-human-readable formats, used for
+/// serialization.
+#[derive(Serialize)]
+enum PayloadRef<'a> {
+    /// The payload is serialized inline.
+    Inline(&'a [u8]),
+
+    /// The payload is stored in a shared memory region.
+    Shared(IpcSharedMemory),
+}
+
+/// Representation of a payload in non-human-readable formats, used for
+/// deserialization. The variants must match those of `PayloadRef`.
+#[derive(Deserialize)]
+enum Payload {
+    /// The payload is serialized inline.
+    Inline(Vec<u8>),
+
+    /// The payload is stored in a shared memory region.
+    Shared(IpcSharedMemory),
+}
+
+/// Calls the given closure, allowing large payloads to be serialized into
+/// shared memory regions while it runs.
+///
+/// The closure must not serialize anything other than the messages it sends
+/// through `ipc_channel` senders, as shared memory regions cannot be
+/// serialized in any other way.
+pub fn with_shared_memory<R>(f: impl FnOnce() -> R) -> R {
+    /// Restores the previous state when dropped, even when `f` panics.
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SHARED_MEMORY_ENABLED.with(|enabled| enabled.set(self.0));
+        }
+    }
+    let _restore = Restore(SHARED_MEMORY_ENABLED.with(|enabled| enabled.replace(true)));
+    f()
+}
+
+/// Serializes a payload, moving it into a shared memory region if this is
+/// allowed and the payload is large enough.
+pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        bytes.serialize(serializer)
+    } else if bytes.len() >= SHARED_MEMORY_THRESHOLD && SHARED_MEMORY_ENABLED.with(Cell::get) {
+        PayloadRef::Shared(IpcSharedMemory::from_bytes(bytes)).serialize(serializer)
+    } else {
+        PayloadRef::Inline(bytes).serialize(serializer)
+    }
+}
+
+/// Deserializes a payload serialized by `serialize()`.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        Vec::deserialize(deserializer)
+    } else {
+        Ok(match Payload::deserialize(deserializer)? {
+            Payload::Inline(bytes) => bytes,
+            Payload::Shared(memory) => memory.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ipc_channel::ipc;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Message(#[serde(with = "super")] Vec<u8>);
+
+    fn payloads() -> Vec<Message> {
+        vec![
+            Message(vec![]),
+            Message(vec![1, 2, 3]),
+            Message((0..SHARED_MEMORY_THRESHOLD).map(|i| i as u8).collect()),
+            Message(vec![42; SHARED_MEMORY_THRESHOLD * 3]),
+        ]
+    }
+
+    #[test]
+    fn ipc() {
+        let (tx, rx) = ipc::channel().unwrap();
+        for payload in payloads() {
+            with_shared_memory(|| tx.send(payload.clone())).unwrap();
+            assert_eq!(rx.recv().unwrap(), payload);
+        }
+        assert!(!SHARED_MEMORY_ENABLED.with(Cell::get));
+    }
+
+    #[test]
+    fn cbor() {
+        for payload in payloads() {
+            let cbor = serde_cbor::to_vec(&payload).unwrap();
+            assert_eq!(serde_cbor::from_slice::<Message>(&cbor).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn json() {
+        let json = serde_json::to_string(&Message(vec![1, 2, 3])).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        assert_eq!(
+            serde_json::from_str::<Message>(&json).unwrap(),
+            Message(vec![1, 2, 3])
+        );
+    }
+}
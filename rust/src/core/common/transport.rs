@@ -0,0 +1,792 @@
+//! Transport layer for simulator and gatestream connections.
+//!
+//! By default, DQCsim connects the simulator to its plugins and the plugins
+//! to each other using `ipc_channel` one-shot servers, which only work for
+//! processes running on the same machine. This module adds TCP and Unix domain
+//! socket transports that carry the same protocol messages, such that plugins
+//! can also run elsewhere.
+//!
+//! Servers are identified by address strings. Addresses of the form
+//! `tcp://<host>:<port>` and `unix://<path>` refer to socket servers; all
+//! other addresses are interpreted as `ipc_channel` one-shot server names.
+//! The host may be omitted from TCP addresses, as in `tcp://:<port>`, in which
+//! case the loopback address `127.0.0.1` is used. Messages sent over sockets
+//! are serialized using CBOR and prefixed with their length in bytes as a
+//! little-endian 64-bit integer. Frames larger than [`MAX_FRAME_SIZE`] are
+//! rejected.
+//!
+//! Socket connections are bridged to local `ipc_channel` channels by
+//! background threads, so the rest of DQCsim deals with the same channel
+//! types regardless of the transport that is used.
+//!
+//! # Security
+//!
+//! The socket transports do not authenticate or encrypt anything. A TCP
+//! server accepts the first peer that connects to it, and that peer is
+//! trusted in the same way as a plugin started by DQCsim itself. Anyone who
+//! can reach the port while DQCsim is waiting for a connection can thus take
+//! the place of a plugin. Plugins that are connected over TCP serve their
+//! upstream connection on the local address that they used to connect to the
+//! simulator, so they are only exposed beyond the loopback interface when
+//! the simulator is. Prefer Unix domain sockets, the access to which is
+//! controlled by the file system permissions, or loopback addresses combined
+//! with a secure tunnel such as SSH port forwarding when plugins run on other
+//! machines.
+//!
+//! [`MAX_FRAME_SIZE`]: ./constant.MAX_FRAME_SIZE.html
+
+use crate::{
+    common::{
+        channel::{
+            DownstreamChannel, IpcChannel, PluginChannel, SimulatorChannel, UpstreamChannel,
+        },
+        error::{err, inv_arg, Result},
+        log::LogRecord,
+        protocol::{PluginInitializeRequest, PluginToSimulator, SimulatorToPlugin},
+        types::PluginType,
+    },
+    host::configuration::PluginLogConfiguration,
+    trace,
+};
+use ipc_channel::ipc::{self, IpcOneShotServer, IpcReceiver, IpcSender};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{self, Read, Write},
+    net::{IpAddr, Ipv4Addr, Shutdown, TcpListener, TcpStream},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The maximum size in bytes of a message sent over a socket, excluding the
+/// length prefix.
+pub const MAX_FRAME_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The interval at which socket servers check for incoming connections while
+/// waiting for a connection with a timeout.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The transport used to serve connections.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    /// `ipc_channel` one-shot servers. Only reachable from the same machine.
+    Ipc,
+    /// TCP sockets, listening on an arbitrary port of the given local address.
+    Tcp(IpAddr),
+    /// Unix domain sockets, created in the temporary directory.
+    #[cfg(unix)]
+    Unix,
+}
+
+/// A parsed server address.
+enum Address {
+    Ipc(String),
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl Address {
+    /// Parses a server address string.
+    fn parse(address: String) -> Result<Address> {
+        if let Some(address) = address.strip_prefix("tcp://") {
+            if address.starts_with(':') {
+                Ok(Address::Tcp(format!("{}{}", Ipv4Addr::LOCALHOST, address)))
+            } else {
+                Ok(Address::Tcp(address.to_string()))
+            }
+        } else if let Some(path) = address.strip_prefix("unix://") {
+            #[cfg(unix)]
+            return Ok(Address::Unix(path.into()));
+            #[cfg(not(unix))]
+            return inv_arg(format!(
+                "cannot connect to {}: Unix domain sockets are not supported on this platform",
+                path
+            ));
+        } else {
+            Ok(Address::Ipc(address))
+        }
+    }
+}
+
+/// A connected socket.
+pub enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// Creates a new handle to the same socket.
+    fn try_clone(&self) -> io::Result<Stream> {
+        Ok(match self {
+            Stream::Tcp(stream) => Stream::Tcp(stream.try_clone()?),
+            #[cfg(unix)]
+            Stream::Unix(stream) => Stream::Unix(stream.try_clone()?),
+        })
+    }
+
+    /// Shuts down the write half of the socket, such that the peer reads EOF.
+    fn shutdown_write(&self) {
+        let result = match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Write),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Write),
+        };
+        if let Err(e) = result {
+            trace!("Failed to shut down socket: {}", e);
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Write side of a socket, shared by the threads that send frames through it.
+/// The write half of the socket is shut down when the last thread is done.
+struct Writer(Stream);
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        self.0.shutdown_write();
+    }
+}
+
+type SharedWriter = Arc<Mutex<Writer>>;
+
+/// Serializes a message and writes it to a socket as a single frame.
+fn write_frame(stream: &mut impl Write, message: &impl Serialize) -> Result<()> {
+    let data = serde_cbor::to_vec(message)?;
+    if data.len() as u64 > MAX_FRAME_SIZE {
+        return err(format!(
+            "cannot send message of {} bytes over socket; the maximum is {} bytes",
+            data.len(),
+            MAX_FRAME_SIZE
+        ));
+    }
+    let mut frame = Vec::with_capacity(data.len() + 8);
+    frame.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    frame.extend_from_slice(&data);
+    stream.write_all(&frame)?;
+    Ok(())
+}
+
+/// Reads a frame from a socket and deserializes it. Returns `None` if the
+/// peer shut down its side of the socket.
+fn read_frame<T: DeserializeOwned>(stream: &mut impl Read) -> Result<Option<T>> {
+    let mut length = [0u8; 8];
+    match stream.read_exact(&mut length) {
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        result => result?,
+    }
+    let length = u64::from_le_bytes(length);
+    if length > MAX_FRAME_SIZE {
+        return err(format!(
+            "received message of {} bytes over socket; the maximum is {} bytes",
+            length, MAX_FRAME_SIZE
+        ));
+    }
+
+    // The buffer grows as the data arrives, instead of being allocated up
+    // front, so the peer cannot make us allocate memory it does not fill.
+    let mut data = vec![];
+    stream.take(length).read_to_end(&mut data)?;
+    if (data.len() as u64) < length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(Some(serde_cbor::from_slice(&data)?))
+}
+
+/// Spawns a thread that converts the messages received through `receiver`
+/// and writes them to the socket, until all senders are dropped.
+fn forward_out<T, F, W>(receiver: IpcReceiver<T>, writer: SharedWriter, mut convert: F)
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: FnMut(T) -> W + Send + 'static,
+    W: Serialize,
+{
+    thread::spawn(move || {
+        while let Ok(message) = receiver.recv() {
+            let frame = convert(message);
+            if let Err(e) = write_frame(&mut writer.lock().unwrap().0, &frame) {
+                trace!("Failed to write to socket: {}", e);
+                break;
+            }
+        }
+    });
+}
+
+/// Spawns a thread that reads frames from the socket and passes them to
+/// `handle`, until the socket is closed or `handle` returns false.
+fn forward_in<W, F>(mut stream: Stream, mut handle: F)
+where
+    W: DeserializeOwned,
+    F: FnMut(W) -> bool + Send + 'static,
+{
+    thread::spawn(move || loop {
+        match read_frame(&mut stream) {
+            Ok(Some(frame)) => {
+                if !handle(frame) {
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                trace!("Failed to read from socket: {}", e);
+                break;
+            }
+        }
+    });
+}
+
+/// Bridges a socket to a local channel for messages that are sent over the
+/// socket as they are.
+fn bridge<T, U>(stream: Stream) -> Result<IpcChannel<T, U>>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    U: Serialize + DeserializeOwned + Send + 'static,
+{
+    let (tx, tx_rx) = ipc::channel()?;
+    let (rx_tx, rx) = ipc::channel()?;
+    forward_in(stream.try_clone()?, move |message: U| {
+        rx_tx.send(message).is_ok()
+    });
+    forward_out(tx_rx, Arc::new(Mutex::new(Writer(stream))), |message: T| {
+        message
+    });
+    Ok((tx, rx))
+}
+
+/// Messages sent from the simulator to a plugin over a socket.
+///
+/// The log channel of the initialization request cannot be sent over a
+/// socket, so it is replaced with `PluginFrame::Log` messages in the other
+/// direction.
+///
+/// Handshakes are sent as `Request` and `Response` frames. Frames are
+/// serialized with their variant names, so these variants must keep their
+/// names for handshakes to remain readable across protocol versions.
+#[derive(Serialize, Deserialize)]
+enum SimulatorFrame {
+    Initialize {
+        downstream: Option<String>,
+        plugin_type: PluginType,
+        seed: u64,
+        log_configuration: PluginLogConfiguration,
+    },
+    Request(SimulatorToPlugin),
+}
+
+/// Messages sent from a plugin to the simulator over a socket.
+#[derive(Serialize, Deserialize)]
+enum PluginFrame {
+    Response(PluginToSimulator),
+    Log(LogRecord),
+}
+
+/// Channel types that can be carried over a socket.
+pub trait SocketChannel: Sized {
+    /// Bridges the given socket to a new channel of this type.
+    fn bridge(stream: Stream) -> Result<Self>;
+}
+
+impl SocketChannel for UpstreamChannel {
+    fn bridge(stream: Stream) -> Result<Self> {
+        bridge(stream)
+    }
+}
+
+impl SocketChannel for DownstreamChannel {
+    fn bridge(stream: Stream) -> Result<Self> {
+        bridge(stream)
+    }
+}
+
+impl SocketChannel for SimulatorChannel {
+    fn bridge(stream: Stream) -> Result<Self> {
+        let (request_tx, request_rx) = ipc::channel()?;
+        let (response_tx, response_rx) = ipc::channel()?;
+
+        // Log records received from the plugin are forwarded to the log
+        // channel of the initialization request.
+        let log: Arc<Mutex<Option<IpcSender<LogRecord>>>> = Arc::default();
+        let log_in = log.clone();
+        forward_in(stream.try_clone()?, move |frame| match frame {
+            PluginFrame::Response(response) => response_tx.send(response).is_ok(),
+            PluginFrame::Log(record) => {
+                if let Some(log) = log_in.lock().unwrap().as_ref() {
+                    log.send(record).ok();
+                }
+                true
+            }
+        });
+
+        forward_out(
+            request_rx,
+            Arc::new(Mutex::new(Writer(stream))),
+            move |request| match request {
+                SimulatorToPlugin::Initialize(request) => {
+                    let PluginInitializeRequest {
+                        downstream,
+                        plugin_type,
+                        seed,
+                        log_configuration,
+                        log_channel,
+                    } = *request;
+                    log.lock().unwrap().replace(log_channel);
+                    SimulatorFrame::Initialize {
+                        downstream,
+                        plugin_type,
+                        seed,
+                        log_configuration,
+                    }
+                }
+                request => SimulatorFrame::Request(request),
+            },
+        );
+
+        Ok((request_tx, response_rx))
+    }
+}
+
+impl SocketChannel for PluginChannel {
+    fn bridge(stream: Stream) -> Result<Self> {
+        let (request_tx, request_rx) = ipc::channel()?;
+        let (response_tx, response_rx) = ipc::channel()?;
+        let writer = Arc::new(Mutex::new(Writer(stream.try_clone()?)));
+
+        forward_out(response_rx, writer.clone(), PluginFrame::Response);
+
+        // Initialization requests get a local log channel, the records of
+        // which are forwarded to the simulator.
+        forward_in(stream, move |frame| {
+            let request = match frame {
+                SimulatorFrame::Initialize {
+                    downstream,
+                    plugin_type,
+                    seed,
+                    log_configuration,
+                } => {
+                    let (log_channel, log_rx) = match ipc::channel() {
+                        Ok(channel) => channel,
+                        Err(e) => {
+                            trace!("Failed to create log channel: {}", e);
+                            return false;
+                        }
+                    };
+                    forward_out(log_rx, writer.clone(), PluginFrame::Log);
+                    PluginInitializeRequest {
+                        downstream,
+                        plugin_type,
+                        seed,
+                        log_configuration,
+                        log_channel,
+                    }
+                    .into()
+                }
+                SimulatorFrame::Request(request) => request,
+            };
+            request_tx.send(request).is_ok()
+        });
+
+        Ok((response_tx, request_rx))
+    }
+}
+
+/// Connects to the one-shot server at the given address.
+///
+/// Returns the channel to communicate with the server, along with the
+/// transport that should be used to serve connections to peers that are
+/// reachable in the same way as the server is.
+pub fn connect<T, U>(address: impl Into<String>) -> Result<(IpcChannel<T, U>, Transport)>
+where
+    T: Serialize + DeserializeOwned,
+    U: Serialize + DeserializeOwned,
+    IpcChannel<T, U>: SocketChannel,
+{
+    match Address::parse(address.into())? {
+        Address::Ipc(name) => {
+            // Attempt to connect to the server.
+            let server = IpcSender::connect(name)?;
+
+            // Construct the channel pair and send the server's half.
+            let (tx, peer_rx) = ipc::channel()?;
+            let (peer_tx, rx) = ipc::channel()?;
+            server.send((peer_tx, peer_rx) as IpcChannel<U, T>)?;
+
+            Ok(((tx, rx), Transport::Ipc))
+        }
+        Address::Tcp(address) => {
+            let stream = TcpStream::connect(address)?;
+            stream.set_nodelay(true)?;
+            let transport = Transport::Tcp(stream.local_addr()?.ip());
+            Ok((SocketChannel::bridge(Stream::Tcp(stream))?, transport))
+        }
+        #[cfg(unix)]
+        Address::Unix(path) => {
+            let stream = UnixStream::connect(path)?;
+            Ok((
+                SocketChannel::bridge(Stream::Unix(stream))?,
+                Transport::Unix,
+            ))
+        }
+    }
+}
+
+/// Unix domain socket listener, which removes its socket file when dropped.
+#[cfg(unix)]
+struct UnixServer {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl UnixServer {
+    /// Binds a listener to the given path. A socket file that is left behind
+    /// by a previous run, and that is thus no longer listened on, is removed
+    /// first.
+    fn bind(path: PathBuf) -> Result<UnixServer> {
+        if is_stale_socket(&path) {
+            trace!("Removing stale socket file {}", path.display());
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        Ok(UnixServer { listener, path })
+    }
+}
+
+/// Returns whether the given path refers to a socket file that no server is
+/// listening on. Note that this connects to the socket to find out; a server
+/// that is still listening on it sees a connection that is closed right away.
+/// This only happens when two servers are configured to use the same path,
+/// in which case binding the second one fails anyway.
+#[cfg(unix)]
+fn is_stale_socket(path: &Path) -> bool {
+    let is_socket = std::fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_socket())
+        .unwrap_or(false);
+    is_socket
+        && matches!(
+            UnixStream::connect(path),
+            Err(ref e) if e.kind() == io::ErrorKind::ConnectionRefused
+        )
+}
+
+#[cfg(unix)]
+impl Drop for UnixServer {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+/// Calls `accept` on a non-blocking listener until it returns a connection,
+/// or returns `None` once the timeout expires.
+fn poll_accept<S>(
+    mut accept: impl FnMut() -> io::Result<S>,
+    timeout: Duration,
+) -> Result<Option<S>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match accept() {
+            Ok(stream) => return Ok(Some(stream)),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Ok(None);
+                }
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Server backend of a `OneShotServer`.
+enum Server<T, U> {
+    /// An `ipc_channel` one-shot server, along with its name.
+    Ipc(IpcOneShotServer<IpcChannel<T, U>>, String),
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixServer),
+}
+
+/// A server that accepts a single connection through any transport.
+pub struct OneShotServer<T, U> {
+    server: Server<T, U>,
+}
+
+impl<T, U> OneShotServer<T, U>
+where
+    T: Serialize + DeserializeOwned,
+    U: Serialize + DeserializeOwned,
+    IpcChannel<T, U>: SocketChannel,
+{
+    /// Creates a one-shot server using the given transport, returning the
+    /// server and the address that the peer should connect to.
+    pub fn new(transport: &Transport) -> Result<(OneShotServer<T, U>, String)> {
+        let (server, address) = match transport {
+            Transport::Ipc => {
+                let (server, name) = IpcOneShotServer::new()?;
+                (Server::Ipc(server, name.clone()), name)
+            }
+            Transport::Tcp(ip) => {
+                let listener = TcpListener::bind((*ip, 0))?;
+                let address = format!("tcp://{}", listener.local_addr()?);
+                (Server::Tcp(listener), address)
+            }
+            #[cfg(unix)]
+            Transport::Unix => {
+                static COUNTER: AtomicUsize = AtomicUsize::new(0);
+                let path = std::env::temp_dir().join(format!(
+                    "dqcsim-{}-{}.sock",
+                    std::process::id(),
+                    COUNTER.fetch_add(1, Ordering::Relaxed)
+                ));
+                let address = format!("unix://{}", path.display());
+                (Server::Unix(UnixServer::bind(path)?), address)
+            }
+        };
+        Ok((OneShotServer { server }, address))
+    }
+
+    /// Creates a one-shot server listening on a known `tcp://<host>:<port>`
+    /// or `unix://<path>` address, returning the server and the address that
+    /// it actually listens on. The latter differs from the given address when
+    /// port 0 is specified for TCP.
+    pub fn bind(address: impl Into<String>) -> Result<(OneShotServer<T, U>, String)> {
+        let (server, address) = match Address::parse(address.into())? {
+            Address::Ipc(address) => {
+                return inv_arg(format!(
+                    "cannot listen on {}: only tcp:// and unix:// addresses are supported",
+                    address
+                ))
+            }
+            Address::Tcp(address) => {
+                let listener = TcpListener::bind(address)?;
+                let address = format!("tcp://{}", listener.local_addr()?);
+                (Server::Tcp(listener), address)
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let address = format!("unix://{}", path.display());
+                (Server::Unix(UnixServer::bind(path)?), address)
+            }
+        };
+        Ok((OneShotServer { server }, address))
+    }
+
+    /// Waits for the peer to connect, returning the channel to communicate
+    /// with it.
+    pub fn accept(self) -> Result<IpcChannel<T, U>> {
+        match self.server {
+            Server::Ipc(server, _) => Ok(server.accept()?.1),
+            Server::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                bridge_tcp(stream)
+            }
+            #[cfg(unix)]
+            Server::Unix(server) => {
+                let (stream, _) = server.listener.accept()?;
+                SocketChannel::bridge(Stream::Unix(stream))
+            }
+        }
+    }
+
+    /// Waits for the peer to connect, returning the channel to communicate
+    /// with it, or `None` if the peer did not connect within the given
+    /// timeout. The server stops listening in either case.
+    pub fn accept_timeout(self, timeout: Duration) -> Result<Option<IpcChannel<T, U>>>
+    where
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        match self.server {
+            Server::Ipc(server, name) => {
+                // `ipc_channel` servers cannot accept with a timeout, so the
+                // server is accepted on a separate thread.
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || tx.send(server.accept().map(|(_, channel)| channel)).ok());
+                match rx.recv_timeout(timeout) {
+                    Ok(result) => Ok(Some(result?)),
+                    Err(_) => {
+                        // Connect to the server ourselves, such that the
+                        // thread stops waiting.
+                        let release = || -> Result<()> {
+                            let sender: IpcSender<IpcChannel<T, U>> = IpcSender::connect(name)?;
+                            let (tx, _) = ipc::channel()?;
+                            let (_, rx) = ipc::channel()?;
+                            sender.send((tx, rx))?;
+                            Ok(())
+                        };
+                        if let Err(e) = release() {
+                            trace!("Failed to release one-shot server: {}", e);
+                        }
+                        Ok(None)
+                    }
+                }
+            }
+            Server::Tcp(listener) => {
+                listener.set_nonblocking(true)?;
+                match poll_accept(|| listener.accept(), timeout)? {
+                    Some((stream, _)) => bridge_tcp(stream).map(Some),
+                    None => Ok(None),
+                }
+            }
+            #[cfg(unix)]
+            Server::Unix(server) => {
+                server.listener.set_nonblocking(true)?;
+                match poll_accept(|| server.listener.accept(), timeout)? {
+                    Some((stream, _)) => {
+                        stream.set_nonblocking(false)?;
+                        SocketChannel::bridge(Stream::Unix(stream)).map(Some)
+                    }
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+}
+
+/// Bridges an accepted TCP connection to a local channel.
+fn bridge_tcp<T, U>(stream: TcpStream) -> Result<IpcChannel<T, U>>
+where
+    IpcChannel<T, U>: SocketChannel,
+{
+    // Accepted sockets inherit the non-blocking mode of the listener on some
+    // platforms.
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    SocketChannel::bridge(Stream::Tcp(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames() {
+        let mut buffer = vec![];
+        write_frame(&mut buffer, &"hello".to_string()).unwrap();
+        write_frame(&mut buffer, &vec![1u32, 2, 3]).unwrap();
+        let mut stream = &buffer[..];
+        assert_eq!(
+            read_frame::<String>(&mut stream).unwrap(),
+            Some("hello".to_string())
+        );
+        assert_eq!(
+            read_frame::<Vec<u32>>(&mut stream).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(read_frame::<String>(&mut stream).unwrap(), None);
+    }
+
+    #[test]
+    fn oversized_frame() {
+        let mut buffer = (MAX_FRAME_SIZE + 1).to_le_bytes().to_vec();
+        buffer.extend_from_slice(&[0; 16]);
+        assert_eq!(
+            read_frame::<String>(&mut &buffer[..])
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "received message of {} bytes over socket; the maximum is {} bytes",
+                MAX_FRAME_SIZE + 1,
+                MAX_FRAME_SIZE
+            )
+        );
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let mut buffer = 16u64.to_le_bytes().to_vec();
+        buffer.extend_from_slice(&[0; 8]);
+        assert!(read_frame::<String>(&mut &buffer[..]).is_err());
+    }
+
+    type Server = OneShotServer<SimulatorToPlugin, PluginToSimulator>;
+
+    #[test]
+    fn ipc_accept_timeout() {
+        let (server, _) = Server::new(&Transport::Ipc).unwrap();
+        assert!(server
+            .accept_timeout(Duration::from_millis(50))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn tcp_accept_timeout() {
+        let (server, address) = Server::bind("tcp://:0").unwrap();
+        assert!(server
+            .accept_timeout(Duration::from_millis(50))
+            .unwrap()
+            .is_none());
+
+        // The port is released when the server gives up.
+        let (_, rebound) = Server::bind(&address[..]).unwrap();
+        assert_eq!(rebound, address);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_accept_timeout() {
+        let path =
+            std::env::temp_dir().join(format!("dqcsim-transport-test-{}.sock", std::process::id()));
+        let address = format!("unix://{}", path.display());
+
+        // Socket files left behind by servers that are no longer running are
+        // replaced.
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        let (server, _) = Server::bind(&address[..]).unwrap();
+
+        // The socket file is removed when the server gives up.
+        assert!(server
+            .accept_timeout(Duration::from_millis(50))
+            .unwrap()
+            .is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn default_tcp_host() {
+        match Address::parse("tcp://:1234".to_string()).unwrap() {
+            Address::Tcp(address) => assert_eq!(address, "127.0.0.1:1234"),
+            _ => panic!("expected a TCP address"),
+        }
+    }
+}
@@ -444,9 +444,43 @@ const EMPTY_CBOR: &[u8] = &[0xA0];
 #[derive(Clone, Hash, PartialEq, Deserialize, Serialize)]
 pub struct ArbData {
     cbor: Vec<u8>,
+    #[serde(with = "args_serde")]
     args: Vec<Vec<u8>>,
 }
 
+/// This mod provides ser/de for the binary arguments of an ArbData, such that
+/// large arguments can be transferred through shared memory.
+mod args_serde {
+    use crate::common::shared_memory;
+    use serde::{
+        ser::SerializeSeq,
+        {Deserialize, Deserializer, Serialize, Serializer},
+    };
+
+    pub fn serialize<S>(value: &[Vec<u8>], serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Wrapper<'a>(#[serde(serialize_with = "shared_memory::serialize")] &'a [u8]);
+        let mut seq = serializer.serialize_seq(Some(value.len()))?;
+        for arg in value.iter() {
+            seq.serialize_element(&Wrapper(arg))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Vec<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(deserialize_with = "shared_memory::deserialize")] Vec<u8>);
+        let v = Vec::deserialize(deserializer)?;
+        Ok(v.into_iter().map(|Wrapper(arg)| arg).collect())
+    }
+}
+
 impl Eq for ArbData {}
 
 impl fmt::Debug for ArbData {
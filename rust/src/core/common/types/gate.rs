@@ -1,10 +1,17 @@
 use crate::common::{
     error::{inv_arg, Result},
-    types::{ArbData, Matrix, QubitRef},
+    gates::UnboundUnitaryGate,
+    types::{
+        ArbData, Basis, Cycles, Matrix, QubitMeasurementResult, QubitMeasurementValue, QubitRef,
+    },
 };
 use num_complex::Complex64;
-use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, convert::TryInto};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    fmt,
+};
 
 /// Represents a type of quantum or mixed quantum-classical gate.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Hash)]
@@ -55,6 +62,40 @@ pub enum GateType {
     /// ignored.
     Prep,
 
+    /// Pauli-product measurement gates jointly measure one or more target
+    /// qubits in the eigenbasis of a tensor product of Pauli operators. The
+    /// Pauli operator for each target qubit is given by the basis of the
+    /// same index in the variant. The matrix is the Pauli product, i.e. the
+    /// measured observable, sized for the number of target qubits.
+    ///
+    /// The semantics are:
+    ///
+    ///  - the state is projected onto the +1 or -1 eigenspace of the
+    ///    observable, with the respective probabilities;
+    ///  - the outcome is propagated upstream as a single measurement of the
+    ///    first target qubit, which is also the only qubit in the measures
+    ///    set; zero represents the +1 eigenvalue (even parity), one
+    ///    represents the -1 eigenvalue (odd parity).
+    ///
+    /// The data field may add pragma-like hints to the gate, for instance to
+    /// represent the line number in the source file that generated the gate,
+    /// error modelling information, and so on. This data may be silently
+    /// ignored.
+    PauliMeasurement(Vec<Basis>),
+
+    /// Barrier gates have zero or more target qubits and no matrix. They do
+    /// not operate on the quantum state, but forbid gates from being moved
+    /// across them by operators that reorder or schedule gates.
+    ///
+    /// The semantics are:
+    ///
+    ///  - no gate operating on any of the target qubits may be reordered
+    ///    across the barrier;
+    ///  - if there are no target qubits, the barrier applies to all qubits.
+    ///
+    /// Backends treat barriers as no-ops.
+    Barrier,
+
     /// Custom gates perform a user-defined mixed quantum-classical operation,
     /// identified by a name. They can have zero or more target, control, and
     /// measured qubits, of which only the target and control sets must be
@@ -72,7 +113,7 @@ pub enum GateType {
 }
 
 /// Represents a type of quantum or mixed quantum-classical gate.
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, Hash)]
+#[derive(Eq, PartialEq, Clone, Deserialize, Hash)]
 pub struct Gate {
     /// Type of gate. See enum definition. The significance of the targets,
     /// controls, measures, and matrix fields is documented there.
@@ -90,10 +131,76 @@ pub struct Gate {
     /// An optional matrix.
     matrix: Option<Matrix>,
 
+    /// The condition for executing this gate, as a list of qubits and the
+    /// value that their latest measurement must have. The gate is executed
+    /// only if all the listed measurements match; it is always executed if
+    /// the list is empty.
+    #[serde(default)]
+    condition: Vec<(QubitRef, bool)>,
+
+    /// The duration of the gate in cycles, if known. This is a timing
+    /// annotation for operators that schedule gates; it does not affect the
+    /// simulation cycle counter, which is only advanced explicitly.
+    #[serde(default)]
+    duration: Option<Cycles>,
+
     /// User-defined classical data to pass along with the gate.
     pub data: ArbData,
 }
 
+impl fmt::Debug for Gate {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = fmt.debug_struct("Gate");
+        debug
+            .field("typ", &self.typ)
+            .field("targets", &self.targets)
+            .field("controls", &self.controls)
+            .field("measures", &self.measures)
+            .field("matrix", &self.matrix);
+        if !self.condition.is_empty() {
+            debug.field("condition", &self.condition);
+        }
+        if let Some(duration) = &self.duration {
+            debug.field("duration", duration);
+        }
+        debug.field("data", &self.data).finish()
+    }
+}
+
+/// The condition and duration fields are omitted from human-readable formats
+/// when they are not set, such that traces and other files written before
+/// these fields were added remain valid. They cannot be omitted from other
+/// formats, because formats such as bincode do not store field names.
+impl Serialize for Gate {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let human_readable = serializer.is_human_readable();
+        let skip_condition = human_readable && self.condition.is_empty();
+        let skip_duration = human_readable && self.duration.is_none();
+        let mut state = serializer
+            .serialize_struct("Gate", 8 - skip_condition as usize - skip_duration as usize)?;
+        state.serialize_field("typ", &self.typ)?;
+        state.serialize_field("targets", &self.targets)?;
+        state.serialize_field("controls", &self.controls)?;
+        state.serialize_field("measures", &self.measures)?;
+        state.serialize_field("matrix", &self.matrix)?;
+        if skip_condition {
+            state.skip_field("condition")?;
+        } else {
+            state.serialize_field("condition", &self.condition)?;
+        }
+        if skip_duration {
+            state.skip_field("duration")?;
+        } else {
+            state.serialize_field("duration", &self.duration)?;
+        }
+        state.serialize_field("data", &self.data)?;
+        state.end()
+    }
+}
+
 impl Gate {
     /// Constructs a new unitary gate.
     pub fn new_unitary(
@@ -139,6 +246,8 @@ impl Gate {
             controls,
             measures: vec![],
             matrix: Some(matrix),
+            condition: vec![],
+            duration: None,
             data: ArbData::default(),
         })
     }
@@ -179,6 +288,8 @@ impl Gate {
             controls: vec![],
             measures,
             matrix: Some(matrix),
+            condition: vec![],
+            duration: None,
             data: ArbData::default(),
         })
     }
@@ -219,6 +330,80 @@ impl Gate {
             controls: vec![],
             measures: vec![],
             matrix: Some(matrix),
+            condition: vec![],
+            duration: None,
+            data: ArbData::default(),
+        })
+    }
+
+    /// Constructs a new Pauli-product measurement gate, measuring the
+    /// observable formed by the tensor product of the Pauli operators
+    /// corresponding to the given bases for the given qubits.
+    pub fn new_pauli_measurement(
+        qubits: impl IntoIterator<Item = (QubitRef, Basis)>,
+    ) -> Result<Gate> {
+        let (targets, bases): (Vec<QubitRef>, Vec<Basis>) = qubits.into_iter().unzip();
+
+        // We need at least one qubit.
+        if targets.is_empty() {
+            return inv_arg("at least one qubit is required");
+        }
+
+        // Enforce uniqueness of the qubits.
+        let mut set = HashSet::new();
+        for qubit in targets.iter() {
+            if !set.insert(qubit) {
+                return inv_arg(format!("qubit {} is used more than once", qubit));
+            }
+        }
+
+        // Construct the observable.
+        let matrix = bases
+            .iter()
+            .map(|basis| {
+                Matrix::from(match basis {
+                    Basis::X => UnboundUnitaryGate::X,
+                    Basis::Y => UnboundUnitaryGate::Y,
+                    Basis::Z => UnboundUnitaryGate::Z,
+                })
+            })
+            .fold(Matrix::new_identity(1), |matrix, pauli| matrix.kron(&pauli));
+
+        // Construct the Gate structure.
+        Ok(Gate {
+            typ: GateType::PauliMeasurement(bases),
+            measures: vec![targets[0]],
+            targets,
+            controls: vec![],
+            matrix: Some(matrix),
+            condition: vec![],
+            duration: None,
+            data: ArbData::default(),
+        })
+    }
+
+    /// Constructs a new barrier gate for the given qubits. An empty list of
+    /// qubits constructs a barrier for all qubits.
+    pub fn new_barrier(qubits: impl IntoIterator<Item = QubitRef>) -> Result<Gate> {
+        let targets: Vec<QubitRef> = qubits.into_iter().collect();
+
+        // Enforce uniqueness of the qubits.
+        let mut set = HashSet::new();
+        for qubit in targets.iter() {
+            if !set.insert(qubit) {
+                return inv_arg(format!("qubit {} is used more than once", qubit));
+            }
+        }
+
+        // Construct the Gate structure.
+        Ok(Gate {
+            typ: GateType::Barrier,
+            targets,
+            controls: vec![],
+            measures: vec![],
+            matrix: None,
+            condition: vec![],
+            duration: None,
             data: ArbData::default(),
         })
     }
@@ -267,6 +452,8 @@ impl Gate {
             controls,
             measures,
             matrix,
+            condition: vec![],
+            duration: None,
             data,
         })
     }
@@ -276,6 +463,50 @@ impl Gate {
         &self.typ
     }
 
+    /// Decomposes a Pauli-product measurement gate into single-qubit basis
+    /// changes, CNOTs, and a Z-basis measurement of the first target qubit,
+    /// followed by the inverse of the basis changes and CNOTs. Executing the
+    /// returned gates in order is equivalent to executing this gate, and the
+    /// measurement result has the same meaning. This allows backends that do
+    /// not support Pauli-product measurements natively to execute them.
+    pub fn decompose_pauli_measurement(&self) -> Result<Vec<Gate>> {
+        let bases = match &self.typ {
+            GateType::PauliMeasurement(bases) => bases,
+            _ => return inv_arg("not a Pauli-product measurement gate"),
+        };
+
+        // Rotate each Pauli operator to Z, and then accumulate the parity of
+        // all qubits into the first one.
+        let mut rotation = vec![];
+        for (&qubit, basis) in self.targets.iter().zip(bases.iter()) {
+            let gates = match basis {
+                Basis::X => vec![UnboundUnitaryGate::H],
+                Basis::Y => vec![UnboundUnitaryGate::SDAG, UnboundUnitaryGate::H],
+                Basis::Z => vec![],
+            };
+            for gate in gates {
+                rotation.push(Gate::new_unitary(vec![qubit], vec![], Matrix::from(gate))?);
+            }
+        }
+        let first = self.targets[0];
+        for &qubit in self.targets.iter().skip(1) {
+            rotation.push(Gate::new_unitary(
+                vec![first],
+                vec![qubit],
+                Matrix::from(UnboundUnitaryGate::X),
+            )?);
+        }
+
+        let mut gates = rotation.clone();
+        let mut measurement = Gate::new_measurement(vec![first], Matrix::from(Basis::Z))?;
+        measurement.data = self.data.clone();
+        gates.push(measurement);
+        for gate in rotation.iter().rev() {
+            gates.push(gate.inverse()?);
+        }
+        Ok(gates)
+    }
+
     /// Returns the name of the gate, if any.
     pub fn get_name(&self) -> Option<&str> {
         if let GateType::Custom(name) = &self.typ {
@@ -305,6 +536,104 @@ impl Gate {
         self.matrix.as_ref()
     }
 
+    /// Returns the condition for executing this gate, as a list of qubits and
+    /// the value that their latest measurement must have. This list is empty
+    /// for unconditional gates.
+    pub fn get_condition(&self) -> &[(QubitRef, bool)] {
+        &self.condition
+    }
+
+    /// Returns whether this gate is conditional.
+    pub fn is_conditional(&self) -> bool {
+        !self.condition.is_empty()
+    }
+
+    /// Returns a new Gate that is only executed when the latest measurement
+    /// of each of the given qubits matches the given value. Any existing
+    /// condition is extended.
+    ///
+    /// This allows frontends to perform feedback without synchronizing with
+    /// the downstream plugins through `get_measurement()`; the condition is
+    /// instead resolved by the backend, using the latest measurement results
+    /// that it reported for the qubits. When the condition of a gate that
+    /// measures qubits is false, the measured qubits get an undefined
+    /// result, rather than keeping their previous one.
+    pub fn with_condition(
+        &self,
+        condition: impl IntoIterator<Item = (QubitRef, bool)>,
+    ) -> Result<Self> {
+        let mut gate = self.clone();
+        for (qubit, value) in condition {
+            if gate.condition.iter().any(|&(q, _)| q == qubit) {
+                return inv_arg(format!(
+                    "qubit {} is used more than once in the condition",
+                    qubit
+                ));
+            }
+            gate.condition.push((qubit, value));
+        }
+        Ok(gate)
+    }
+
+    /// Returns a new Gate with the condition removed.
+    pub fn without_condition(&self) -> Self {
+        Gate {
+            condition: vec![],
+            ..self.clone()
+        }
+    }
+
+    /// Evaluates the condition of this gate, given a function that returns
+    /// the latest measurement value of a qubit. Undefined measurement values
+    /// never match the condition.
+    pub fn evaluate_condition(
+        &self,
+        measurement: impl Fn(QubitRef) -> QubitMeasurementValue,
+    ) -> bool {
+        self.condition
+            .iter()
+            .all(|&(qubit, value)| measurement(qubit) == QubitMeasurementValue::from(value))
+    }
+
+    /// Returns an undefined measurement result for each qubit measured by
+    /// this gate. These are the results of a conditional gate that is not
+    /// executed.
+    pub fn undefined_measurements(&self) -> Vec<QubitMeasurementResult> {
+        self.measures
+            .iter()
+            .map(|&qubit| {
+                QubitMeasurementResult::new(
+                    qubit,
+                    QubitMeasurementValue::Undefined,
+                    ArbData::default(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the duration of this gate in cycles, if it has been annotated
+    /// with one.
+    pub fn get_duration(&self) -> Option<Cycles> {
+        self.duration
+    }
+
+    /// Returns a new Gate annotated with the given duration in cycles,
+    /// replacing any existing duration.
+    pub fn with_duration(&self, duration: Cycles) -> Self {
+        Gate {
+            duration: Some(duration),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a new Gate with the duration annotation removed.
+    pub fn without_duration(&self) -> Self {
+        Gate {
+            duration: None,
+            ..self.clone()
+        }
+    }
+
     /// Returns a new Gate with its controls moved to the matrix.
     pub fn with_matrix_controls(&self) -> Self {
         let num_controls = self.controls.len();
@@ -318,6 +647,8 @@ impl Gate {
                 controls: vec![],
                 measures: self.measures.to_vec(),
                 matrix: Some(matrix),
+                condition: self.condition.clone(),
+                duration: self.duration,
                 data: self.data.clone(),
             }
         } else {
@@ -332,17 +663,25 @@ impl Gate {
         if self.typ == GateType::Unitary {
             let matrix = self.matrix.as_ref().unwrap();
             let (control_set, matrix) = matrix.strip_control(epsilon, ignore_global_phase);
-            let mut targets = self.get_targets().to_vec();
-            let mut controls = vec![];
-            for c in control_set {
-                controls.push(targets.remove(c));
-            }
+            let mut control_indices = control_set.into_iter().collect::<Vec<_>>();
+            control_indices.sort();
+            let mut controls = self.controls.clone();
+            controls.extend(control_indices.iter().map(|&c| self.targets[c]));
+            let targets = self
+                .targets
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !control_indices.contains(i))
+                .map(|(_, &q)| q)
+                .collect();
             Gate {
                 typ: self.typ.clone(),
                 targets,
                 controls,
                 measures: self.measures.to_vec(),
                 matrix: Some(matrix),
+                condition: self.condition.clone(),
+                duration: self.duration,
                 data: self.data.clone(),
             }
         } else {
@@ -350,6 +689,109 @@ impl Gate {
         }
     }
 
+    /// Returns the inverse of this gate, which must be a unitary gate. The
+    /// inverse has the same qubits and data as the original gate, and the
+    /// adjoint of its matrix.
+    pub fn inverse(&self) -> Result<Self> {
+        if self.typ != GateType::Unitary {
+            return inv_arg("only unitary gates can be inverted");
+        }
+        Ok(Gate {
+            typ: self.typ.clone(),
+            targets: self.targets.clone(),
+            controls: self.controls.clone(),
+            measures: vec![],
+            matrix: self.matrix.as_ref().map(Matrix::adjoint),
+            condition: self.condition.clone(),
+            duration: self.duration,
+            data: self.data.clone(),
+        })
+    }
+
+    /// Returns all the qubits that a unitary gate operates on, in the order
+    /// used by the matrix returned by `with_matrix_controls()`.
+    fn unitary_qubits(&self) -> Vec<QubitRef> {
+        self.controls
+            .iter()
+            .chain(self.targets.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether this unitary gate can be fused with other gates.
+    fn is_fusable(&self) -> bool {
+        self.condition.is_empty() && self.duration.is_none() && self.data == ArbData::default()
+    }
+
+    /// Fuses adjacent unitary gates in a gate sequence to reduce the number
+    /// of gates.
+    ///
+    /// A unitary gate is fused into an earlier unitary gate when none of the
+    /// gates in between operate on any of its qubits, and when the qubits of
+    /// one of the two gates are a subset of the qubits of the other, such
+    /// that fusion never produces gates larger than those in the input. The
+    /// fused gates are constructed using `new_unitary()`, after which any
+    /// controls encoded in their matrix are moved to the control qubit list
+    /// using `with_gate_controls()` with the given `epsilon`. Gates that carry
+    /// data, a condition, or a duration are not fused, as these cannot be
+    /// merged in general.
+    ///
+    /// All other gate types act as barriers: no gate is ever moved across
+    /// them, regardless of the qubits they operate on.
+    pub fn fuse(gates: impl IntoIterator<Item = Gate>, epsilon: f64) -> Result<Vec<Gate>> {
+        let mut output: Vec<Gate> = vec![];
+
+        // Index of the last gate in the output that operates on each qubit,
+        // since the last barrier.
+        let mut last: HashMap<QubitRef, usize> = HashMap::new();
+
+        for gate in gates {
+            if gate.typ != GateType::Unitary {
+                last.clear();
+                output.push(gate);
+                continue;
+            }
+
+            // Find the gate we might fuse with. All qubits of the new gate
+            // must be unused after it.
+            let qubits = gate.unitary_qubits();
+            let candidate = qubits.iter().filter_map(|q| last.get(q)).max().cloned();
+            let fused = if let Some(index) = candidate {
+                let previous = &output[index];
+                let previous_qubits = previous.unitary_qubits();
+                let subset = qubits.iter().all(|q| previous_qubits.contains(q))
+                    || previous_qubits.iter().all(|q| qubits.contains(q));
+                if subset && previous.is_fusable() && gate.is_fusable() {
+                    let mut all_qubits = previous_qubits.clone();
+                    all_qubits.extend(qubits.iter().filter(|q| !previous_qubits.contains(q)));
+                    let matrix =
+                        expand_matrix(&gate.with_matrix_controls(), &all_qubits).multiply(
+                            &expand_matrix(&previous.with_matrix_controls(), &all_qubits),
+                        )?;
+                    output[index] = Gate::new_unitary(all_qubits.clone(), vec![], matrix)?
+                        .with_gate_controls(epsilon, false);
+                    for qubit in all_qubits {
+                        last.insert(qubit, index);
+                    }
+                    true
+                } else {
+                    false
+                }
+            } else {
+                false
+            };
+
+            if !fused {
+                for qubit in qubits {
+                    last.insert(qubit, output.len());
+                }
+                output.push(gate);
+            }
+        }
+
+        Ok(output)
+    }
+
     /// Replaces all qubit references in the gate with undefined qubits. This
     /// is used as a gate detector cache preprocessing step when the detector
     /// functions do not depend on which qubits are bound to the gate, only the
@@ -361,14 +803,68 @@ impl Gate {
             controls: vec![QubitRef::null(); self.controls.len()],
             measures: vec![QubitRef::null(); self.measures.len()],
             matrix: self.matrix.clone(),
+            condition: self
+                .condition
+                .iter()
+                .map(|&(_, value)| (QubitRef::null(), value))
+                .collect(),
+            duration: self.duration,
+            data: self.data.clone(),
+        }
+    }
+
+    /// Replaces all qubit references in the gate using the given mapping
+    /// function. The mapping must be injective for the result to be valid;
+    /// this is used by operators that remap qubits, such as for routing.
+    pub fn with_qubit_refs(&self, mapping: impl Fn(QubitRef) -> QubitRef) -> Self {
+        Gate {
+            typ: self.typ.clone(),
+            targets: self.targets.iter().cloned().map(&mapping).collect(),
+            controls: self.controls.iter().cloned().map(&mapping).collect(),
+            measures: self.measures.iter().cloned().map(&mapping).collect(),
+            matrix: self.matrix.clone(),
+            condition: self
+                .condition
+                .iter()
+                .map(|&(qubit, value)| (mapping(qubit), value))
+                .collect(),
+            duration: self.duration,
             data: self.data.clone(),
         }
     }
 }
 
+/// Returns the matrix of a unitary gate without controls, expanded to
+/// operate on the given superset of its target qubits. The first qubit
+/// corresponds to the most significant bit of the matrix indices.
+fn expand_matrix(gate: &Gate, qubits: &[QubitRef]) -> Matrix {
+    let matrix = gate.matrix.as_ref().unwrap();
+    let dimension = 1 << qubits.len();
+    let bits: Vec<usize> = gate
+        .targets
+        .iter()
+        .map(|t| qubits.len() - 1 - qubits.iter().position(|q| q == t).unwrap())
+        .collect();
+    let mask = bits.iter().fold(0, |mask, bit| mask | (1 << bit));
+    let index = |i: usize| {
+        bits.iter()
+            .fold(0, |index, bit| (index << 1) | ((i >> bit) & 1))
+    };
+    Matrix::new((0..dimension * dimension).map(|i| {
+        let (row, col) = (i / dimension, i % dimension);
+        if row & !mask == col & !mask {
+            matrix[(index(row), index(col))]
+        } else {
+            Complex64::new(0.0, 0.0)
+        }
+    }))
+    .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::gates::UnboundUnitaryGate;
 
     fn qref(q: u64) -> QubitRef {
         QubitRef::from_foreign(q).unwrap()
@@ -706,6 +1202,25 @@ mod tests {
         assert_eq!(x.get_controls(), &[qref(1)]);
     }
 
+    #[test]
+    fn with_gate_controls_multiple() {
+        // Toffoli with an additional control in the control list.
+        let targets = vec![qref(1), qref(2), qref(3)];
+        let controls = vec![qref(4)];
+        let x = Matrix::new(vec![
+            Complex64::new(0f64, 0f64),
+            Complex64::new(1f64, 0f64),
+            Complex64::new(1f64, 0f64),
+            Complex64::new(0f64, 0f64),
+        ])
+        .unwrap();
+        let toffoli = Gate::new_unitary(targets, controls, x.add_controls(2)).unwrap();
+        let x_gate = toffoli.with_gate_controls(0.001, false);
+        assert_eq!(x_gate.get_controls(), &[qref(4), qref(1), qref(2)]);
+        assert_eq!(x_gate.get_targets(), &[qref(3)]);
+        assert_eq!(x_gate.get_matrix(), Some(&x));
+    }
+
     #[test]
     fn with_matrix_controls() {
         let targets = vec![qref(1)];
@@ -722,4 +1237,334 @@ mod tests {
         assert_eq!(cnot.get_controls(), &[]);
         assert_eq!(cnot.get_targets(), &[qref(2), qref(1)]);
     }
+
+    #[test]
+    fn with_qubit_refs() {
+        let measure = Gate::new_measurement(
+            vec![qref(1), qref(2)],
+            vec![
+                Complex64::new(1f64, 0f64),
+                Complex64::new(0f64, 0f64),
+                Complex64::new(0f64, 0f64),
+                Complex64::new(1f64, 0f64),
+            ],
+        )
+        .unwrap();
+        let mapped = measure.with_qubit_refs(|q| qref(q.to_foreign().unwrap() + 2));
+        assert_eq!(mapped.get_measures(), &[qref(3), qref(4)]);
+        assert_eq!(mapped.get_matrix(), measure.get_matrix());
+    }
+
+    /// Returns the matrix of a sequence of unitary gates operating on the
+    /// given qubits.
+    fn circuit_matrix(gates: &[Gate], qubits: &[QubitRef]) -> Matrix {
+        gates
+            .iter()
+            .fold(Matrix::new_identity(1 << qubits.len()), |matrix, gate| {
+                expand_matrix(&gate.with_matrix_controls(), qubits)
+                    .multiply(&matrix)
+                    .unwrap()
+            })
+    }
+
+    #[test]
+    fn inverse() {
+        let s = Matrix::from(UnboundUnitaryGate::S);
+        let gate = Gate::new_unitary(vec![qref(1)], vec![qref(2)], s.clone()).unwrap();
+        let inverse = gate.inverse().unwrap();
+        assert_eq!(inverse.get_targets(), &[qref(1)]);
+        assert_eq!(inverse.get_controls(), &[qref(2)]);
+        assert!(inverse.get_matrix().unwrap().approx_eq(
+            &Matrix::from(UnboundUnitaryGate::SDAG),
+            1.0e-9,
+            false
+        ));
+
+        let measure = Gate::new_measurement(vec![qref(1)], Matrix::new_identity(2)).unwrap();
+        assert_eq!(
+            measure.inverse().unwrap_err().to_string(),
+            "Invalid argument: only unitary gates can be inverted"
+        );
+    }
+
+    #[test]
+    fn fuse_single_qubit() {
+        let q = [qref(1), qref(2)];
+        let gate = |g, q| Gate::new_unitary(vec![q], vec![], Matrix::from(g)).unwrap();
+        let gates = vec![
+            gate(UnboundUnitaryGate::H, q[0]),
+            gate(UnboundUnitaryGate::X, q[1]),
+            gate(UnboundUnitaryGate::Z, q[0]),
+            gate(UnboundUnitaryGate::H, q[0]),
+        ];
+        let fused = Gate::fuse(gates.clone(), 1.0e-6).unwrap();
+        assert_eq!(fused.len(), 2);
+        assert_eq!(fused[0].get_targets(), &[q[0]]);
+        assert!(fused[0].get_matrix().unwrap().approx_eq(
+            &Matrix::from(UnboundUnitaryGate::X),
+            1.0e-9,
+            false
+        ));
+        assert_eq!(fused[1], gates[1]);
+    }
+
+    #[test]
+    fn fuse_multi_qubit() {
+        let q = [qref(1), qref(2), qref(3)];
+        let x = Matrix::from(UnboundUnitaryGate::X);
+        let z = Matrix::from(UnboundUnitaryGate::Z);
+        let gates = vec![
+            Gate::new_unitary(vec![q[0]], vec![], x.clone()).unwrap(),
+            Gate::new_unitary(vec![q[1]], vec![], x.clone()).unwrap(),
+            Gate::new_unitary(vec![q[1]], vec![q[0]], x.clone()).unwrap(),
+            Gate::new_unitary(vec![q[1]], vec![q[0]], z).unwrap(),
+            Gate::new_unitary(vec![q[2]], vec![q[1]], x.clone()).unwrap(),
+        ];
+        let fused = Gate::fuse(gates.clone(), 1.0e-6).unwrap();
+
+        // The controlled gates are fused into the second X gate, as that is
+        // the last gate operating on any of their qubits. The unconditional X
+        // means that the fused gate has no controls. The final CNOT is not
+        // fused, because neither qubit set is a subset of the other.
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0], gates[0]);
+        assert_eq!(fused[1].get_controls(), &[]);
+        assert_eq!(fused[2], gates[4]);
+        assert!(circuit_matrix(&fused, &q).approx_eq(&circuit_matrix(&gates, &q), 1.0e-9, false));
+
+        // Two identical controlled gates with the same control are fused into
+        // a controlled identity, from which the control is stripped.
+        let gates = vec![gates[3].clone(), gates[3].clone()];
+        let fused = Gate::fuse(gates, 1.0e-6).unwrap();
+        assert_eq!(fused.len(), 1);
+        assert!(fused[0]
+            .get_matrix()
+            .unwrap()
+            .approx_eq(&Matrix::new_identity(4), 1.0e-9, false));
+    }
+
+    #[test]
+    fn fuse_barriers() {
+        let q = [qref(1), qref(2)];
+        let h = Matrix::from(UnboundUnitaryGate::H);
+        let unitary = Gate::new_unitary(vec![q[0]], vec![], h).unwrap();
+        let mut with_data = unitary.clone();
+        with_data.data = ArbData::from_args(vec![b"hint".to_vec()]);
+        let measure = Gate::new_measurement(vec![q[1]], Matrix::new_identity(2)).unwrap();
+        let prep = Gate::new_prep(vec![q[1]], Matrix::new_identity(2)).unwrap();
+        let custom = Gate::new_custom(
+            "barrier",
+            vec![],
+            vec![],
+            vec![],
+            None::<Vec<Complex64>>,
+            ArbData::default(),
+        )
+        .unwrap();
+        let barrier = Gate::new_barrier(vec![q[1]]).unwrap();
+        for barrier in &[measure, prep, custom, barrier] {
+            let gates = vec![unitary.clone(), barrier.clone(), unitary.clone()];
+            assert_eq!(Gate::fuse(gates.clone(), 1.0e-6).unwrap(), gates);
+        }
+        let gates = vec![unitary.clone(), with_data.clone(), unitary.clone()];
+        assert_eq!(Gate::fuse(gates.clone(), 1.0e-6).unwrap(), gates);
+        let gates = vec![unitary.clone(), unitary.with_duration(2)];
+        assert_eq!(Gate::fuse(gates.clone(), 1.0e-6).unwrap(), gates);
+    }
+
+    #[test]
+    fn condition() {
+        let x = Gate::new_unitary(
+            vec![qref(1)],
+            vec![],
+            vec![
+                Complex64::new(0f64, 0f64),
+                Complex64::new(1f64, 0f64),
+                Complex64::new(1f64, 0f64),
+                Complex64::new(0f64, 0f64),
+            ],
+        )
+        .unwrap();
+        assert!(!x.is_conditional());
+        assert!(x.evaluate_condition(|_| QubitMeasurementValue::Undefined));
+
+        let cx = x
+            .with_condition(vec![(qref(2), true), (qref(3), false)])
+            .unwrap();
+        assert!(cx.is_conditional());
+        assert_eq!(cx.get_condition(), &[(qref(2), true), (qref(3), false)]);
+        assert!(cx.evaluate_condition(|q| QubitMeasurementValue::from(q == qref(2))));
+        assert!(!cx.evaluate_condition(|_| QubitMeasurementValue::One));
+        assert!(!cx.evaluate_condition(|q| if q == qref(2) {
+            QubitMeasurementValue::One
+        } else {
+            QubitMeasurementValue::Undefined
+        }));
+        assert_eq!(cx.without_condition(), x);
+
+        let mapped = cx.with_qubit_refs(|q| qref(q.to_foreign().unwrap() + 2));
+        assert_eq!(mapped.get_condition(), &[(qref(4), true), (qref(5), false)]);
+        assert_eq!(cx.inverse().unwrap().get_condition(), cx.get_condition());
+
+        assert_eq!(
+            cx.with_condition(vec![(qref(2), false)])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 2 is used more than once in the condition"
+        );
+        let measure = Gate::new_measurement(vec![qref(1)], Matrix::new_identity(2)).unwrap();
+        let cmeasure = measure.with_condition(vec![(qref(2), true)]).unwrap();
+        assert!(cmeasure.is_conditional());
+        assert_eq!(
+            cmeasure.undefined_measurements(),
+            vec![QubitMeasurementResult::new(
+                qref(1),
+                QubitMeasurementValue::Undefined,
+                ArbData::default()
+            )]
+        );
+        assert!(x.undefined_measurements().is_empty());
+
+        // Conditional gates are never fused.
+        let gates = vec![x.clone(), cx.clone(), x];
+        assert_eq!(Gate::fuse(gates.clone(), 1.0e-6).unwrap(), gates);
+    }
+
+    #[test]
+    fn new_pauli_measurement() {
+        let g =
+            Gate::new_pauli_measurement(vec![(qref(2), Basis::X), (qref(1), Basis::Z)]).unwrap();
+        assert_eq!(
+            g.get_type(),
+            &GateType::PauliMeasurement(vec![Basis::X, Basis::Z])
+        );
+        assert_eq!(g.get_targets(), [qref(2), qref(1)]);
+        assert_eq!(g.get_controls(), []);
+        assert_eq!(g.get_measures(), [qref(2)]);
+        let xz = Matrix::from(UnboundUnitaryGate::X).kron(&Matrix::from(UnboundUnitaryGate::Z));
+        assert_eq!(g.get_matrix(), Some(&xz));
+
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![]).unwrap_err().to_string(),
+            "Invalid argument: at least one qubit is required"
+        );
+        assert_eq!(
+            Gate::new_pauli_measurement(vec![(qref(1), Basis::X), (qref(1), Basis::Z)])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 1 is used more than once"
+        );
+    }
+
+    #[test]
+    fn decompose_pauli_measurement() {
+        let q = [qref(1), qref(2), qref(3)];
+        let g =
+            Gate::new_pauli_measurement(vec![(q[0], Basis::Y), (q[1], Basis::X), (q[2], Basis::Z)])
+                .unwrap();
+        let gates = g.decompose_pauli_measurement().unwrap();
+        let index = gates
+            .iter()
+            .position(|gate| gate.get_type() == &GateType::Measurement)
+            .unwrap();
+        assert_eq!(gates[index].get_measures(), [q[0]]);
+        assert_eq!(gates[index].get_matrix(), Some(&Matrix::from(Basis::Z)));
+
+        // The gates before the measurement rotate the observable to Z on the
+        // first qubit, and the gates after it undo the rotation.
+        let rotation = circuit_matrix(&gates[..index], &q);
+        let observable = rotation
+            .multiply(g.get_matrix().unwrap())
+            .unwrap()
+            .multiply(&rotation.adjoint())
+            .unwrap();
+        let z = Matrix::from(UnboundUnitaryGate::Z).kron(&Matrix::new_identity(4));
+        assert!(observable.approx_eq(&z, 1.0e-9, false));
+        assert!(circuit_matrix(&gates[index + 1..], &q)
+            .multiply(&rotation)
+            .unwrap()
+            .approx_eq(&Matrix::new_identity(8), 1.0e-9, false));
+
+        assert_eq!(
+            gates[index]
+                .decompose_pauli_measurement()
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: not a Pauli-product measurement gate"
+        );
+    }
+
+    #[test]
+    fn barrier() {
+        let b = Gate::new_barrier(vec![qref(1), qref(2)]).unwrap();
+        assert_eq!(b.get_type(), &GateType::Barrier);
+        assert_eq!(b.get_targets(), &[qref(1), qref(2)]);
+        assert!(b.get_controls().is_empty());
+        assert!(b.get_measures().is_empty());
+        assert!(b.get_matrix().is_none());
+
+        let all = Gate::new_barrier(vec![]).unwrap();
+        assert!(all.get_targets().is_empty());
+
+        assert_eq!(
+            Gate::new_barrier(vec![qref(1), qref(1)])
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: qubit 1 is used more than once"
+        );
+    }
+
+    #[test]
+    fn duration() {
+        let h =
+            Gate::new_unitary(vec![qref(1)], vec![], Matrix::from(UnboundUnitaryGate::H)).unwrap();
+        assert_eq!(h.get_duration(), None);
+
+        let timed = h.with_duration(3);
+        assert_eq!(timed.get_duration(), Some(3));
+        assert_eq!(timed.with_duration(4).get_duration(), Some(4));
+        assert_eq!(timed.without_duration(), h);
+
+        // The duration is retained by gate transformations.
+        assert_eq!(timed.inverse().unwrap().get_duration(), Some(3));
+        assert_eq!(timed.without_qubit_refs().get_duration(), Some(3));
+        assert_eq!(
+            timed
+                .with_qubit_refs(|q| qref(q.to_foreign().unwrap() + 1))
+                .get_duration(),
+            Some(3)
+        );
+
+        // The duration is part of the serialized representation.
+        let json = serde_json::to_string(&timed).unwrap();
+        assert!(json.contains("\"duration\":3"));
+        assert_eq!(serde_json::from_str::<Gate>(&json).unwrap(), timed);
+    }
+
+    #[test]
+    fn serde_optional_fields() {
+        let h =
+            Gate::new_unitary(vec![qref(1)], vec![], Matrix::from(UnboundUnitaryGate::H)).unwrap();
+        let annotated = h
+            .with_condition(vec![(qref(2), true)])
+            .unwrap()
+            .with_duration(3);
+
+        // Gates without a condition or duration are serialized as they were
+        // before these fields were added, and such gates can still be read.
+        let json = serde_json::to_string(&h).unwrap();
+        assert!(!json.contains("condition") && !json.contains("duration"));
+        assert_eq!(serde_json::from_str::<Gate>(&json).unwrap(), h);
+        let json = serde_json::to_string(&annotated).unwrap();
+        assert_eq!(serde_json::from_str::<Gate>(&json).unwrap(), annotated);
+
+        // Formats that do not store field names always include the fields.
+        let (tx, rx) = ipc_channel::ipc::channel().unwrap();
+        for gate in [h, annotated] {
+            let cbor = serde_cbor::to_vec(&gate).unwrap();
+            assert_eq!(serde_cbor::from_slice::<Gate>(&cbor).unwrap(), gate);
+            tx.send(gate.clone()).unwrap();
+            assert_eq!(rx.recv().unwrap(), gate);
+        }
+    }
 }
@@ -33,12 +33,23 @@ pub struct Matrix {
 }
 
 /// This mod provides ser/de for Vec<Complex64>.
+///
+/// Human-readable formats represent the elements as structures with real and
+/// imaginary parts. Other formats represent them as the little-endian bytes
+/// of these parts, such that large matrices can be transferred through shared
+/// memory.
 mod complex_serde {
     use super::Complex64;
+    use crate::common::shared_memory;
     use serde::{
+        de::Error,
         ser::SerializeSeq,
         {Deserialize, Deserializer, Serialize, Serializer},
     };
+    use std::convert::TryInto;
+
+    /// Size of a serialized complex number in bytes.
+    const COMPLEX_SIZE: usize = 16;
 
     #[derive(Serialize, Deserialize)]
     #[serde(remote = "Complex64")]
@@ -51,6 +62,15 @@ mod complex_serde {
     where
         S: Serializer,
     {
+        if !serializer.is_human_readable() {
+            let mut bytes = Vec::with_capacity(value.len() * COMPLEX_SIZE);
+            for c in value.iter() {
+                bytes.extend_from_slice(&c.re.to_le_bytes());
+                bytes.extend_from_slice(&c.im.to_le_bytes());
+            }
+            return shared_memory::serialize(&bytes, serializer);
+        }
+
         #[derive(Serialize)]
         struct Wrapper<'a>(#[serde(with = "Complex64Def")] &'a Complex64);
         let mut seq = serializer.serialize_seq(Some(value.len()))?;
@@ -64,6 +84,24 @@ mod complex_serde {
     where
         D: Deserializer<'de>,
     {
+        if !deserializer.is_human_readable() {
+            let bytes = shared_memory::deserialize(deserializer)?;
+            if bytes.len() % COMPLEX_SIZE != 0 {
+                return Err(D::Error::custom(
+                    "matrix data is not a whole number of complex numbers",
+                ));
+            }
+            return Ok(bytes
+                .chunks_exact(COMPLEX_SIZE)
+                .map(|c| {
+                    Complex64::new(
+                        f64::from_le_bytes(c[..8].try_into().unwrap()),
+                        f64::from_le_bytes(c[8..].try_into().unwrap()),
+                    )
+                })
+                .collect());
+        }
+
         #[derive(Deserialize)]
         struct Wrapper(#[serde(with = "Complex64Def")] Complex64);
         let v = Vec::deserialize(deserializer)?;
@@ -394,6 +432,95 @@ impl Matrix {
         (controls, Matrix::new(entries).unwrap())
     }
 
+    /// Returns the matrix product of this Matrix and another Matrix, i.e.
+    /// `self * other`. When both matrices represent gates, the result
+    /// represents applying `other` first and then `self`.
+    ///
+    /// Returns an error if the dimensions of the matrices differ.
+    pub fn multiply(&self, other: &Matrix) -> Result<Matrix> {
+        if self.dimension != other.dimension {
+            return inv_arg(format!(
+                "cannot multiply a {0}x{0} matrix with a {1}x{1} matrix",
+                self.dimension, other.dimension
+            ));
+        }
+        Ok(self.multiply_unchecked(other))
+    }
+
+    /// Returns the matrix product of two matrices that are known to have the
+    /// same dimension.
+    fn multiply_unchecked(&self, other: &Matrix) -> Matrix {
+        let n = self.dimension;
+        let mut data = Vec::with_capacity(n * n);
+        for row in 0..n {
+            for col in 0..n {
+                data.push(
+                    (0..n)
+                        .map(|k| self[(row, k)] * other[(k, col)])
+                        .sum::<Complex64>(),
+                );
+            }
+        }
+        Matrix { data, dimension: n }
+    }
+
+    /// Returns the tensor (Kronecker) product of this Matrix and another
+    /// Matrix, i.e. `self ⊗ other`.
+    ///
+    /// Because the first qubit of a gate corresponds to the most significant
+    /// bit of the matrix indices, the qubits of `self` come before the qubits
+    /// of `other` in the resulting Matrix.
+    pub fn kron(&self, other: &Matrix) -> Matrix {
+        let dimension = self.dimension * other.dimension;
+        let mut data = Vec::with_capacity(dimension * dimension);
+        for row in 0..dimension {
+            for col in 0..dimension {
+                data.push(
+                    self[(row / other.dimension, col / other.dimension)]
+                        * other[(row % other.dimension, col % other.dimension)],
+                );
+            }
+        }
+        Matrix { data, dimension }
+    }
+
+    /// Returns the adjoint (conjugate transpose) of this Matrix. For unitary
+    /// matrices, this is the inverse.
+    pub fn adjoint(&self) -> Matrix {
+        let n = self.dimension;
+        let mut data = Vec::with_capacity(n * n);
+        for row in 0..n {
+            for col in 0..n {
+                data.push(self[(col, row)].conj());
+            }
+        }
+        Matrix { data, dimension: n }
+    }
+
+    /// Returns the trace of this Matrix, i.e. the sum of its diagonal
+    /// elements.
+    pub fn trace(&self) -> Complex64 {
+        (0..self.dimension).map(|i| self[(i, i)]).sum()
+    }
+
+    /// Returns this Matrix raised to the given non-negative integer power.
+    /// The zeroth power is the identity matrix.
+    pub fn pow(&self, exponent: u32) -> Matrix {
+        let mut result = Matrix::new_identity(self.dimension);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.multiply_unchecked(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.multiply_unchecked(&base);
+            }
+        }
+        result
+    }
+
     /// Returns the number of elements in the Matrix.
     pub fn len(&self) -> usize {
         self.data.len()
@@ -427,7 +554,7 @@ impl Matrix {
 }
 
 /// Predefined measurement/prep bases.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Basis {
     X,
     Y,
@@ -667,4 +794,126 @@ mod tests {
         let x: Matrix = UnboundUnitaryGate::X.into();
         assert!(matrix_a.approx_eq(&x, 0.001, false));
     }
+
+    #[test]
+    fn multiply() {
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        let y: Matrix = UnboundUnitaryGate::Y.into();
+        let z: Matrix = UnboundUnitaryGate::Z.into();
+        let h: Matrix = UnboundUnitaryGate::H.into();
+
+        // XY = iZ
+        let iz = Matrix::new(z.clone().into_iter().map(|e| e * c!(0., 1.))).unwrap();
+        assert!(x.multiply(&y).unwrap().approx_eq(&iz, 0.0001, false));
+
+        // HZH = X
+        assert!(h
+            .multiply(&z)
+            .unwrap()
+            .multiply(&h)
+            .unwrap()
+            .approx_eq(&x, 0.0001, false));
+
+        assert_eq!(
+            x.multiply(&Matrix::new_identity(4))
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument: cannot multiply a 2x2 matrix with a 4x4 matrix"
+        );
+    }
+
+    #[test]
+    fn kron() {
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        let i = Matrix::new_identity(2);
+        assert_eq!(x.kron(&i).dimension(), 4);
+        assert!(x.kron(&i).approx_eq(
+            &matrix!(
+                0., 0., 1., 0.;
+                0., 0., 0., 1.;
+                1., 0., 0., 0.;
+                0., 1., 0., 0.;
+            ),
+            0.0001,
+            false
+        ));
+        assert!(i.kron(&x).approx_eq(
+            &matrix!(
+                0., 1., 0., 0.;
+                1., 0., 0., 0.;
+                0., 0., 0., 1.;
+                0., 0., 1., 0.;
+            ),
+            0.0001,
+            false
+        ));
+        assert_eq!(x.kron(&x).kron(&x).num_qubits(), Some(3));
+    }
+
+    #[test]
+    fn adjoint() {
+        let s: Matrix = UnboundUnitaryGate::S.into();
+        let sdag: Matrix = UnboundUnitaryGate::SDAG.into();
+        assert!(s.adjoint().approx_eq(&sdag, 0.0001, false));
+        let m = matrix!(
+            1., (0., 2.);
+            3., (4., -5.);
+        );
+        assert!(m.adjoint().approx_eq(
+            &matrix!(
+                1., 3.;
+                (0., -2.), (4., 5.);
+            ),
+            0.0001,
+            false
+        ));
+        let u: Matrix = UnboundUnitaryGate::R(0.1, 0.2, 0.3).into();
+        assert!(u.multiply(&u.adjoint()).unwrap().approx_eq(
+            &Matrix::new_identity(2),
+            0.0001,
+            false
+        ));
+    }
+
+    #[test]
+    fn trace() {
+        assert_eq!(Matrix::new_identity(8).trace(), c!(8.));
+        let x: Matrix = UnboundUnitaryGate::X.into();
+        assert_eq!(x.trace(), c!(0.));
+        let m = matrix!(
+            (1., 1.), 3.;
+            3., (4., -5.);
+        );
+        assert_eq!(m.trace(), c!(5., -4.));
+    }
+
+    #[test]
+    fn pow() {
+        let t: Matrix = UnboundUnitaryGate::T.into();
+        let s: Matrix = UnboundUnitaryGate::S.into();
+        let z: Matrix = UnboundUnitaryGate::Z.into();
+        assert!(t.pow(0).approx_eq(&Matrix::new_identity(2), 0.0001, false));
+        assert!(t.pow(1).approx_eq(&t, 0.0001, false));
+        assert!(t.pow(2).approx_eq(&s, 0.0001, false));
+        assert!(t.pow(4).approx_eq(&z, 0.0001, false));
+        assert!(t.pow(7).approx_eq(&t.adjoint(), 0.0001, false));
+        assert!(t.pow(13).approx_eq(&z.multiply(&t).unwrap(), 0.0001, false));
+    }
+
+    #[test]
+    fn serde() {
+        let m = matrix!(
+            (1., 1.), 3.;
+            3., (4., -5.);
+        );
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, "{\"data\":[{\"re\":1.0,\"im\":1.0},{\"re\":3.0,\"im\":0.0},{\"re\":3.0,\"im\":0.0},{\"re\":4.0,\"im\":-5.0}],\"dimension\":2}");
+        assert_eq!(serde_json::from_str::<Matrix>(&json).unwrap(), m);
+
+        let large = Matrix::new_identity(128);
+        for m in [m, large] {
+            let cbor = serde_cbor::to_vec(&m).unwrap();
+            assert_eq!(serde_cbor::from_slice::<Matrix>(&cbor).unwrap(), m);
+        }
+    }
 }
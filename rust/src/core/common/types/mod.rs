@@ -33,6 +33,10 @@ pub use measurement::{QubitMeasurementResult, QubitMeasurementValue};
 mod plugin_type;
 pub use plugin_type::PluginType;
 
+// Optional protocol features supported by plugins.
+mod plugin_capability;
+pub use plugin_capability::PluginCapability;
+
 // Metadata used to identify plugins.
 mod plugin_metadata;
 pub use plugin_metadata::PluginMetadata;
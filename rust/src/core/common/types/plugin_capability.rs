@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Enumeration of the optional protocol features that a plugin may support.
+///
+/// Plugins report the features they support to the simulator in their
+/// handshake. Capabilities are opt-in: a plugin that does not declare any is
+/// not checked. Frontends declare the kinds of gates that they send, while
+/// operators and backends declare the kinds of gates that they accept.
+/// Operators are expected to be able to pass on the gates that they accept.
+/// When the simulation is initialized, DQCsim rejects the pipeline if a
+/// plugin does not support a capability of its upstream neighbour, as long as
+/// both plugins declare their capabilities.
+///
+/// The discriminants are the bit indices used to represent the capabilities
+/// in the handshake, so they must never change.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum PluginCapability {
+    /// The plugin handles unitary gates.
+    UnitaryGates = 0,
+
+    /// The plugin handles measurement gates.
+    MeasurementGates = 1,
+
+    /// The plugin handles prep gates.
+    PrepGates = 2,
+
+    /// The plugin handles Pauli-product measurement gates.
+    PauliMeasurementGates = 3,
+
+    /// The plugin handles barriers.
+    Barriers = 4,
+
+    /// The plugin handles custom gates. Note that it may still reject custom
+    /// gates with names it doesn't recognize.
+    CustomGates = 5,
+
+    /// The plugin handles classically-conditioned gates. Backends need not
+    /// declare this, since DQCsim resolves the conditions for them.
+    ConditionalGates = 6,
+}
+
+impl PluginCapability {
+    /// All capabilities, in order of their discriminant.
+    const ALL: [PluginCapability; 7] = [
+        PluginCapability::UnitaryGates,
+        PluginCapability::MeasurementGates,
+        PluginCapability::PrepGates,
+        PluginCapability::PauliMeasurementGates,
+        PluginCapability::Barriers,
+        PluginCapability::CustomGates,
+        PluginCapability::ConditionalGates,
+    ];
+
+    /// Converts the given capabilities to a bitmask, in which the bit indexed
+    /// by the discriminant of each capability is set.
+    pub fn to_mask<'a>(capabilities: impl IntoIterator<Item = &'a PluginCapability>) -> u64 {
+        capabilities
+            .into_iter()
+            .fold(0, |mask, &capability| mask | 1 << capability as u64)
+    }
+
+    /// Converts a bitmask produced by `to_mask()` back to a list of
+    /// capabilities. Bits that do not correspond to a capability known to
+    /// this version of DQCsim are ignored.
+    pub fn from_mask(mask: u64) -> Vec<PluginCapability> {
+        PluginCapability::ALL
+            .iter()
+            .filter(|&&capability| mask & 1 << capability as u64 != 0)
+            .cloned()
+            .collect()
+    }
+}
+
+impl fmt::Display for PluginCapability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PluginCapability::UnitaryGates => write!(f, "unitary gates"),
+            PluginCapability::MeasurementGates => write!(f, "measurement gates"),
+            PluginCapability::PrepGates => write!(f, "prep gates"),
+            PluginCapability::PauliMeasurementGates => {
+                write!(f, "Pauli-product measurement gates")
+            }
+            PluginCapability::Barriers => write!(f, "barriers"),
+            PluginCapability::CustomGates => write!(f, "custom gates"),
+            PluginCapability::ConditionalGates => write!(f, "conditional gates"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask() {
+        assert_eq!(PluginCapability::to_mask(&[]), 0);
+        assert_eq!(
+            PluginCapability::to_mask(&[
+                PluginCapability::UnitaryGates,
+                PluginCapability::ConditionalGates
+            ]),
+            0b100_0001
+        );
+        assert_eq!(
+            PluginCapability::from_mask(0b100_0001),
+            vec![
+                PluginCapability::UnitaryGates,
+                PluginCapability::ConditionalGates
+            ]
+        );
+        assert_eq!(
+            PluginCapability::from_mask(PluginCapability::to_mask(&PluginCapability::ALL)),
+            PluginCapability::ALL.to_vec()
+        );
+
+        // Capabilities added by later versions are ignored.
+        assert_eq!(
+            PluginCapability::from_mask(1 << 63 | 1 << 4),
+            vec![PluginCapability::Barriers]
+        );
+    }
+}
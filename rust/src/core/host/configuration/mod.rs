@@ -14,6 +14,7 @@ pub use timeout::Timeout;
 
 mod plugin;
 pub use plugin::{
+    attach::{PluginAttachConfiguration, PluginAttachNonfunctionalConfiguration},
     log::PluginLogConfiguration,
     process::{
         PluginProcessConfiguration, PluginProcessFunctionalConfiguration,
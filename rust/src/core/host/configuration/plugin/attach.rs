@@ -0,0 +1,177 @@
+use crate::{
+    common::{
+        error::Result,
+        log::{tee_file::TeeFileConfiguration, LoglevelFilter},
+        types::{ArbCmd, PluginType},
+    },
+    host::{
+        configuration::{
+            plugin::log::PluginLogConfiguration, timeout::Timeout, PluginConfiguration,
+            PluginProcessFunctionalConfiguration, ReproductionPathStyle,
+        },
+        plugin::{attach::PluginAttach, Plugin},
+        reproduction::{PluginModification, PluginReproduction},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Structure describing the NONfunctional configuration of an attached
+/// plugin, i.e. the parameters that only affect how the plugin represents its
+/// output.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PluginAttachNonfunctionalConfiguration {
+    /// Specifies the verbosity of the messages sent to DQCsim.
+    pub verbosity: LoglevelFilter,
+
+    /// Specifies the tee file configuration for this plugin.
+    pub tee_files: Vec<TeeFileConfiguration>,
+
+    /// Specifies the timeout for the externally started plugin to attach.
+    /// This is infinite by default, since the plugin is usually started by
+    /// hand or by a job scheduler after DQCsim starts listening.
+    pub accept_timeout: Timeout,
+
+    /// Specifies the timeout duration to wait for the plugin to acknowledge
+    /// the abort request. Unlike spawned plugins, attached plugins cannot be
+    /// killed when this timeout expires; DQCsim just stops waiting.
+    pub shutdown_timeout: Timeout,
+}
+
+impl Default for PluginAttachNonfunctionalConfiguration {
+    fn default() -> PluginAttachNonfunctionalConfiguration {
+        PluginAttachNonfunctionalConfiguration {
+            verbosity: LoglevelFilter::Trace,
+            tee_files: vec![],
+            accept_timeout: Timeout::Infinite,
+            shutdown_timeout: Timeout::from_seconds(5),
+        }
+    }
+}
+
+/// Represents the complete configuration for a plugin that is started
+/// externally, for instance under a debugger, inside a container, or by a job
+/// scheduler, and attaches to DQCsim through a known address.
+///
+/// DQCsim listens on the address and waits for the plugin to connect to it.
+/// The plugin must be passed the address in place of the simulator address
+/// that DQCsim normally passes to the plugins it spawns.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PluginAttachConfiguration {
+    /// Name of the plugin, used to refer to the plugin by the log system.
+    pub name: String,
+
+    /// The address to listen on, either `tcp://<host>:<port>` or
+    /// `unix://<path>`. The host defaults to the loopback address when it is
+    /// omitted, as in `tcp://:<port>`. Note that DQCsim does not authenticate
+    /// the plugin that connects; see the [`transport`] module.
+    ///
+    /// [`transport`]: ../../common/transport/index.html
+    ///
+    /// Note that an attached plugin connecting from another machine can only
+    /// reach the plugins next to it if they serve their connections over TCP
+    /// as well, which is the case when they are attached over TCP themselves.
+    pub address: String,
+
+    /// Plugin type.
+    pub typ: PluginType,
+
+    /// ArbCmd objects passed to the plugin initialization RPC.
+    pub init: Vec<ArbCmd>,
+
+    /// The nonfunctional configuration of the plugin, i.e. any options that
+    /// do not affect how the plugin behaves functionally, but only affect its
+    /// output representation.
+    pub nonfunctional: PluginAttachNonfunctionalConfiguration,
+}
+
+impl PluginAttachConfiguration {
+    /// Creates a new attached plugin configuration.
+    ///
+    /// The default values are inserted for the configuration options.
+    pub fn new(
+        name: impl Into<String>,
+        address: impl Into<String>,
+        typ: impl Into<PluginType>,
+    ) -> PluginAttachConfiguration {
+        PluginAttachConfiguration {
+            name: name.into(),
+            address: address.into(),
+            typ: typ.into(),
+            init: vec![],
+            nonfunctional: PluginAttachNonfunctionalConfiguration::default(),
+        }
+    }
+
+    /// Adds an init cmd to the list, builder style.
+    pub fn with_init_cmd(mut self, cmd: impl Into<ArbCmd>) -> PluginAttachConfiguration {
+        self.init.push(cmd.into());
+        self
+    }
+
+    /// Sets the accept timeout, builder style.
+    pub fn with_accept_timeout(mut self, timeout: Timeout) -> PluginAttachConfiguration {
+        self.nonfunctional.accept_timeout = timeout;
+        self
+    }
+}
+
+impl From<PluginAttachConfiguration> for Box<dyn PluginConfiguration> {
+    fn from(configuration: PluginAttachConfiguration) -> Box<dyn PluginConfiguration> {
+        Box::new(configuration) as Box<dyn PluginConfiguration>
+    }
+}
+
+impl PluginConfiguration for PluginAttachConfiguration {
+    fn instantiate(self: Box<Self>) -> Box<dyn Plugin> {
+        Box::new(PluginAttach::new(*self))
+    }
+
+    fn get_log_configuration(&self) -> PluginLogConfiguration {
+        self.into()
+    }
+
+    fn get_type(&self) -> PluginType {
+        self.typ
+    }
+
+    fn get_reproduction(&self, _: ReproductionPathStyle) -> Result<PluginReproduction> {
+        Ok(PluginReproduction {
+            name: self.name.clone(),
+            executable: PathBuf::new(),
+            script: None,
+            functional: PluginProcessFunctionalConfiguration {
+                init: self.init.clone(),
+                ..Default::default()
+            },
+            attach: Some(self.address.clone()),
+        })
+    }
+
+    fn apply_modification(&mut self, modification: &PluginModification) {
+        if let Some(verbosity) = modification.verbosity {
+            self.nonfunctional.verbosity = verbosity;
+        }
+        self.nonfunctional
+            .tee_files
+            .extend(modification.tee_files.iter().cloned());
+        if let Some(accept_timeout) = modification.accept_timeout {
+            self.nonfunctional.accept_timeout = accept_timeout;
+        }
+        if let Some(shutdown_timeout) = modification.shutdown_timeout {
+            self.nonfunctional.shutdown_timeout = shutdown_timeout;
+        }
+    }
+
+    fn limit_verbosity(&mut self, max_verbosity: LoglevelFilter) {
+        if self.nonfunctional.verbosity > max_verbosity {
+            self.nonfunctional.verbosity = max_verbosity;
+        }
+    }
+
+    fn set_default_name(&mut self, default_name: String) {
+        if self.name.is_empty() {
+            self.name = default_name;
+        }
+    }
+}
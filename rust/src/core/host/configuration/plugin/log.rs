@@ -1,6 +1,6 @@
 use crate::{
     common::log::{tee_file::TeeFileConfiguration, LoglevelFilter},
-    host::configuration::PluginProcessConfiguration,
+    host::configuration::{PluginAttachConfiguration, PluginProcessConfiguration},
 };
 use serde::{Deserialize, Serialize};
 
@@ -38,3 +38,13 @@ impl From<&PluginProcessConfiguration> for PluginLogConfiguration {
         }
     }
 }
+
+impl From<&PluginAttachConfiguration> for PluginLogConfiguration {
+    fn from(cfg: &PluginAttachConfiguration) -> PluginLogConfiguration {
+        PluginLogConfiguration {
+            name: cfg.name.clone(),
+            verbosity: cfg.nonfunctional.verbosity,
+            tee_files: cfg.nonfunctional.tee_files.clone(),
+        }
+    }
+}
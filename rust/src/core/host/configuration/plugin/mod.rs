@@ -3,11 +3,12 @@ use crate::{
     host::{
         configuration::{plugin::log::PluginLogConfiguration, ReproductionPathStyle},
         plugin::Plugin,
-        reproduction::PluginReproduction,
+        reproduction::{PluginModification, PluginReproduction},
     },
 };
 use std::fmt::Debug;
 
+pub mod attach;
 pub mod log;
 pub mod process;
 pub mod thread;
@@ -30,6 +31,13 @@ pub trait PluginConfiguration: Debug {
     /// error.
     fn get_reproduction(&self, path_style: ReproductionPathStyle) -> Result<PluginReproduction>;
 
+    /// Applies a nonfunctional configuration modification to this plugin.
+    ///
+    /// Called when a run is reproduced to apply the modifications specified
+    /// on the command line. Options that don't apply to this kind of plugin
+    /// are ignored.
+    fn apply_modification(&mut self, modification: &PluginModification);
+
     /// Limits the verbosity of the messages reported to the simulator.
     ///
     /// Called when the simulation is initialized to limit the plugin's
@@ -11,7 +11,7 @@ use crate::{
             ReproductionPathStyle,
         },
         plugin::{process::PluginProcess, Plugin},
-        reproduction::PluginReproduction,
+        reproduction::{PluginModification, PluginReproduction},
     },
 };
 use serde::{Deserialize, Serialize};
@@ -304,9 +304,31 @@ impl PluginConfiguration for PluginProcessConfiguration {
                 env: self.functional.env.clone(),
                 work: path_style.convert_path(&self.functional.work)?,
             },
+            attach: None,
         })
     }
 
+    fn apply_modification(&mut self, modification: &PluginModification) {
+        if let Some(verbosity) = modification.verbosity {
+            self.nonfunctional.verbosity = verbosity;
+        }
+        self.nonfunctional
+            .tee_files
+            .extend(modification.tee_files.iter().cloned());
+        if let Some(stdout_mode) = &modification.stdout_mode {
+            self.nonfunctional.stdout_mode = stdout_mode.clone();
+        }
+        if let Some(stderr_mode) = &modification.stderr_mode {
+            self.nonfunctional.stderr_mode = stderr_mode.clone();
+        }
+        if let Some(accept_timeout) = modification.accept_timeout {
+            self.nonfunctional.accept_timeout = accept_timeout;
+        }
+        if let Some(shutdown_timeout) = modification.shutdown_timeout {
+            self.nonfunctional.shutdown_timeout = shutdown_timeout;
+        }
+    }
+
     fn limit_verbosity(&mut self, max_verbosity: LoglevelFilter) {
         if self.nonfunctional.verbosity > max_verbosity {
             self.nonfunctional.verbosity = max_verbosity;
@@ -10,7 +10,7 @@ use crate::{
             thread::{PluginThread, PluginThreadClosure},
             Plugin,
         },
-        reproduction::PluginReproduction,
+        reproduction::{PluginModification, PluginReproduction},
     },
     plugin::{definition::PluginDefinition, state::PluginState},
     trace,
@@ -115,6 +115,15 @@ impl PluginConfiguration for PluginThreadConfiguration {
         inv_op("It's not possible to build a plugin reproduction for PluginThreads")
     }
 
+    fn apply_modification(&mut self, modification: &PluginModification) {
+        if let Some(verbosity) = modification.verbosity {
+            self.log_configuration.verbosity = verbosity;
+        }
+        self.log_configuration
+            .tee_files
+            .extend(modification.tee_files.iter().cloned());
+    }
+
     fn limit_verbosity(&mut self, max_verbosity: LoglevelFilter) {
         if self.log_configuration.verbosity > max_verbosity {
             self.log_configuration.verbosity = max_verbosity;
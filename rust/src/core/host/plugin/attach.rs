@@ -0,0 +1,128 @@
+//! Implementation of the plugin trait for plugins that are started externally
+//! and attach to DQCsim through a known address.
+
+use crate::{
+    common::{
+        channel::SimulatorChannel,
+        error::Result,
+        log::thread::LogThread,
+        protocol::{PluginToSimulator, SimulatorToPlugin},
+        shared_memory::with_shared_memory,
+        transport::OneShotServer,
+        types::{ArbCmd, PluginType},
+    },
+    host::{
+        configuration::{PluginAttachConfiguration, PluginLogConfiguration, Timeout},
+        plugin::{accept_with_timeout, Plugin},
+    },
+    info, trace, warn,
+};
+use std::time;
+
+/// A Plugin that was started externally, for instance under a debugger,
+/// inside a container, or by a job scheduler.
+///
+/// PluginAttach implements the [`Plugin`] trait by listening on the address
+/// specified in its [`PluginAttachConfiguration`] and waiting for the plugin
+/// to connect, instead of spawning it.
+#[derive(Debug)]
+pub struct PluginAttach {
+    /// The complete plugin configuration.
+    configuration: PluginAttachConfiguration,
+    /// The SimulatorChannel is populated by the spawn method of the Plugin
+    /// trait.
+    channel: Option<SimulatorChannel>,
+}
+
+impl PluginAttach {
+    /// Constructs a new PluginAttach based on a PluginAttachConfiguration.
+    /// DQCsim does not start listening for the plugin until the [`Plugin`]
+    /// trait's [`spawn`] method is called.
+    pub fn new(configuration: PluginAttachConfiguration) -> PluginAttach {
+        PluginAttach {
+            configuration,
+            channel: None,
+        }
+    }
+}
+
+impl Plugin for PluginAttach {
+    /// Listens on the configured address and waits for the plugin to attach.
+    fn spawn(&mut self, _: &LogThread) -> Result<()> {
+        let (server, address) = OneShotServer::bind(&self.configuration.address[..])?;
+        info!(
+            "Waiting for plugin {} to attach to {}",
+            self.configuration.name, address
+        );
+        self.channel = Some(accept_with_timeout(
+            server,
+            self.configuration.nonfunctional.accept_timeout,
+        )?);
+        info!("Plugin {} attached", self.configuration.name);
+        Ok(())
+    }
+
+    fn plugin_type(&self) -> PluginType {
+        self.configuration.typ
+    }
+
+    fn init_cmds(&self) -> Vec<ArbCmd> {
+        self.configuration.init.clone()
+    }
+
+    fn log_configuration(&self) -> PluginLogConfiguration {
+        PluginLogConfiguration::from(&self.configuration)
+    }
+
+    fn rpc(&mut self, msg: SimulatorToPlugin) -> Result<PluginToSimulator> {
+        let sender = &self.channel.as_ref().unwrap().0;
+        with_shared_memory(|| sender.send(msg))?;
+        Ok(self.channel.as_ref().unwrap().1.recv()?)
+    }
+}
+
+impl Drop for PluginAttach {
+    fn drop(&mut self) {
+        trace!("Dropping PluginAttach");
+
+        let channel = match self.channel.take() {
+            Some(channel) => channel,
+            None => {
+                trace!("PluginAttach was never attached");
+                return;
+            }
+        };
+
+        trace!(
+            "Aborting PluginAttach (timeout: {:?})",
+            self.configuration.nonfunctional.shutdown_timeout
+        );
+        if let Err(e) = channel.0.send(SimulatorToPlugin::Abort) {
+            warn!("Failed to abort attached plugin: {}", e);
+            return;
+        }
+
+        // We can't kill a process we didn't spawn, so all we can do is wait
+        // for the plugin to acknowledge the abort request.
+        match self.configuration.nonfunctional.shutdown_timeout {
+            Timeout::Infinite => {
+                channel.1.recv().ok();
+            }
+            Timeout::Duration(duration) => {
+                let now = time::Instant::now();
+                loop {
+                    if now.elapsed() >= duration {
+                        warn!("Attached plugin did not acknowledge abort request in time");
+                        break;
+                    }
+                    match channel.1.try_recv() {
+                        Ok(PluginToSimulator::Success) => break,
+                        Ok(_) | Err(_) => {
+                            std::thread::sleep(std::time::Duration::from_millis(10));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,20 +1,24 @@
 //! Contains structs that manage the lifetime and connections of a single
 //! plugin.
 
+pub mod attach;
 pub mod process;
 pub mod thread;
 
 use crate::{
     common::{
+        channel::SimulatorChannel,
         error::{err, Result},
         log::thread::LogThread,
         protocol::{
-            PluginAcceptUpstreamRequest, PluginInitializeRequest, PluginInitializeResponse,
-            PluginToSimulator, PluginUserInitializeRequest, SimulatorToPlugin,
+            Handshake, PluginAcceptUpstreamRequest, PluginInitializeRequest,
+            PluginInitializeResponse, PluginToSimulator, PluginUserInitializeRequest,
+            SimulatorToPlugin, PROTOCOL_VERSION,
         },
-        types::{ArbCmd, ArbData, PluginType},
+        transport::OneShotServer,
+        types::{ArbCmd, ArbData, PluginCapability, PluginType},
     },
-    host::configuration::PluginLogConfiguration,
+    host::configuration::{PluginLogConfiguration, Timeout},
 };
 use std::fmt::Debug;
 
@@ -70,6 +74,27 @@ impl dyn Plugin {
         self.log_configuration().name
     }
 
+    /// Exchanges a `Handshake` with this plugin, returning the capabilities
+    /// that it reports. This must be the first request sent to the plugin.
+    ///
+    /// An error is returned if the plugin uses a different protocol version.
+    pub fn handshake(&mut self) -> Result<Vec<PluginCapability>> {
+        let handshake = checked_rpc!(
+            self,
+            Handshake::new(&[]),
+            expect Handshake
+        )?;
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            err(format!(
+                "plugin {} uses protocol version {}, but the simulator uses protocol version {}",
+                self.name(),
+                handshake.protocol_version,
+                PROTOCOL_VERSION
+            ))?;
+        }
+        Ok(handshake.get_capabilities())
+    }
+
     /// Sends an `PluginInitializeRequest` to this plugin.
     pub fn initialize(
         &mut self,
@@ -116,3 +141,18 @@ impl dyn Plugin {
         )
     }
 }
+
+/// Waits for a plugin to connect to the given simulator server, giving up
+/// when the timeout expires.
+fn accept_with_timeout(
+    server: OneShotServer<SimulatorToPlugin, PluginToSimulator>,
+    timeout: Timeout,
+) -> Result<SimulatorChannel> {
+    match timeout {
+        Timeout::Infinite => server.accept(),
+        Timeout::Duration(timeout) => match server.accept_timeout(timeout)? {
+            Some(channel) => Ok(channel),
+            None => err("plugin did not connect within specified timeout"),
+        },
+    }
+}
@@ -4,22 +4,23 @@
 use crate::{
     common::{
         channel::SimulatorChannel,
-        error::{err, inv_op, ErrorKind, Result},
+        error::{inv_op, Result},
         log::{stdio::proxy_stdio, thread::LogThread},
         protocol::{PluginToSimulator, SimulatorToPlugin},
+        shared_memory::with_shared_memory,
+        transport::{OneShotServer, Transport},
         types::{ArbCmd, PluginType},
     },
     host::{
         configuration::{
             EnvMod, PluginLogConfiguration, PluginProcessConfiguration, StreamCaptureMode, Timeout,
         },
-        plugin::Plugin,
+        plugin::{accept_with_timeout, Plugin},
     },
     info, trace, warn,
 };
-use ipc_channel::ipc;
 use is_executable::IsExecutable;
-use std::{process, sync, thread, time};
+use std::{process, time};
 
 /// A Plugin running in a child process.
 ///
@@ -60,7 +61,7 @@ impl Plugin for PluginProcess {
     /// configuration specifies a script.
     fn spawn(&mut self, logger: &LogThread) -> Result<()> {
         // Setup connection channel
-        let (server, server_name) = ipc::IpcOneShotServer::new()?;
+        let (server, server_name) = OneShotServer::new(&Transport::Ipc)?;
 
         // Get an absolute path to the plugin executable
         let exe_path = self.configuration.specification.executable.canonicalize()?;
@@ -127,49 +128,10 @@ impl Plugin for PluginProcess {
         }
 
         // Connect and get channel from child process
-        match self.configuration.nonfunctional.accept_timeout {
-            Timeout::Infinite => {
-                let (_, channel) = server.accept()?;
-                self.channel = Some(channel);
-            }
-            Timeout::Duration(timeout) => {
-                #[cfg_attr(feature = "cargo-clippy", allow(clippy::mutex_atomic))]
-                let pair = sync::Arc::new((sync::Mutex::new(false), sync::Condvar::new()));
-                let pair2 = pair.clone();
-                let handle: thread::JoinHandle<Result<SimulatorChannel>> =
-                    thread::spawn(move || {
-                        {
-                            let &(ref lock, _) = &*pair2;
-                            let mut started = lock.lock().expect("Unable to aquire lock");
-                            *started = true;
-                        }
-                        let (_, channel) = server.accept()?;
-                        {
-                            let &(_, ref cvar) = &*pair2;
-                            cvar.notify_one();
-                        }
-                        Ok(channel)
-                    });
-                let &(ref lock, ref cvar) = &*pair;
-                let (started, wait_result) = cvar
-                    .wait_timeout(
-                        lock.lock()
-                            .expect("Plugin IPC connection start lock poisoned"),
-                        timeout,
-                    )
-                    .expect("Plugin IPC connection start lock poisoned");
-                if *started && !wait_result.timed_out() {
-                    self.channel = handle
-                        .join()
-                        .map_err(|_| {
-                            ErrorKind::Other("Plugin IPC connection thread failed".to_string())
-                        })?
-                        .ok();
-                } else {
-                    err("plugin did not connect within specified timeout")?
-                }
-            }
-        }
+        self.channel = Some(accept_with_timeout(
+            server,
+            self.configuration.nonfunctional.accept_timeout,
+        )?);
 
         Ok(())
     }
@@ -187,7 +149,8 @@ impl Plugin for PluginProcess {
     }
 
     fn rpc(&mut self, msg: SimulatorToPlugin) -> Result<PluginToSimulator> {
-        self.channel.as_ref().unwrap().0.send(msg)?;
+        let sender = &self.channel.as_ref().unwrap().0;
+        with_shared_memory(|| sender.send(msg))?;
         Ok(self.channel.as_ref().unwrap().1.recv()?)
     }
 }
@@ -7,6 +7,7 @@ use crate::{
         error::Result,
         log::thread::LogThread,
         protocol::{PluginToSimulator, SimulatorToPlugin},
+        shared_memory::with_shared_memory,
         types::{ArbCmd, PluginType},
     },
     error, fatal,
@@ -86,7 +87,8 @@ impl Plugin for PluginThread {
     }
 
     fn rpc(&mut self, msg: SimulatorToPlugin) -> Result<PluginToSimulator> {
-        self.channel.as_ref().unwrap().0.send(msg)?;
+        let sender = &self.channel.as_ref().unwrap().0;
+        with_shared_memory(|| sender.send(msg))?;
         Ok(self.channel.as_ref().unwrap().1.recv()?)
     }
 
@@ -21,7 +21,9 @@ pub struct PluginReproduction {
     /// Name of the plugin, used to refer to the plugin by the log system.
     pub name: String,
 
-    /// The executable filename of the plugin.
+    /// The executable filename of the plugin. This is empty for plugins that
+    /// attached to DQCsim.
+    #[serde(default, skip_serializing_if = "path_is_empty")]
     pub executable: PathBuf,
 
     /// If specified, the executable is expected to be an interpreter, which is
@@ -31,8 +33,60 @@ pub struct PluginReproduction {
 
     /// The functional configuration of the plugin, i.e. the options
     /// configuring how the plugin behaves (besides the specification).
+    ///
+    /// Only the initialization commands are used for plugins that attached to
+    /// DQCsim.
     #[serde(flatten)]
     pub functional: PluginProcessFunctionalConfiguration,
+
+    /// If specified, the plugin was started externally and attached to
+    /// DQCsim through this address, instead of being spawned by DQCsim.
+    /// Reproducing the run requires the plugin to be started externally
+    /// again, and to attach to the same address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attach: Option<String>,
+}
+
+/// Returns whether the given path is empty, in which case it is omitted from
+/// reproduction files.
+fn path_is_empty(path: &Path) -> bool {
+    path.as_os_str().is_empty()
+}
+
+impl PluginReproduction {
+    /// Constructs a configuration for the plugin with the given type. The
+    /// nonfunctional configuration is set to its default value.
+    fn to_configuration(&self, typ: PluginType) -> Result<Box<dyn PluginConfiguration>> {
+        match (&self.attach, path_is_empty(&self.executable)) {
+            (None, false) => Ok(Box::new(PluginProcessConfiguration {
+                name: self.name.clone(),
+                specification: PluginProcessSpecification::new(
+                    &self.executable,
+                    self.script.clone(),
+                    typ,
+                ),
+                functional: self.functional.clone(),
+                nonfunctional: PluginProcessNonfunctionalConfiguration::default(),
+            })),
+            (Some(address), true) if self.script.is_none() => {
+                Ok(Box::new(PluginAttachConfiguration {
+                    name: self.name.clone(),
+                    address: address.clone(),
+                    typ,
+                    init: self.functional.init.clone(),
+                    nonfunctional: PluginAttachNonfunctionalConfiguration::default(),
+                }))
+            }
+            (None, true) => err(format!(
+                "reproduction file corrupted: plugin {} specifies neither an executable nor an attach address",
+                self.name
+            )),
+            (Some(_), _) => err(format!(
+                "reproduction file corrupted: plugin {} specifies both an attach address and an executable or script",
+                self.name
+            )),
+        }
+    }
 }
 
 /// Represents a nonfunctional configuration modification for a previously
@@ -64,7 +118,7 @@ pub struct PluginModification {
     pub stderr_mode: Option<StreamCaptureMode>,
 
     /// Specifies the timeout for connecting to the plugin after it has been
-    /// spawned.
+    /// spawned, or for an attached plugin to attach.
     pub accept_timeout: Option<Timeout>,
 
     /// Specifies the timeout for connecting to the plugin after it has been
@@ -80,25 +134,7 @@ impl PluginModification {
     pub fn apply(self, to: &mut Vec<PluginProcessConfiguration>) -> Result<()> {
         for plugin_config in &mut to.iter_mut() {
             if plugin_config.name == self.name {
-                if let Some(verbosity) = self.verbosity {
-                    plugin_config.nonfunctional.verbosity = verbosity;
-                }
-                plugin_config
-                    .nonfunctional
-                    .tee_files
-                    .extend(self.tee_files.iter().cloned());
-                if let Some(stdout_mode) = &self.stdout_mode {
-                    plugin_config.nonfunctional.stdout_mode = stdout_mode.clone();
-                }
-                if let Some(stderr_mode) = &self.stderr_mode {
-                    plugin_config.nonfunctional.stderr_mode = stderr_mode.clone();
-                }
-                if let Some(accept_timeout) = &self.accept_timeout {
-                    plugin_config.nonfunctional.accept_timeout = *accept_timeout;
-                }
-                if let Some(shutdown_timeout) = &self.shutdown_timeout {
-                    plugin_config.nonfunctional.shutdown_timeout = *shutdown_timeout;
-                }
+                plugin_config.apply_modification(&self);
                 return Ok(());
             }
         }
@@ -108,6 +144,25 @@ impl PluginModification {
             friendly_enumerate(to.iter().map(|x| &x.name[..]), Some("or"))
         ))
     }
+
+    /// Applies this plugin modification to a vector of plugin configurations
+    /// of any kind.
+    ///
+    /// An error is returned if the referenced plugin cannot be found in the
+    /// vector, otherwise `Ok(())` is returned.
+    pub fn apply_to_any(self, to: &mut Vec<Box<dyn PluginConfiguration>>) -> Result<()> {
+        for plugin_config in &mut to.iter_mut() {
+            if plugin_config.get_name() == self.name {
+                plugin_config.apply_modification(&self);
+                return Ok(());
+            }
+        }
+        inv_arg(format!(
+            "There is no plugin named {}. The available plugins are {}.",
+            self.name,
+            friendly_enumerate(to.iter().map(|x| x.get_name()), Some("or"))
+        ))
+    }
 }
 
 /// The contents of a reproduction file.
@@ -190,45 +245,36 @@ impl Reproduction {
             config.seed.value = self.seed;
         }
 
+        // Make sure we have at least a frontend and a backend.
+        let plugin_count = self.plugins.len();
+        if plugin_count < 2 {
+            err("reproduction file corrupted: less than two plugins specified")?;
+        }
+
         // Construct the plugin configurations. The nonfunctional config is set
-        // to the default value. Note that we pretend that every plugin is an
-        // operator here; we change this later.
+        // to the default value.
         let mut plugins = self
             .plugins
             .iter()
-            .map(|x| PluginProcessConfiguration {
-                name: x.name.clone(),
-                specification: PluginProcessSpecification::new(
-                    &x.executable,
-                    x.script.clone(),
-                    PluginType::Operator,
-                ),
-                functional: x.functional.clone(),
-                nonfunctional: PluginProcessNonfunctionalConfiguration::default(),
+            .enumerate()
+            .map(|(index, x)| {
+                x.to_configuration(if index == 0 {
+                    PluginType::Frontend
+                } else if index == plugin_count - 1 {
+                    PluginType::Backend
+                } else {
+                    PluginType::Operator
+                })
             })
-            .collect::<Vec<PluginProcessConfiguration>>();
-
-        // Set the plugin types and make sure we have at least a frontend
-        // and a backend.
-        let plugin_count = plugins.len();
-        if plugin_count < 2 {
-            err("reproduction file corrupted: less than two plugins specified")?;
-        }
-        plugins[0].specification.typ = PluginType::Frontend;
-        plugins[plugin_count - 1].specification.typ = PluginType::Backend;
+            .collect::<Result<Vec<Box<dyn PluginConfiguration>>>>()?;
 
         // Update the plugin nonfunctional configurations using the
         // modification list.
         for m in modifications {
-            m.apply(&mut plugins)?;
+            m.apply_to_any(&mut plugins)?;
         }
 
-        // Wrap the PluginProcessConfigurations in a box for the simulator
-        // configuration structure.
-        config.plugins = plugins
-            .into_iter()
-            .map(|plugin| Box::new(plugin) as Box<dyn PluginConfiguration>)
-            .collect();
+        config.plugins = plugins;
 
         Ok(self.host_calls.clone())
     }
@@ -246,3 +292,100 @@ impl Reproduction {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn reproduction(plugins: &str) -> Reproduction {
+        serde_yaml::from_str(&format!(
+            "seed: 0\nplugins:\n{}host_calls: []\nhostname: host\nusername: user\nworkdir: /\n",
+            plugins
+        ))
+        .unwrap()
+    }
+
+    fn to_run(reproduction: &Reproduction) -> Result<SimulatorConfiguration> {
+        let mut config = SimulatorConfiguration::default();
+        reproduction.to_run(&mut config, vec![], true)?;
+        Ok(config)
+    }
+
+    #[test]
+    fn process_plugins() {
+        let reproduction = reproduction(
+            "  - name: front\n    executable: /front\n    script: ~\n    init: []\n    env: []\n    work: .\n\
+             \x20 - name: back\n    executable: /back\n    script: /back.py\n    init: []\n    env: []\n    work: /\n",
+        );
+        assert_eq!(reproduction.plugins[0].executable, PathBuf::from("/front"));
+        assert_eq!(reproduction.plugins[0].attach, None);
+        assert_eq!(
+            reproduction.plugins[1].script,
+            Some(PathBuf::from("/back.py"))
+        );
+
+        let config = to_run(&reproduction).unwrap();
+        assert_eq!(config.plugins[0].get_type(), PluginType::Frontend);
+        assert_eq!(config.plugins[1].get_type(), PluginType::Backend);
+        assert_eq!(
+            config.plugins[1]
+                .get_reproduction(ReproductionPathStyle::Keep)
+                .unwrap(),
+            reproduction.plugins[1]
+        );
+
+        let yaml = serde_yaml::to_string(&reproduction).unwrap();
+        assert!(!yaml.contains("attach"));
+    }
+
+    #[test]
+    fn attached_plugins() {
+        let reproduction = reproduction(
+            "  - name: front\n    executable: /front\n    script: ~\n    init: []\n    env: []\n    work: .\n\
+             \x20 - name: back\n    script: ~\n    init: []\n    env: []\n    work: .\n    attach: tcp://:1234\n",
+        );
+        assert_eq!(
+            reproduction.plugins[1].attach,
+            Some("tcp://:1234".to_string())
+        );
+
+        let config = to_run(&reproduction).unwrap();
+        assert_eq!(config.plugins[1].get_type(), PluginType::Backend);
+        assert_eq!(
+            config.plugins[1]
+                .get_reproduction(ReproductionPathStyle::Keep)
+                .unwrap(),
+            reproduction.plugins[1]
+        );
+
+        let yaml = serde_yaml::to_string(&reproduction).unwrap();
+        assert_eq!(yaml.matches("executable").count(), 1);
+        assert_eq!(
+            serde_yaml::from_str::<Reproduction>(&yaml).unwrap(),
+            reproduction
+        );
+    }
+
+    #[test]
+    fn invalid_plugins() {
+        assert_eq!(
+            to_run(&reproduction(
+                "  - name: front\n    script: ~\n    init: []\n    env: []\n    work: .\n\
+                 \x20 - name: back\n    executable: /back\n    script: ~\n    init: []\n    env: []\n    work: .\n",
+            ))
+            .unwrap_err()
+            .to_string(),
+            "reproduction file corrupted: plugin front specifies neither an executable nor an attach address"
+        );
+        assert_eq!(
+            to_run(&reproduction(
+                "  - name: front\n    executable: /front\n    script: ~\n    init: []\n    env: []\n    work: .\n    attach: tcp://:1234\n\
+                 \x20 - name: back\n    executable: /back\n    script: ~\n    init: []\n    env: []\n    work: .\n",
+            ))
+            .unwrap_err()
+            .to_string(),
+            "reproduction file corrupted: plugin front specifies both an attach address and an executable or script"
+        );
+    }
+}
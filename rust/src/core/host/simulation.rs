@@ -7,7 +7,7 @@ use crate::{
         error::{err, inv_arg, inv_op, Result},
         log::thread::LogThread,
         protocol::{FrontendRunRequest, PluginToSimulator},
-        types::{ArbCmd, ArbData, PluginMetadata},
+        types::{ArbCmd, ArbData, PluginCapability, PluginMetadata, PluginType},
     },
     debug, error, fatal,
     host::{
@@ -32,6 +32,7 @@ pub type Pipeline = Vec<Box<dyn Plugin>>;
 struct InitializedPlugin {
     pub plugin: Box<dyn Plugin>,
     pub metadata: PluginMetadata,
+    pub capabilities: Vec<PluginCapability>,
 }
 
 /// Tracks the state of the simulated accelerator.
@@ -107,6 +108,36 @@ impl AcceleratorState {
     }
 }
 
+/// Checks that every plugin in the pipeline supports the capabilities of its
+/// upstream neighbour, skipping pairs of plugins for which either plugin does
+/// not declare any capabilities. Backends support conditional gates
+/// implicitly, since their conditions are resolved by DQCsim.
+fn check_capabilities(
+    pipeline: &[Box<dyn Plugin>],
+    capabilities: &[Vec<PluginCapability>],
+) -> Result<()> {
+    for (index, pair) in capabilities.windows(2).enumerate() {
+        let (upstream, downstream) = (&pair[0], &pair[1]);
+        if upstream.is_empty() || downstream.is_empty() {
+            continue;
+        }
+        let backend = pipeline[index + 1].plugin_type() == PluginType::Backend;
+        for capability in upstream {
+            let supported = downstream.contains(capability)
+                || (backend && *capability == PluginCapability::ConditionalGates);
+            if !supported {
+                err(format!(
+                    "plugin {} does not support {}, but upstream plugin {} does",
+                    pipeline[index + 1].name(),
+                    capability,
+                    pipeline[index].name()
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Simulation instance.
 #[derive(Debug)]
 pub struct Simulation {
@@ -153,6 +184,14 @@ impl Simulation {
             err("Failed to spawn plugin(s)")?
         }
 
+        // Exchange handshakes with the plugins, and make sure that they can
+        // work together before sending them anything else.
+        let capabilities = pipeline
+            .iter_mut()
+            .map(|plugin| plugin.handshake())
+            .collect::<Result<Vec<_>>>()?;
+        check_capabilities(&pipeline, &capabilities)?;
+
         // Initialize the plugins.
         let mut downstream = None;
         let mut metadata = vec![];
@@ -174,20 +213,26 @@ impl Simulation {
             plugin.user_initialize()?
         }
 
-        // Zip the plugin and metadata vectors together. Note that the metadata
-        // vector is reversed at this point!
+        // Zip the plugin, metadata, and capability vectors together. Note that
+        // the metadata vector is reversed at this point!
         let pipeline: Vec<_> = pipeline
             .into_iter()
             .zip(metadata.into_iter().rev())
-            .map(|(plugin, metadata)| InitializedPlugin { plugin, metadata })
+            .zip(capabilities)
+            .map(|((plugin, metadata), capabilities)| InitializedPlugin {
+                plugin,
+                metadata,
+                capabilities,
+            })
             .collect();
 
         for (i, p) in pipeline.iter().enumerate() {
             debug!(
-                "Plugin {} with instance name {} is {}",
+                "Plugin {} with instance name {} is {}, with capabilities {:?}",
                 i,
                 p.plugin.name(),
                 p.metadata,
+                p.capabilities,
             );
         }
 
@@ -363,6 +408,24 @@ impl Simulation {
         Ok(&self.pipeline[self.convert_plugin_index(index)?].metadata)
     }
 
+    /// Returns the optional protocol features supported by the plugin
+    /// referenced by instance name.
+    pub fn get_capabilities(&self, name: impl AsRef<str>) -> Result<&[PluginCapability]> {
+        let name = name.as_ref();
+        for (i, p) in self.pipeline.iter().enumerate() {
+            if p.plugin.name() == name {
+                return self.get_capabilities_idx(i as isize);
+            }
+        }
+        inv_arg(format!("plugin {} not found", name))
+    }
+
+    /// Returns the optional protocol features supported by the plugin
+    /// referenced by index.
+    pub fn get_capabilities_idx(&self, index: isize) -> Result<&[PluginCapability]> {
+        Ok(&self.pipeline[self.convert_plugin_index(index)?].capabilities)
+    }
+
     /// Writes a the reproduction log to a file.
     pub fn write_reproduction_file(&self, filename: impl AsRef<Path>) -> Result<()> {
         if let Some(log) = &self.reproduction_log {
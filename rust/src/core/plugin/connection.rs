@@ -20,13 +20,15 @@
 
 use crate::{
     common::{
-        channel::{PluginChannel, UpstreamChannel},
+        channel::PluginChannel,
         error::{inv_op, ErrorKind, Result},
         protocol::{GatestreamDown, GatestreamUp, PluginToSimulator, SimulatorToPlugin},
+        shared_memory::with_shared_memory,
+        transport::{self, OneShotServer, Transport},
     },
     trace,
 };
-use ipc_channel::ipc::{IpcOneShotServer, IpcReceiverSet, IpcSelectionResult, IpcSender};
+use ipc_channel::ipc::{IpcReceiverSet, IpcSelectionResult, IpcSender};
 use std::collections::{HashMap, VecDeque};
 
 /// Incoming enum used to map incoming requests in the IpcReceiverSet used in
@@ -89,8 +91,12 @@ pub struct Connection {
     /// Buffer for incoming requests.
     incoming_buffer: VecDeque<IncomingMessage>,
 
+    /// Transport used to serve the upstream connection. This matches the
+    /// transport used to connect to the Simulator.
+    transport: Transport,
+
     /// Pending upstream connection.
-    pending_upstream: Option<IpcOneShotServer<UpstreamChannel>>,
+    pending_upstream: Option<OneShotServer<GatestreamUp, GatestreamDown>>,
 
     /// Simulator response sender.
     response: IpcSender<PluginToSimulator>,
@@ -107,20 +113,15 @@ impl Connection {
     /// Attempts to connect to the provided simulator server address. Then
     /// continues to setup a request-response channel pair between the
     /// Simulator and the Plugin. The method returns the PluginChannel and
-    /// sends the SimulatorChannel to the Simulator.
-    fn connect(simulator: impl Into<String>) -> Result<PluginChannel> {
-        // Attempt to connect to the Simulator.
-        let connect = IpcSender::connect(simulator.into())?;
-
-        // Construct the Simulator and Plugin channel pair.
-        let (request_tx, request) = ipc_channel::ipc::channel()?;
-        let (response, response_rx) = ipc_channel::ipc::channel()?;
-
-        // Send channel to the simulator.
-        connect.send((request_tx, response_rx))?;
-
-        // Return the PluginChannel.
-        Ok((response, request))
+    /// the transport to use for serving the upstream plugin.
+    ///
+    /// The address may refer to an `ipc_channel` one-shot server, a TCP
+    /// server (`tcp://<host>:<port>`) or a Unix domain socket server
+    /// (`unix://<path>`). See the [`transport`] module.
+    ///
+    /// [`transport`]: ../../common/transport/index.html
+    fn connect(simulator: impl Into<String>) -> Result<(PluginChannel, Transport)> {
+        transport::connect(simulator)
     }
 
     /// Construct a Connection wrapper instance.
@@ -140,7 +141,7 @@ impl Connection {
     /// [`init`]: ./struct.Connection.html#method.initc
     pub fn new(simulator: impl Into<String>) -> Result<Connection> {
         // Attempt to connect to the simulator instance.
-        let channel = Connection::connect(simulator)?;
+        let (channel, transport) = Connection::connect(simulator)?;
 
         // Create incoming request collections.
         let mut incoming = IpcReceiverSet::new()?;
@@ -154,6 +155,7 @@ impl Connection {
             incoming_map,
             incoming_buffer: VecDeque::new(),
             response: channel.0,
+            transport,
             downstream: None,
             pending_upstream: None,
             upstream: None,
@@ -167,20 +169,13 @@ impl Connection {
         }
 
         // Attempt to connect to the downstream plugin.
-        let downstream = IpcSender::connect(downstream.into())?;
-
-        // Create channel pair.
-        let (down_tx, down_rx) = ipc_channel::ipc::channel()?;
-        let (up_tx, up_rx) = ipc_channel::ipc::channel()?;
-
-        // Send upstream channel to downstream plugin.
-        downstream.send((up_tx, down_rx) as UpstreamChannel)?;
+        let (downstream, _) = transport::connect::<GatestreamDown, GatestreamUp>(downstream)?;
 
         // Store downstream channel incoming and outgoing in connection
         // wrapper.
         self.incoming_map
-            .insert(self.incoming.add(up_rx)?, Incoming::Downstream);
-        self.downstream.replace(down_tx);
+            .insert(self.incoming.add(downstream.1)?, Incoming::Downstream);
+        self.downstream.replace(downstream.0);
 
         Ok(())
     }
@@ -193,7 +188,7 @@ impl Connection {
         } else if self.upstream.is_some() {
             inv_op("already connected to an upstream plugin")?;
         }
-        let (pending, address) = IpcOneShotServer::new()?;
+        let (pending, address) = OneShotServer::new(&self.transport)?;
         self.pending_upstream.replace(pending);
         Ok(address)
     }
@@ -208,7 +203,7 @@ impl Connection {
         }
 
         // Wait for upstream plugin to connect.
-        let (_, upstream): (_, UpstreamChannel) = self.pending_upstream.take().unwrap().accept()?;
+        let upstream = self.pending_upstream.take().unwrap().accept()?;
 
         // Store upstream channel incoming and outgoing in connection
         // wrapper.
@@ -246,9 +241,17 @@ impl Connection {
     /// failed.
     pub fn send(&self, message: OutgoingMessage) -> Result<()> {
         match message {
-            OutgoingMessage::Simulator(response) => self.response.send(response)?,
-            OutgoingMessage::Downstream(request) => self.downstream_ref()?.send(request)?,
-            OutgoingMessage::Upstream(response) => self.upstream_ref()?.send(response)?,
+            OutgoingMessage::Simulator(response) => {
+                with_shared_memory(|| self.response.send(response))?
+            }
+            OutgoingMessage::Downstream(request) => {
+                let downstream = self.downstream_ref()?;
+                with_shared_memory(|| downstream.send(request))?
+            }
+            OutgoingMessage::Upstream(response) => {
+                let upstream = self.upstream_ref()?;
+                with_shared_memory(|| upstream.send(response))?
+            }
         }
         Ok(())
     }
@@ -264,11 +267,34 @@ impl Connection {
                 match event {
                     IpcSelectionResult::MessageReceived(id, msg) => {
                         if let Some(incoming) = self.incoming_map.get(&id) {
-                            self.incoming_buffer.push_back(match incoming {
-                                Incoming::Simulator => IncomingMessage::Simulator(msg.to()?),
-                                Incoming::Upstream => IncomingMessage::Upstream(msg.to()?),
-                                Incoming::Downstream => IncomingMessage::Downstream(msg.to()?),
-                            });
+                            // Batched gatestream messages are unpacked here,
+                            // so they are indistinguishable from individually
+                            // sent messages to the rest of the plugin.
+                            match incoming {
+                                Incoming::Simulator => self
+                                    .incoming_buffer
+                                    .push_back(IncomingMessage::Simulator(msg.to()?)),
+                                Incoming::Upstream => match msg.to()? {
+                                    GatestreamDown::PipelinedBatch(batch) => self
+                                        .incoming_buffer
+                                        .extend(batch.into_iter().map(|(sequence, request)| {
+                                            IncomingMessage::Upstream(GatestreamDown::Pipelined(
+                                                sequence, request,
+                                            ))
+                                        })),
+                                    request => self
+                                        .incoming_buffer
+                                        .push_back(IncomingMessage::Upstream(request)),
+                                },
+                                Incoming::Downstream => match msg.to()? {
+                                    GatestreamUp::Batch(batch) => self
+                                        .incoming_buffer
+                                        .extend(batch.into_iter().map(IncomingMessage::Downstream)),
+                                    response => self
+                                        .incoming_buffer
+                                        .push_back(IncomingMessage::Downstream(response)),
+                                },
+                            }
                             received_any = true;
                         }
                     }
@@ -284,6 +310,12 @@ impl Connection {
         Ok(())
     }
 
+    /// Returns whether any received messages are buffered, i.e. whether
+    /// `next_request()` can return without blocking.
+    pub fn has_buffered_requests(&self) -> bool {
+        !self.incoming_buffer.is_empty()
+    }
+
     /// Fetch next request from either the Simulator request channel or the
     /// upstream Plugin request channel.
     ///
@@ -333,11 +365,21 @@ impl Drop for Connection {
 #[cfg(test)]
 mod tests {
     use super::{Connection, IncomingMessage, OutgoingMessage};
-    use crate::common::{
-        channel::SimulatorChannel,
-        protocol::{PluginToSimulator, SimulatorToPlugin},
+    use crate::{
+        common::{
+            channel::SimulatorChannel,
+            log::{LogRecord, Loglevel, LoglevelFilter},
+            protocol::{
+                GatestreamDown, GatestreamUp, Handshake, PipelinedGatestreamDown,
+                PluginInitializeRequest, PluginToSimulator, SimulatorToPlugin,
+            },
+            transport::{OneShotServer, Transport},
+            types::{PluginCapability, PluginType, SequenceNumber, SequenceNumberGenerator},
+        },
+        host::configuration::PluginLogConfiguration,
     };
-    use ipc_channel::ipc::IpcOneShotServer;
+    use ipc_channel::ipc::{self, IpcOneShotServer};
+    use std::{net::Ipv4Addr, sync::mpsc};
 
     #[test]
     fn connect() {
@@ -347,7 +389,7 @@ mod tests {
         // 'Plugin' runs in a thread.
         let plugin = std::thread::spawn(move || {
             // Get the PluginChannel
-            let channel = Connection::connect(server_name).unwrap();
+            let (channel, _) = Connection::connect(server_name).unwrap();
 
             // Wait for a request.
             let req = channel.1.recv();
@@ -412,6 +454,209 @@ mod tests {
         assert!(plugin.join().is_ok());
     }
 
+    /// Runs a simulator/plugin exchange including the handshake and the
+    /// initialization request over the given transport.
+    fn simulator_connection_over(transport: Transport) {
+        // Main thread runs the 'Simulator'.
+        let (server, server_name) =
+            OneShotServer::<SimulatorToPlugin, PluginToSimulator>::new(&transport).unwrap();
+
+        // The 'Plugin' runs in a thread.
+        let plugin = std::thread::spawn(move || {
+            let mut connection = Connection::new(server_name).unwrap();
+
+            // Exchange handshakes.
+            assert_eq!(
+                connection.next_request().unwrap().unwrap(),
+                IncomingMessage::Simulator(SimulatorToPlugin::Handshake(Handshake::new(&[])))
+            );
+            connection
+                .send(OutgoingMessage::Simulator(PluginToSimulator::Handshake(
+                    Handshake::new(&[PluginCapability::UnitaryGates]),
+                )))
+                .unwrap();
+
+            // Wait for the initialization request.
+            let req = connection.next_request().unwrap().unwrap();
+            let log_channel = match req {
+                IncomingMessage::Simulator(SimulatorToPlugin::Initialize(req)) => {
+                    assert_eq!(req.plugin_type, PluginType::Backend);
+                    assert_eq!(req.seed, 42);
+                    req.log_channel
+                }
+                _ => panic!("expected initialization request"),
+            };
+
+            // Log something through the log channel.
+            log_channel
+                .send(LogRecord::new(
+                    "plugin",
+                    "hello",
+                    Loglevel::Info,
+                    "module",
+                    "file",
+                    1,
+                    2,
+                    3,
+                ))
+                .unwrap();
+
+            // Send a response.
+            let res = connection.send(OutgoingMessage::Simulator(PluginToSimulator::Success));
+            assert!(res.is_ok());
+        });
+
+        // Simulator gets the SimulatorChannel.
+        let channel = server.accept().unwrap();
+
+        // Exchange handshakes.
+        channel.0.send(Handshake::new(&[]).into()).unwrap();
+        match channel.1.recv().unwrap() {
+            PluginToSimulator::Handshake(handshake) => assert_eq!(
+                handshake.get_capabilities(),
+                vec![PluginCapability::UnitaryGates]
+            ),
+            _ => panic!("expected handshake"),
+        }
+
+        // Send the initialization request.
+        let (log_tx, log_rx) = ipc::channel().unwrap();
+        let req = channel.0.send(
+            PluginInitializeRequest {
+                downstream: None,
+                plugin_type: PluginType::Backend,
+                seed: 42,
+                log_configuration: PluginLogConfiguration::new("plugin", LoglevelFilter::Info),
+                log_channel: log_tx,
+            }
+            .into(),
+        );
+        assert!(req.is_ok());
+
+        // Get the log record and the response.
+        assert_eq!(log_rx.recv().unwrap().payload(), "hello");
+        assert_eq!(channel.1.recv().unwrap(), PluginToSimulator::Success);
+
+        assert!(plugin.join().is_ok());
+    }
+
+    /// Connects two plugins over the given transport and sends a request and
+    /// response through the gatestream, followed by a batch of requests and
+    /// a batch of responses.
+    fn gatestream_over(transport: Transport) {
+        let (up_server, up_name) =
+            OneShotServer::<SimulatorToPlugin, PluginToSimulator>::new(&transport).unwrap();
+        let (down_server, down_name) =
+            OneShotServer::<SimulatorToPlugin, PluginToSimulator>::new(&transport).unwrap();
+        let (address_tx, address_rx) = mpsc::channel();
+
+        // The downstream 'Plugin' serves the upstream connection.
+        let downstream = std::thread::spawn(move || {
+            let mut connection = Connection::new(down_name).unwrap();
+            address_tx
+                .send(connection.serve_upstream().unwrap())
+                .unwrap();
+            connection.accept_upstream().unwrap();
+
+            let req = connection.next_request().unwrap().unwrap();
+            assert_eq!(
+                req,
+                IncomingMessage::Upstream(GatestreamDown::Pipelined(
+                    SequenceNumber::none(),
+                    PipelinedGatestreamDown::Advance(3)
+                ))
+            );
+            connection
+                .send(OutgoingMessage::Upstream(GatestreamUp::Advanced(3)))
+                .unwrap();
+
+            // Batches are unpacked into separate messages.
+            let mut sequence = SequenceNumberGenerator::new();
+            for cycles in 1..=2 {
+                let req = connection.next_request().unwrap().unwrap();
+                assert_eq!(
+                    req,
+                    IncomingMessage::Upstream(GatestreamDown::Pipelined(
+                        sequence.get_next(),
+                        PipelinedGatestreamDown::Advance(cycles)
+                    ))
+                );
+                assert_eq!(connection.has_buffered_requests(), cycles == 1);
+            }
+            connection
+                .send(OutgoingMessage::Upstream(GatestreamUp::Batch(vec![
+                    GatestreamUp::Advanced(1),
+                    GatestreamUp::Advanced(2),
+                    GatestreamUp::CompletedUpTo(sequence.get_previous()),
+                ])))
+                .unwrap();
+        });
+
+        // The upstream 'Plugin' connects to it.
+        let upstream = std::thread::spawn(move || {
+            let mut connection = Connection::new(up_name).unwrap();
+            connection
+                .connect_downstream(address_rx.recv().unwrap())
+                .unwrap();
+
+            connection
+                .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
+                    SequenceNumber::none(),
+                    PipelinedGatestreamDown::Advance(3),
+                )))
+                .unwrap();
+            let res = connection.next_downstream_request().unwrap().unwrap();
+            assert_eq!(res, IncomingMessage::Downstream(GatestreamUp::Advanced(3)));
+
+            let mut sequence = SequenceNumberGenerator::new();
+            connection
+                .send(OutgoingMessage::Downstream(GatestreamDown::PipelinedBatch(
+                    vec![
+                        (sequence.get_next(), PipelinedGatestreamDown::Advance(1)),
+                        (sequence.get_next(), PipelinedGatestreamDown::Advance(2)),
+                    ],
+                )))
+                .unwrap();
+            for expected in [
+                GatestreamUp::Advanced(1),
+                GatestreamUp::Advanced(2),
+                GatestreamUp::CompletedUpTo(sequence.get_previous()),
+            ] {
+                let res = connection.next_downstream_request().unwrap().unwrap();
+                assert_eq!(res, IncomingMessage::Downstream(expected));
+            }
+        });
+
+        // Keep the simulator channels alive until the plugins are done.
+        let _up = up_server.accept().unwrap();
+        let _down = down_server.accept().unwrap();
+
+        assert!(downstream.join().is_ok());
+        assert!(upstream.join().is_ok());
+    }
+
+    #[test]
+    fn tcp_simulator_connection() {
+        simulator_connection_over(Transport::Tcp(Ipv4Addr::LOCALHOST.into()));
+    }
+
+    #[test]
+    fn tcp_gatestream() {
+        gatestream_over(Transport::Tcp(Ipv4Addr::LOCALHOST.into()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_simulator_connection() {
+        simulator_connection_over(Transport::Unix);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_gatestream() {
+        gatestream_over(Transport::Unix);
+    }
+
     #[test]
     fn bad_address() {
         // Attempt to connect to an non-existing server
@@ -5,12 +5,56 @@ use crate::{
     common::{
         error::{inv_op, Result},
         types::{
-            ArbCmd, ArbData, Gate, PluginMetadata, PluginType, QubitMeasurementResult, QubitRef,
+            ArbCmd, ArbData, Gate, PluginCapability, PluginMetadata, PluginType,
+            QubitMeasurementResult, QubitRef,
         },
     },
     plugin::state::PluginState,
 };
-use std::fmt;
+use std::{fmt, time::Duration};
+
+/// Policy for batching pipelined gatestream messages.
+///
+/// Pipelined messages, i.e. allocations, frees, gates, and advances sent
+/// downstream and the responses to them sent upstream, are queued up and sent
+/// as a single batch to reduce IPC overhead. A batch is sent when it reaches
+/// the maximum size, when the plugin needs to wait for another plugin or the
+/// host (for instance to get a measurement result, or because it has nothing
+/// else to do), or when its oldest message is found to exceed the maximum
+/// age.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BatchPolicy {
+    /// Maximum number of messages in a batch. Setting this to one disables
+    /// batching.
+    pub max_size: usize,
+
+    /// Maximum age of the oldest message in a batch.
+    ///
+    /// The age is checked whenever a message is queued and before each
+    /// incoming request is handled; there is no timer. Because a batch is
+    /// always sent before the plugin blocks, this bounds the delay unless a
+    /// single callback runs for longer than this without queuing anything.
+    pub max_age: Duration,
+}
+
+impl Default for BatchPolicy {
+    fn default() -> BatchPolicy {
+        BatchPolicy {
+            max_size: 256,
+            max_age: Duration::from_millis(10),
+        }
+    }
+}
+
+impl BatchPolicy {
+    /// Returns a policy that sends every message immediately.
+    pub fn disabled() -> BatchPolicy {
+        BatchPolicy {
+            max_size: 1,
+            max_age: Duration::from_secs(0),
+        }
+    }
+}
 
 /// Defines a plugin.
 ///
@@ -26,6 +70,12 @@ pub struct PluginDefinition {
     /// Name, author, and version of the plugin.
     metadata: PluginMetadata,
 
+    /// Optional protocol features supported by the plugin.
+    capabilities: Vec<PluginCapability>,
+
+    /// Policy for batching pipelined gatestream messages.
+    batch_policy: BatchPolicy,
+
     /// Initialization callback.
     pub initialize: Box<dyn Fn(&mut PluginState, Vec<ArbCmd>) -> Result<()> + Send + 'static>,
 
@@ -86,6 +136,8 @@ impl PluginDefinition {
             PluginType::Frontend => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                capabilities: vec![],
+                batch_policy: BatchPolicy::default(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("run() is not implemented")),
@@ -100,6 +152,8 @@ impl PluginDefinition {
             PluginType::Operator => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                capabilities: vec![],
+                batch_policy: BatchPolicy::default(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("operator.run() called")),
@@ -116,6 +170,8 @@ impl PluginDefinition {
             PluginType::Backend => PluginDefinition {
                 typ,
                 metadata: metadata.into(),
+                capabilities: vec![],
+                batch_policy: BatchPolicy::default(),
                 initialize: Box::new(|_, _| Ok(())),
                 drop: Box::new(|_| Ok(())),
                 run: Box::new(|_, _| inv_op("backend.run() called")),
@@ -144,6 +200,29 @@ impl PluginDefinition {
     pub fn get_metadata(&self) -> &PluginMetadata {
         &self.metadata
     }
+
+    /// Returns the optional protocol features supported by the plugin.
+    pub fn get_capabilities(&self) -> &[PluginCapability] {
+        &self.capabilities
+    }
+
+    /// Sets the optional protocol features supported by the plugin. No
+    /// capabilities are declared by default, in which case DQCsim does not
+    /// check whether the plugin is compatible with its neighbours. See
+    /// `PluginCapability` for more information.
+    pub fn set_capabilities(&mut self, capabilities: impl IntoIterator<Item = PluginCapability>) {
+        self.capabilities = capabilities.into_iter().collect();
+    }
+
+    /// Returns the policy for batching pipelined gatestream messages.
+    pub fn get_batch_policy(&self) -> BatchPolicy {
+        self.batch_policy
+    }
+
+    /// Sets the policy for batching pipelined gatestream messages.
+    pub fn set_batch_policy(&mut self, batch_policy: BatchPolicy) {
+        self.batch_policy = batch_policy;
+    }
 }
 
 #[cfg(test)]
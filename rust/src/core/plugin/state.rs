@@ -4,9 +4,9 @@ use crate::{
     common::{
         error::{err, inv_arg, inv_op, oe_err, Result},
         protocol::{
-            FrontendRunRequest, FrontendRunResponse, GatestreamDown, GatestreamUp,
+            FrontendRunRequest, FrontendRunResponse, GatestreamDown, GatestreamUp, Handshake,
             PipelinedGatestreamDown, PluginInitializeRequest, PluginInitializeResponse,
-            PluginToSimulator, SimulatorToPlugin,
+            PluginToSimulator, SimulatorToPlugin, PROTOCOL_VERSION,
         },
         types::{
             ArbCmd, ArbData, Cycle, Cycles, Gate, PluginType, QubitMeasurementResult,
@@ -28,7 +28,10 @@ use rand_chacha::{
     rand_core::{RngCore, SeedableRng},
     ChaChaRng,
 };
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Instant,
+};
 
 /// Deterministic random number generator used for plugins.
 ///
@@ -173,6 +176,10 @@ pub struct PluginState<'a> {
     /// `CompletedUpTo` message.
     upstream_completed_up_to: SequenceNumber,
 
+    /// The latest measurement values that we reported upstream for each
+    /// upstream qubit, used to resolve the conditions of conditional gates.
+    upstream_measurements: HashMap<QubitRef, QubitMeasurementValue>,
+
     /// Downstream sequence number generator.
     downstream_sequence_tx: SequenceNumberGenerator,
 
@@ -205,11 +212,49 @@ pub struct PluginState<'a> {
     /// we sent downstream.
     downstream_expected_measurements: VecDeque<(SequenceNumber, HashSet<QubitRef>)>,
 
+    /// Pipelined requests queued up to be sent downstream as a batch.
+    downstream_batch: Vec<(SequenceNumber, PipelinedGatestreamDown)>,
+
+    /// The time at which the oldest request in `downstream_batch` was queued.
+    downstream_batch_started: Instant,
+
+    /// Responses to pipelined requests queued up to be sent upstream as a
+    /// batch.
+    upstream_batch: Vec<GatestreamUp>,
+
+    /// The time at which the oldest response in `upstream_batch` was queued.
+    upstream_batch_started: Instant,
+
     /// Aborted flag indicates if the plugin received the aborted signal.
     aborted: bool,
 }
 
 impl<'a> PluginState<'a> {
+    /// Waits for the handshake from the simulator and responds with our own.
+    ///
+    /// The handshake is decoded correctly regardless of the protocol version
+    /// of the simulator. An error is returned if the versions differ, without
+    /// decoding any further messages. Returns whether the simulator is still
+    /// connected.
+    fn handle_handshake(&mut self) -> Result<bool> {
+        let handshake = match self.connection.next_request()? {
+            Some(IncomingMessage::Simulator(SimulatorToPlugin::Handshake(handshake))) => handshake,
+            Some(_) => return err("Protocol error: expected a handshake from the simulator"),
+            None => return Ok(false),
+        };
+        self.connection
+            .send(OutgoingMessage::Simulator(PluginToSimulator::Handshake(
+                Handshake::new(self.definition.get_capabilities()),
+            )))?;
+        if handshake.protocol_version != PROTOCOL_VERSION {
+            err(format!(
+                "simulator uses protocol version {}, but this plugin uses protocol version {}",
+                handshake.protocol_version, PROTOCOL_VERSION
+            ))?;
+        }
+        Ok(true)
+    }
+
     /// Handles a SimulatorToPlugin::Initialize RPC.
     fn handle_init(&mut self, req: PluginInitializeRequest) -> Result<PluginInitializeResponse> {
         let typ = self.definition.get_type();
@@ -357,10 +402,7 @@ impl<'a> PluginState<'a> {
             if self.definition.get_type() == PluginType::Operator {
                 let measurements = (self.definition.modify_measurement)(self, measurement)?;
                 for measurement in measurements {
-                    self.connection
-                        .send(OutgoingMessage::Upstream(GatestreamUp::Measured(
-                            measurement,
-                        )))?;
+                    self.queue_upstream(GatestreamUp::Measured(measurement))?;
                 }
             }
         } else {
@@ -505,10 +547,7 @@ impl<'a> PluginState<'a> {
             if acknowledged {
                 let (_, _, postponed_measurements) = self.upstream_postponed.pop_front().unwrap();
                 for postponed_measurement in postponed_measurements {
-                    self.connection
-                        .send(OutgoingMessage::Upstream(GatestreamUp::Measured(
-                            postponed_measurement,
-                        )))?;
+                    self.queue_upstream(GatestreamUp::Measured(postponed_measurement))?;
                 }
             } else {
                 break;
@@ -530,10 +569,7 @@ impl<'a> PluginState<'a> {
         // acknowledge.
         if completed_up_to.after(self.upstream_completed_up_to) {
             trace!("We've completed up to {}", completed_up_to);
-            self.connection
-                .send(OutgoingMessage::Upstream(GatestreamUp::CompletedUpTo(
-                    completed_up_to,
-                )))?;
+            self.queue_upstream(GatestreamUp::CompletedUpTo(completed_up_to))?;
             self.upstream_completed_up_to = completed_up_to;
         }
         Ok(())
@@ -595,6 +631,12 @@ impl<'a> PluginState<'a> {
                     trace!("Received a request from the host");
 
                     let response = OutgoingMessage::Simulator(match message {
+                        SimulatorToPlugin::Handshake(_) => {
+                            let e = "Protocol error: unexpected handshake from the simulator"
+                                .to_string();
+                            error!("{}", e);
+                            PluginToSimulator::Failure(e)
+                        }
                         SimulatorToPlugin::Initialize(req) => match self.handle_init(*req) {
                             Ok(x) => PluginToSimulator::Initialized(x),
                             Err(e) => {
@@ -679,9 +721,36 @@ impl<'a> PluginState<'a> {
                         }
                         PipelinedGatestreamDown::Free(qubits) => {
                             self.upstream_qubit_ref_generator.free(qubits.clone());
+                            for qubit in qubits.iter() {
+                                self.upstream_measurements.remove(qubit);
+                            }
                             (self.definition.free)(self, qubits)
                         }
+                        PipelinedGatestreamDown::Gate(gate)
+                            if gate.is_conditional()
+                                && self.definition.get_type() == PluginType::Backend
+                                && !self.evaluate_condition(&gate) =>
+                        {
+                            // Backends resolve the conditions of conditional
+                            // gates using the measurement results they
+                            // reported. When the condition is false, the gate
+                            // is not executed, and the qubits it measures get
+                            // an undefined result.
+                            trace!("Condition of gate {} is false; not executing it", sequence);
+                            queued_measurements.extend(gate.undefined_measurements());
+                            Ok(())
+                        }
                         PipelinedGatestreamDown::Gate(gate) => {
+                            // Conditional gates that reach this point for a
+                            // backend are executed, so the backend sees them
+                            // without their condition. Operators pass
+                            // conditional gates on to the user's callback as
+                            // they are.
+                            let gate = if self.definition.get_type() == PluginType::Backend {
+                                gate.without_condition()
+                            } else {
+                                gate
+                            };
                             let mut measures: HashSet<_> =
                                 gate.get_measures().iter().cloned().collect();
                             (self.definition.gate)(self, gate).and_then(|measurements| {
@@ -714,8 +783,7 @@ impl<'a> PluginState<'a> {
                         })
                         }
                         PipelinedGatestreamDown::Advance(cycles) => self
-                            .connection
-                            .send(OutgoingMessage::Upstream(GatestreamUp::Advanced(cycles)))
+                            .queue_upstream(GatestreamUp::Advanced(cycles))
                             .and_then(|_| (self.definition.advance)(self, cycles)),
                     };
 
@@ -723,10 +791,7 @@ impl<'a> PluginState<'a> {
                     if let Err(e) = response {
                         let e = e.to_string();
                         error!("{}", e);
-                        self.connection
-                            .send(OutgoingMessage::Upstream(GatestreamUp::Failure(
-                                sequence, e,
-                            )))?;
+                        self.queue_upstream(GatestreamUp::Failure(sequence, e))?;
                     }
 
                     // Save that we've completed the downstream handling of the
@@ -751,9 +816,7 @@ impl<'a> PluginState<'a> {
                         );
                     } else {
                         for measurement in queued_measurements {
-                            self.connection.send(OutgoingMessage::Upstream(
-                                GatestreamUp::Measured(measurement),
-                            ))?;
+                            self.queue_upstream(GatestreamUp::Measured(measurement))?;
                         }
                     }
 
@@ -772,8 +835,12 @@ impl<'a> PluginState<'a> {
                         Ok(r) => GatestreamUp::ArbSuccess(r),
                         Err(e) => GatestreamUp::ArbFailure(e.to_string()),
                     };
+                    self.flush_upstream()?;
                     self.connection.send(OutgoingMessage::Upstream(response))?;
                 }
+                IncomingMessage::Upstream(GatestreamDown::PipelinedBatch(_)) => {
+                    panic!("Connection returned a batch of pipelined requests")
+                }
                 IncomingMessage::Downstream(message) => self.handle_downstream_message(message)?,
             }
         }
@@ -784,6 +851,7 @@ impl<'a> PluginState<'a> {
     /// Helper function for synchronize_downstream_up_to(). Do not call this
     /// directly.
     fn _synchronize_downstream_up_to(&mut self, num: SequenceNumber) -> Result<()> {
+        self.flush()?;
         while num.after(self.downstream_sequence_rx) {
             match self.connection.next_downstream_request()? {
                 Some(IncomingMessage::Downstream(message)) => {
@@ -826,6 +894,111 @@ impl<'a> PluginState<'a> {
         self.synchronize_downstream_up_to(self.downstream_sequence_tx.get_previous())
     }
 
+    /// Queues a pipelined request for the downstream plugin, sending the batch
+    /// if the batch policy says so. Returns the sequence number assigned to
+    /// the request.
+    fn queue_downstream(&mut self, request: PipelinedGatestreamDown) -> Result<SequenceNumber> {
+        let sequence = self.downstream_sequence_tx.get_next();
+        if self.downstream_batch.is_empty() {
+            self.downstream_batch_started = Instant::now();
+        }
+        self.downstream_batch.push((sequence, request));
+        if self.downstream_batch.len() >= self.definition.get_batch_policy().max_size {
+            self.flush_downstream()?;
+        } else {
+            self.flush_expired()?;
+        }
+        Ok(sequence)
+    }
+
+    /// Sends the queued pipelined requests to the downstream plugin.
+    fn flush_downstream(&mut self) -> Result<()> {
+        let message = match self.downstream_batch.len() {
+            0 => return Ok(()),
+            1 => {
+                let (sequence, request) = self.downstream_batch.pop().unwrap();
+                GatestreamDown::Pipelined(sequence, request)
+            }
+            _ => GatestreamDown::PipelinedBatch(std::mem::take(&mut self.downstream_batch)),
+        };
+        trace!("Flushing requests to downstream");
+        self.connection.send(OutgoingMessage::Downstream(message))
+    }
+
+    /// Queues a response to a pipelined request for the upstream plugin,
+    /// sending the batch if the batch policy says so.
+    fn queue_upstream(&mut self, response: GatestreamUp) -> Result<()> {
+        if let GatestreamUp::Measured(measurement) = &response {
+            self.upstream_measurements
+                .insert(measurement.qubit, measurement.value);
+        }
+        if self.upstream_batch.is_empty() {
+            self.upstream_batch_started = Instant::now();
+        }
+        self.upstream_batch.push(response);
+        if self.upstream_batch.len() >= self.definition.get_batch_policy().max_size {
+            self.flush_upstream()?;
+        } else {
+            self.flush_expired()?;
+        }
+        Ok(())
+    }
+
+    /// Sends the queued pipelined responses to the upstream plugin.
+    fn flush_upstream(&mut self) -> Result<()> {
+        let message = match self.upstream_batch.len() {
+            0 => return Ok(()),
+            1 => self.upstream_batch.pop().unwrap(),
+            _ => GatestreamUp::Batch(std::mem::take(&mut self.upstream_batch)),
+        };
+        trace!("Flushing responses to upstream");
+        self.connection.send(OutgoingMessage::Upstream(message))
+    }
+
+    /// Sends all queued pipelined messages. This must be done whenever we're
+    /// about to wait for another plugin or the host, as they may in turn be
+    /// waiting for the messages we're holding back.
+    fn flush(&mut self) -> Result<()> {
+        self.flush_downstream()?;
+        self.flush_upstream()
+    }
+
+    /// Sends the queued pipelined messages of the batches of which the oldest
+    /// message exceeds the maximum age of the batch policy.
+    fn flush_expired(&mut self) -> Result<()> {
+        let max_age = self.definition.get_batch_policy().max_age;
+        if !self.downstream_batch.is_empty() && self.downstream_batch_started.elapsed() >= max_age {
+            self.flush_downstream()?;
+        }
+        if !self.upstream_batch.is_empty() && self.upstream_batch_started.elapsed() >= max_age {
+            self.flush_upstream()?;
+        }
+        Ok(())
+    }
+
+    /// Sends the queued pipelined messages before the next incoming request
+    /// is fetched. If no requests are buffered, fetching the next one blocks,
+    /// and the other plugins may be waiting for the messages we're holding
+    /// back, so everything is sent. Otherwise, only expired batches are sent.
+    fn flush_before_request(&mut self) -> Result<()> {
+        if self.connection.has_buffered_requests() {
+            self.flush_expired()
+        } else {
+            self.flush()
+        }
+    }
+
+    /// Evaluates the condition of a gate received from upstream, using the
+    /// latest measurement results that we reported upstream.
+    fn evaluate_condition(&self, gate: &Gate) -> bool {
+        gate.evaluate_condition(|qubit| {
+            self.upstream_measurements
+                .get(&qubit)
+                .cloned()
+                .unwrap_or(QubitMeasurementValue::Undefined)
+        })
+    }
+
     /// Checks that the qubit references in the specified iterator are all
     /// currently valid.
     fn check_qubits_live<'b, 'c>(
@@ -866,15 +1039,30 @@ impl<'a> PluginState<'a> {
             upstream_issued_up_to: SequenceNumber::none(),
             upstream_postponed: VecDeque::new(),
             upstream_completed_up_to: SequenceNumber::none(),
+            upstream_measurements: HashMap::new(),
             downstream_qubit_data: HashMap::new(),
             downstream_measurement_queue: VecDeque::new(),
             downstream_expected_measurements: VecDeque::new(),
+            downstream_batch: vec![],
+            downstream_batch_started: Instant::now(),
+            upstream_batch: vec![],
+            upstream_batch_started: Instant::now(),
             aborted: false,
         };
 
-        while let Some(request) = state.connection.next_request()? {
-            if state.handle_incoming_message(request)? {
-                break;
+        if !state.handle_handshake()? {
+            return Ok(());
+        }
+
+        loop {
+            state.flush_before_request()?;
+            match state.connection.next_request()? {
+                Some(request) => {
+                    if state.handle_incoming_message(request)? {
+                        break;
+                    }
+                }
+                None => break,
             }
         }
         Ok(())
@@ -922,6 +1110,8 @@ impl<'a> PluginState<'a> {
             // messages break out of it so the response is sent by the above
             // code.
             while self.host_to_frontend_data.is_empty() {
+                self.flush_before_request()?;
+
                 // Fetch the next message.
                 let request = self
                     .connection
@@ -985,11 +1175,7 @@ impl<'a> PluginState<'a> {
         }
 
         // Send the allocate message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Allocate(num_qubits, commands),
-            )))?;
+        self.queue_downstream(PipelinedGatestreamDown::Allocate(num_qubits, commands))?;
 
         // Return the references to the qubits.
         Ok(qubits)
@@ -1008,11 +1194,7 @@ impl<'a> PluginState<'a> {
         self.check_qubits_live(qubits.iter())?;
 
         // Send the free message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Free(qubits.clone()),
-            )))?;
+        self.queue_downstream(PipelinedGatestreamDown::Free(qubits.clone()))?;
 
         // Kill our classical storage for the qubits.
         for qubit in qubits.iter() {
@@ -1038,17 +1220,13 @@ impl<'a> PluginState<'a> {
         self.check_qubits_live(gate.get_targets())?;
         self.check_qubits_live(gate.get_controls())?;
         self.check_qubits_live(gate.get_measures())?;
+        self.check_qubits_live(gate.get_condition().iter().map(|(qubit, _)| qubit))?;
 
         // Store which qubits we're expecting to be measured.
         let measures: HashSet<_> = gate.get_measures().iter().cloned().collect();
 
         // Send the gate message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Gate(gate),
-            )))?;
-        let sequence = self.downstream_sequence_tx.get_previous();
+        let sequence = self.queue_downstream(PipelinedGatestreamDown::Gate(gate))?;
 
         // Update the last-mutation sequence number for the measured qubits.
         for measure in measures.iter() {
@@ -1106,6 +1284,48 @@ impl<'a> PluginState<'a> {
         }
     }
 
+    /// Resolves the condition of a gate received from upstream, returning
+    /// whether the gate should be executed. Unconditional gates are always
+    /// executed.
+    ///
+    /// Conditional gates are normally passed on to the backend, which
+    /// resolves them using the measurement results it returned. Operators
+    /// that modify measurement results in `modify_measurement()` must resolve
+    /// the conditions themselves, using this function, and only send the
+    /// gates that are executed downstream, without their condition.
+    /// Otherwise, the backend would resolve the conditions using the
+    /// unmodified results. The condition is evaluated using the measurement
+    /// results that this operator reported upstream, so this needs to wait
+    /// for the downstream plugins to return any measurement results that are
+    /// still outstanding.
+    ///
+    /// When the condition is false, the `gate()` callback must return an
+    /// undefined measurement result for each qubit measured by the gate, as
+    /// the backend does for conditional gates that are not executed. See
+    /// `Gate::undefined_measurements()`.
+    ///
+    /// Only operator plugins are allowed to call this. Doing so in other
+    /// plugins will result in an `Err` return value.
+    pub fn resolve_condition(&mut self, gate: &Gate) -> Result<bool> {
+        if self.definition.get_type() != PluginType::Operator {
+            return inv_op("resolve_condition() is only available for operators")?;
+        } else if !self.synchronized_to_rpcs {
+            return inv_op(
+                "resolve_condition() cannot be called while handling a gatestream response",
+            )?;
+        }
+        if !gate.is_conditional() {
+            return Ok(true);
+        }
+
+        // Measurement results of earlier requests are postponed until they
+        // are returned by the downstream plugins.
+        if !self.upstream_postponed.is_empty() {
+            self.synchronize_downstream()?;
+        }
+        Ok(self.evaluate_condition(gate))
+    }
+
     /// Returns the number of downstream cycles since the latest measurement
     /// of the given downstream qubit.
     ///
@@ -1197,11 +1417,7 @@ impl<'a> PluginState<'a> {
         self.downstream_cycle_tx = self.downstream_cycle_tx.advance(cycles);
 
         // Send the advance message.
-        self.connection
-            .send(OutgoingMessage::Downstream(GatestreamDown::Pipelined(
-                self.downstream_sequence_tx.get_next(),
-                PipelinedGatestreamDown::Advance(cycles),
-            )))?;
+        self.queue_downstream(PipelinedGatestreamDown::Advance(cycles))?;
 
         // Return the current simulation time.
         Ok(self.downstream_cycle_tx)
@@ -2,26 +2,36 @@ use dqcsim::{
     common::{
         error::err,
         log::{thread::LogThread, LoglevelFilter},
+        protocol::{Handshake, PROTOCOL_VERSION},
+        shared_memory::SHARED_MEMORY_THRESHOLD,
         types::{
-            ArbCmd, ArbData, Gate, Matrix, PluginMetadata, PluginType, QubitMeasurementResult,
-            QubitMeasurementValue, QubitRef,
+            ArbCmd, ArbData, Gate, Matrix, PluginCapability, PluginMetadata, PluginType,
+            QubitMeasurementResult, QubitMeasurementValue, QubitRef,
         },
     },
     host::{
         accelerator::Accelerator,
         configuration::{
-            PluginLogConfiguration, PluginThreadConfiguration, Seed, SimulatorConfiguration,
+            PluginAttachConfiguration, PluginLogConfiguration, PluginThreadConfiguration,
+            ReproductionPathStyle, Seed, SimulatorConfiguration, Timeout,
         },
         plugin::Plugin,
+        reproduction::{PluginModification, Reproduction},
         simulation::Simulation,
         simulator::Simulator,
     },
-    plugin::definition::PluginDefinition,
+    plugin::{
+        definition::{BatchPolicy, PluginDefinition},
+        state::PluginState,
+    },
 };
+use ipc_channel::ipc::{self, IpcOneShotServer, IpcReceiver, IpcSender};
 use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Instant,
 };
 
 pub fn fe_op_be() -> (PluginDefinition, PluginDefinition, PluginDefinition) {
@@ -435,6 +445,210 @@ fn simulation_metadata() {
     assert!(metadata.is_err());
 }
 
+#[test]
+fn simulation_capabilities() {
+    let mut backend = PluginDefinition::new(PluginType::Backend, PluginMetadata::new("", "", ""));
+    backend.set_capabilities(vec![
+        PluginCapability::UnitaryGates,
+        PluginCapability::MeasurementGates,
+    ]);
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(PluginThreadConfiguration::new(
+            backend,
+            PluginLogConfiguration::new("backend", LoglevelFilter::Off),
+        ))
+        .with_plugin(thread_config_type(PluginType::Frontend));
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_ok());
+    let simulation = &mut simulator.unwrap().simulation;
+
+    assert_eq!(
+        simulation.get_capabilities("backend").unwrap(),
+        &[
+            PluginCapability::UnitaryGates,
+            PluginCapability::MeasurementGates
+        ]
+    );
+    assert!(simulation.get_capabilities_idx(0).unwrap().is_empty());
+    assert!(simulation.get_capabilities("asdf").is_err());
+}
+
+fn capability_config(
+    plugin_type: PluginType,
+    name: &str,
+    capabilities: Vec<PluginCapability>,
+) -> PluginThreadConfiguration {
+    let mut definition = PluginDefinition::new(plugin_type, PluginMetadata::new("", "", ""));
+    definition.set_capabilities(capabilities);
+    PluginThreadConfiguration::new(
+        definition,
+        PluginLogConfiguration::new(name, LoglevelFilter::Off),
+    )
+}
+
+#[test]
+fn simulation_capability_mismatch() {
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(capability_config(
+            PluginType::Frontend,
+            "frontend",
+            vec![
+                PluginCapability::UnitaryGates,
+                PluginCapability::CustomGates,
+            ],
+        ))
+        .with_plugin(capability_config(
+            PluginType::Backend,
+            "backend",
+            vec![PluginCapability::UnitaryGates],
+        ));
+
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_err());
+    assert_eq!(
+        simulator.unwrap_err().to_string(),
+        "plugin backend does not support custom gates, but upstream plugin frontend does"
+    );
+}
+
+#[test]
+fn simulation_capability_conditional_backend() {
+    // Conditions are resolved by DQCsim before gates reach the backend.
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(capability_config(
+            PluginType::Frontend,
+            "frontend",
+            vec![
+                PluginCapability::UnitaryGates,
+                PluginCapability::ConditionalGates,
+            ],
+        ))
+        .with_plugin(capability_config(
+            PluginType::Backend,
+            "backend",
+            vec![PluginCapability::UnitaryGates],
+        ));
+
+    assert!(Simulator::new(configuration).is_ok());
+}
+
+// Simulator-to-plugin messages of a hypothetical later version of DQCsim, in
+// which everything but the handshake has changed.
+#[derive(Debug, Serialize, Deserialize)]
+enum LaterSimulatorToPlugin {
+    Handshake(Handshake),
+    Initialize {
+        downstream: Vec<String>,
+        seed: [u8; 32],
+    },
+}
+
+// Plugin-to-simulator messages of the same hypothetical later version.
+#[derive(Debug, Serialize, Deserialize)]
+enum LaterPluginToSimulator {
+    Handshake(Handshake),
+    Initialized { upstream: Vec<String> },
+}
+
+#[test]
+fn protocol_version_mismatch_plugin() {
+    // This backend has been built against a later version of DQCsim.
+    let backend = PluginThreadConfiguration::new_raw(
+        Box::new(|server| {
+            let server = IpcSender::connect(server).unwrap();
+            let (tx, peer_rx) = ipc::channel::<LaterPluginToSimulator>().unwrap();
+            let (peer_tx, rx) = ipc::channel::<LaterSimulatorToPlugin>().unwrap();
+            server.send((peer_tx, peer_rx)).unwrap();
+            match rx.recv().unwrap() {
+                LaterSimulatorToPlugin::Handshake(handshake) => {
+                    assert_eq!(handshake.protocol_version, PROTOCOL_VERSION);
+                }
+                x => panic!("unexpected message {:?}", x),
+            }
+            tx.send(LaterPluginToSimulator::Handshake(Handshake {
+                protocol_version: PROTOCOL_VERSION + 1,
+                capabilities: 0,
+            }))
+            .unwrap();
+        }),
+        PluginType::Backend,
+        PluginLogConfiguration::new("backend", LoglevelFilter::Off),
+    );
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(thread_config_type(PluginType::Frontend))
+        .with_plugin(backend);
+
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_err());
+    assert_eq!(
+        simulator.unwrap_err().to_string(),
+        format!(
+            "plugin backend uses protocol version {}, but the simulator uses protocol version {}",
+            PROTOCOL_VERSION + 1,
+            PROTOCOL_VERSION
+        )
+    );
+}
+
+#[test]
+fn protocol_version_mismatch_simulator() {
+    // This simulator has been built against a later version of DQCsim.
+    let (server, server_name) = IpcOneShotServer::<(
+        IpcSender<LaterSimulatorToPlugin>,
+        IpcReceiver<LaterPluginToSimulator>,
+    )>::new()
+    .unwrap();
+
+    let plugin = std::thread::spawn(move || {
+        let mut definition =
+            PluginDefinition::new(PluginType::Backend, PluginMetadata::new("", "", ""));
+        definition.set_capabilities(vec![PluginCapability::UnitaryGates]);
+        PluginState::run(&definition, server_name)
+    });
+
+    let (_, (tx, rx)) = server.accept().unwrap();
+    tx.send(LaterSimulatorToPlugin::Handshake(Handshake {
+        protocol_version: PROTOCOL_VERSION + 1,
+        capabilities: 0,
+    }))
+    .unwrap();
+    match rx.recv().unwrap() {
+        LaterPluginToSimulator::Handshake(handshake) => {
+            assert_eq!(handshake.protocol_version, PROTOCOL_VERSION);
+            assert_eq!(
+                handshake.get_capabilities(),
+                vec![PluginCapability::UnitaryGates]
+            );
+        }
+        x => panic!("unexpected message {:?}", x),
+    }
+
+    // The plugin must not attempt to decode this.
+    let _ = tx.send(LaterSimulatorToPlugin::Initialize {
+        downstream: vec![],
+        seed: [0; 32],
+    });
+
+    let result = plugin.join().unwrap();
+    assert!(result.is_err());
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        format!(
+            "simulator uses protocol version {}, but this plugin uses protocol version {}",
+            PROTOCOL_VERSION + 1,
+            PROTOCOL_VERSION
+        )
+    );
+}
+
 #[test]
 fn simulation_initial_state() {
     let simulation = &mut minimal_simulator().simulation;
@@ -1293,6 +1507,436 @@ fn host_arb_sync_with_gatestream() {
     assert_eq!(gates_executed, 10);
 }
 
+/// Sends the given number of gates through a frontend-operator-backend
+/// pipeline using the given batch policy, measuring the qubit after every
+/// 100 gates. Returns the number of gates per second.
+fn gatestream_throughput(policy: BatchPolicy, num_gates: usize) -> f64 {
+    let (mut frontend, mut operator, mut backend) = fe_op_be();
+
+    frontend.run = Box::new(move |state, _| {
+        let qubit = state.allocate(1, vec![]).unwrap()[0];
+        let gate = Gate::new_unitary(
+            vec![qubit],
+            vec![],
+            vec![
+                Complex64::new(0.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 0.0),
+            ],
+        )
+        .unwrap();
+        for i in 1..=num_gates {
+            state.gate(gate.clone()).unwrap();
+            if i % 100 == 0 {
+                state
+                    .gate(Gate::new_measurement(vec![qubit], Matrix::new_identity(2)).unwrap())
+                    .unwrap();
+                assert_eq!(
+                    state.get_measurement(qubit).unwrap().value,
+                    QubitMeasurementValue::One
+                );
+            }
+        }
+        Ok(ArbData::default())
+    });
+
+    backend.gate = Box::new(|_, gate| {
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|qubit| {
+                QubitMeasurementResult::new(*qubit, QubitMeasurementValue::One, ArbData::default())
+            })
+            .collect())
+    });
+
+    frontend.set_batch_policy(policy);
+    operator.set_batch_policy(policy);
+    backend.set_batch_policy(policy);
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    let start = Instant::now();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+    num_gates as f64 / start.elapsed().as_secs_f64()
+}
+
+#[test]
+#[ignore]
+// Benchmarks the gatestream throughput with and without batching. Run with
+// `cargo test --release -- --ignored gatestream_batching`.
+fn gatestream_batching_throughput() {
+    let unbatched = gatestream_throughput(BatchPolicy::disabled(), 100_000);
+    let batched = gatestream_throughput(BatchPolicy::default(), 100_000);
+    assert!(
+        batched > unbatched,
+        "batched: {:.0} gates/s, unbatched: {:.0} gates/s",
+        batched,
+        unbatched
+    );
+}
+
+#[test]
+// Checks that the results don't depend on the batch policy.
+fn gatestream_batching() {
+    gatestream_throughput(BatchPolicy::disabled(), 1000);
+    gatestream_throughput(
+        BatchPolicy {
+            max_size: 7,
+            max_age: std::time::Duration::from_secs(3600),
+        },
+        1000,
+    );
+    gatestream_throughput(
+        BatchPolicy {
+            max_size: 1000,
+            max_age: std::time::Duration::from_secs(0),
+        },
+        1000,
+    );
+}
+
+#[test]
+// This sends matrices and ArbData arguments large enough to be transferred
+// through shared memory through the pipeline.
+fn large_payloads() {
+    let (mut frontend, operator, mut backend) = fe_op_be();
+    let large = vec![0x5A; SHARED_MEMORY_THRESHOLD * 2];
+
+    let fe_large = large.clone();
+    frontend.run = Box::new(move |state, args| {
+        if args.get_args() != [fe_large.clone()] {
+            return err("frontend received wrong arguments");
+        }
+        let qubits = state.allocate(7, vec![])?;
+        let mut gate = Gate::new_unitary(qubits, vec![], Matrix::new_identity(128))?;
+        gate.data = ArbData::from_args(vec![fe_large.clone()]);
+        state.gate(gate)?;
+        Ok(ArbData::from_args(vec![fe_large.clone()]))
+    });
+
+    let be_large = large.clone();
+    backend.gate = Box::new(move |_, gate| {
+        if gate.get_matrix() != Some(&Matrix::new_identity(128)) {
+            return err("backend received wrong matrix");
+        }
+        if gate.data.get_args() != [be_large.clone()] {
+            return err("backend received wrong arguments");
+        }
+        Ok(vec![])
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator
+        .simulation
+        .start(ArbData::from_args(vec![large.clone()]))
+        .unwrap();
+    let result = simulator.simulation.wait().unwrap();
+    assert_eq!(result.get_args(), [large]);
+}
+
+#[test]
+// This tests whether conditional gates are resolved by the backend without
+// synchronizing the frontend.
+fn conditional_gates() {
+    let (mut frontend, operator, mut backend) = fe_op_be();
+
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(2, vec![]).unwrap();
+        state
+            .gate(Gate::new_measurement(qubits.clone(), Matrix::new_identity(2)).unwrap())
+            .unwrap();
+
+        let x = Gate::new_unitary(
+            vec![qubits[1]],
+            vec![],
+            vec![
+                Complex64::new(0.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 0.0),
+            ],
+        )
+        .unwrap();
+
+        // The backend measures one for the first qubit and zero for the
+        // second, so only the first and third gates are executed.
+        for condition in &[
+            vec![(qubits[0], true)],
+            vec![(qubits[1], true)],
+            vec![(qubits[0], true), (qubits[1], false)],
+            vec![(qubits[0], false)],
+        ] {
+            state
+                .gate(x.with_condition(condition.clone()).unwrap())
+                .unwrap();
+        }
+
+        // Conditional measurements report a new result when their condition
+        // is true, and an undefined result when it is false.
+        let mut measure = Gate::new_measurement(vec![qubits[1]], Matrix::new_identity(2)).unwrap();
+        measure.data = ArbData::from_args(vec![vec![1]]);
+        state
+            .gate(measure.with_condition(vec![(qubits[0], true)]).unwrap())
+            .unwrap();
+        let measurement = state.get_measurement(qubits[1]).unwrap();
+        assert_eq!(measurement.value, QubitMeasurementValue::Zero);
+        assert_eq!(measurement.data.get_args(), &[vec![1]]);
+
+        let measure = Gate::new_measurement(vec![qubits[0]], Matrix::new_identity(2)).unwrap();
+        state
+            .gate(measure.with_condition(vec![(qubits[1], true)]).unwrap())
+            .unwrap();
+        assert_eq!(
+            state.get_measurement(qubits[0]).unwrap().value,
+            QubitMeasurementValue::Undefined
+        );
+
+        Ok(ArbData::default())
+    });
+
+    let gates_executed = Arc::new(Mutex::new(Box::new(0u8)));
+
+    let ge_gate = Arc::clone(&gates_executed);
+    backend.gate = Box::new(move |_, gate| {
+        assert!(!gate.is_conditional());
+        if gate.get_measures().is_empty() {
+            let ge: &mut u8 = &mut ge_gate.lock().unwrap();
+            *ge += 1;
+        }
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|&qubit| {
+                QubitMeasurementResult::new(
+                    qubit,
+                    QubitMeasurementValue::from(qubit == QubitRef::from_foreign(1).unwrap()),
+                    gate.data.clone(),
+                )
+            })
+            .collect())
+    });
+
+    let ge_arb = Arc::clone(&gates_executed);
+    backend.host_arb = Box::new(move |_, _| {
+        let ge: &u8 = &ge_arb.lock().unwrap();
+        Ok(ArbData::from_args(vec![vec![*ge]]))
+    });
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+
+    let gates_executed = simulator
+        .simulation
+        .arb_idx(2, ArbCmd::new("a", "b", ArbData::default()))
+        .unwrap()
+        .get_args()[0][0];
+    assert_eq!(gates_executed, 2);
+}
+
+#[test]
+// This tests whether operators that modify measurement results can resolve
+// conditional gates using the modified results.
+fn conditional_gates_modified_measurements() {
+    let (mut frontend, mut operator, mut backend) = fe_op_be();
+
+    frontend.run = Box::new(|state, _| {
+        let qubits = state.allocate(2, vec![]).unwrap();
+        state
+            .gate(Gate::new_measurement(qubits.clone(), Matrix::new_identity(2)).unwrap())
+            .unwrap();
+
+        // The backend measures one for the first qubit and zero for the
+        // second, but the operator inverts the results, so only the second
+        // and fourth gates are executed.
+        for (index, condition) in [
+            vec![(qubits[0], true)],
+            vec![(qubits[1], true)],
+            vec![(qubits[0], true), (qubits[1], false)],
+            vec![(qubits[0], false)],
+        ]
+        .iter()
+        .enumerate()
+        {
+            let gate = Gate::new_custom(
+                "a",
+                vec![qubits[1]],
+                vec![],
+                vec![],
+                None::<Matrix>,
+                ArbData::from_args(vec![vec![index as u8]]),
+            )
+            .unwrap()
+            .with_condition(condition.clone())
+            .unwrap();
+            state.gate(gate).unwrap();
+        }
+
+        Ok(ArbData::default())
+    });
+
+    operator.gate = Box::new(|state, gate| {
+        if state.resolve_condition(&gate)? {
+            state.gate(gate.without_condition())?;
+        }
+        Ok(vec![])
+    });
+
+    operator.modify_measurement = Box::new(|_, mut measurement| {
+        measurement.value = match measurement.value {
+            QubitMeasurementValue::Zero => QubitMeasurementValue::One,
+            QubitMeasurementValue::One => QubitMeasurementValue::Zero,
+            QubitMeasurementValue::Undefined => QubitMeasurementValue::Undefined,
+        };
+        Ok(vec![measurement])
+    });
+
+    let gates_executed = Arc::new(Mutex::new(vec![]));
+
+    let ge_gate = Arc::clone(&gates_executed);
+    backend.gate = Box::new(move |_, gate| {
+        assert!(!gate.is_conditional());
+        if gate.get_measures().is_empty() {
+            ge_gate.lock().unwrap().push(gate.data.get_args()[0][0]);
+        }
+        Ok(gate
+            .get_measures()
+            .iter()
+            .map(|&qubit| {
+                QubitMeasurementResult::new(
+                    qubit,
+                    QubitMeasurementValue::from(qubit == QubitRef::from_foreign(1).unwrap()),
+                    ArbData::default(),
+                )
+            })
+            .collect())
+    });
+
+    let ge_arb = Arc::clone(&gates_executed);
+    backend.host_arb =
+        Box::new(move |_, _| Ok(ArbData::from_args(vec![ge_arb.lock().unwrap().clone()])));
+
+    let ptc = |definition| {
+        PluginThreadConfiguration::new(
+            definition,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        )
+    };
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(ptc(frontend))
+        .with_plugin(ptc(operator))
+        .with_plugin(ptc(backend));
+
+    let mut simulator = Simulator::new(configuration).unwrap();
+    simulator.simulation.start(ArbData::default()).unwrap();
+    simulator.simulation.wait().unwrap();
+
+    let gates_executed = simulator
+        .simulation
+        .arb_idx(2, ArbCmd::new("a", "b", ArbData::default()))
+        .unwrap()
+        .get_args()[0]
+        .clone();
+    assert_eq!(gates_executed, vec![1, 3]);
+}
+
+#[test]
+fn conditional_gate_non_alloc() {
+    let mut frontend = PluginDefinition::new(
+        PluginType::Frontend,
+        PluginMetadata::new("frontend", "dqcsim", "0.1.0"),
+    );
+
+    frontend.initialize = Box::new(|state, _| {
+        let q = state.allocate(1, vec![]).expect("alloc fail");
+        let gate = Gate::new_unitary(
+            q,
+            vec![],
+            vec![
+                Complex64::new(0.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(1.0, 0.0),
+                Complex64::new(0.0, 0.0),
+            ],
+        )
+        .unwrap()
+        .with_condition(vec![(QubitRef::from_foreign(2).unwrap(), true)])
+        .unwrap();
+        let m = state.gate(gate);
+        assert!(m.is_err());
+        assert_eq!(
+            m.unwrap_err().to_string(),
+            "Invalid argument: qubit 2 is not allocated"
+        );
+        Ok(())
+    });
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("front", LoglevelFilter::Trace),
+        ))
+        .with_plugin(PluginThreadConfiguration::new(
+            PluginDefinition::new(
+                PluginType::Backend,
+                PluginMetadata::new("backend", "dqcsim", "0.1.0"),
+            ),
+            PluginLogConfiguration::new("backend", LoglevelFilter::Trace),
+        ));
+
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_ok());
+}
+
 #[test]
 fn plugin_thread_panic() {
     let (mut frontend, _, backend) = fe_op_be();
@@ -1324,3 +1968,121 @@ fn plugin_thread_panic() {
     let wait = simulator.simulation.wait();
     assert!(wait.is_err());
 }
+
+#[cfg(unix)]
+#[test]
+fn attached_plugin() {
+    let (mut frontend, _, backend) = fe_op_be();
+
+    frontend.run = Box::new(|_, _| Ok(ArbData::default()));
+
+    let path = std::env::temp_dir().join(format!("dqcsim-attach-test-{}.sock", std::process::id()));
+    let address = format!("unix://{}", path.display());
+
+    // Start the backend "externally" once DQCsim is listening for it.
+    let backend_address = address.clone();
+    let backend = std::thread::spawn(move || {
+        while !path.exists() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        PluginState::run(&backend, backend_address).unwrap();
+    });
+
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(PluginThreadConfiguration::new(
+            frontend,
+            PluginLogConfiguration::new("", LoglevelFilter::Off),
+        ))
+        .with_plugin(
+            PluginAttachConfiguration::new("backend", address, PluginType::Backend)
+                .with_accept_timeout(Timeout::from_seconds(5)),
+        );
+
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_ok());
+    let mut simulator = simulator.unwrap();
+
+    let start = simulator.simulation.start(ArbData::default());
+    assert!(start.is_ok());
+    let wait = simulator.simulation.wait();
+    assert!(wait.is_ok());
+
+    drop(simulator);
+    assert!(backend.join().is_ok());
+}
+
+#[test]
+fn attached_plugin_timeout() {
+    let configuration = SimulatorConfiguration::default()
+        .without_reproduction()
+        .without_logging()
+        .with_plugin(thread_config_type(PluginType::Frontend))
+        .with_plugin(
+            PluginAttachConfiguration::new("backend", "tcp://127.0.0.1:0", PluginType::Backend)
+                .with_accept_timeout(Timeout::from_millis(100)),
+        );
+
+    let simulator = Simulator::new(configuration);
+    assert!(simulator.is_err());
+    assert_eq!(
+        simulator.unwrap_err().to_string(),
+        "Failed to spawn plugin(s)"
+    );
+}
+
+#[test]
+fn attached_plugin_reproduction() {
+    let configuration = SimulatorConfiguration::default()
+        .with_reproduction_path_style(ReproductionPathStyle::Keep)
+        .with_plugin(
+            PluginAttachConfiguration::new("front", "tcp://127.0.0.1:4000", PluginType::Frontend)
+                .with_init_cmd(ArbCmd::new("a", "b", ArbData::default())),
+        )
+        .with_plugin(PluginAttachConfiguration::new(
+            "back",
+            "unix:///tmp/back.sock",
+            PluginType::Backend,
+        ));
+
+    // The attached plugins should survive a round trip through a
+    // reproduction file.
+    let reproduction = Reproduction::new_logger(&configuration).unwrap();
+    let path =
+        std::env::temp_dir().join(format!("dqcsim-attach-test-{}.repro", std::process::id()));
+    reproduction.to_file(&path).unwrap();
+    let reproduced = Reproduction::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(reproduced, reproduction);
+
+    let mut reproduced_configuration = SimulatorConfiguration::default();
+    reproduced
+        .to_run(
+            &mut reproduced_configuration,
+            vec![PluginModification {
+                name: "back".to_string(),
+                verbosity: None,
+                tee_files: vec![],
+                stdout_mode: None,
+                stderr_mode: None,
+                accept_timeout: Some(Timeout::from_seconds(1)),
+                shutdown_timeout: None,
+            }],
+            true,
+        )
+        .unwrap();
+
+    assert_eq!(
+        format!("{:?}", reproduced_configuration.plugins[0]),
+        format!("{:?}", configuration.plugins[0])
+    );
+    assert_eq!(
+        format!("{:?}", reproduced_configuration.plugins[1]),
+        format!(
+            "{:?}",
+            PluginAttachConfiguration::new("back", "unix:///tmp/back.sock", PluginType::Backend)
+                .with_accept_timeout(Timeout::from_seconds(1))
+        )
+    );
+}
@@ -69,9 +69,9 @@ class build(_build):
                     'HOME', 'root') + '/.cargo/bin/cargo')
             finally:
                 if debug:
-                    cargo["build"]["--features"]["bindings cli null-plugins"] & FG
+                    cargo["build"]["--features"]["bindings cli null-plugins sv-plugins dm-plugins chp-plugins pauli-plugins readout-plugins decompose-plugins map-plugins record-plugins replay-plugins qasm-plugins cqasm-plugins stats-plugins decoherence-plugins"] & FG
                 else:
-                    cargo["build"]["--release"]["--features"]["bindings cli null-plugins"] & FG
+                    cargo["build"]["--release"]["--features"]["bindings cli null-plugins sv-plugins dm-plugins chp-plugins pauli-plugins readout-plugins decompose-plugins map-plugins record-plugins replay-plugins qasm-plugins cqasm-plugins stats-plugins decoherence-plugins"] & FG
 
         local['mkdir']("-p", py_target_dir)
         sys.path.append("python/tools")
@@ -196,6 +196,19 @@ setup(
             output_dir + '/dqcsfenull',
             output_dir + '/dqcsopnull',
             output_dir + '/dqcsbenull',
+            output_dir + '/dqcsbesv',
+            output_dir + '/dqcsbedm',
+            output_dir + '/dqcsbechp',
+            output_dir + '/dqcsoppauli',
+            output_dir + '/dqcsopreadout',
+            output_dir + '/dqcsopdecompose',
+            output_dir + '/dqcsopmap',
+            output_dir + '/dqcsoprecord',
+            output_dir + '/dqcsfetrace',
+            output_dir + '/dqcsfeqasm',
+            output_dir + '/dqcsfecq',
+            output_dir + '/dqcsopstats',
+            output_dir + '/dqcsopdecoherence',
             py_bin_dir + '/dqcsfepy',
             py_bin_dir + '/dqcsoppy',
             py_bin_dir + '/dqcsbepy',